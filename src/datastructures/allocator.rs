@@ -0,0 +1,304 @@
+use std::{
+    alloc::{self, Layout},
+    error::Error,
+    fmt,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The error returned when an [`Allocator`] can't satisfy a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "memory allocation failed")
+    }
+}
+
+impl Error for AllocError {}
+
+/// A source of memory for the containers of this crate, like the unstable `std::alloc::Allocator`.
+///
+/// # Safety
+///
+/// Memory returned by an allocator must be valid for its layout until it is passed back to
+/// `deallocate`, `grow` or `shrink` of the same allocator, or of a clone of it.
+pub unsafe trait Allocator {
+    /// Allocates memory fitting `layout`, zero sized layouts may get a dangling pointer.
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError>;
+
+    /// Frees memory returned by this allocator.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated by this allocator with `layout`.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+
+    /// Moves a block into a larger one, keeping its contents. On failure the old block is untouched.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated by this allocator with `old_layout`, and `new_layout`
+    /// must not be smaller.
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = self.allocate(new_layout)?;
+        ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), old_layout.size());
+        self.deallocate(ptr, old_layout);
+        Ok(new_ptr)
+    }
+
+    /// Moves a block into a smaller one, keeping the contents that fit.
+    /// On failure the old block is untouched.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated by this allocator with `old_layout`, and `new_layout`
+    /// must not be larger.
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = self.allocate(new_layout)?;
+        ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), new_layout.size());
+        self.deallocate(ptr, old_layout);
+        Ok(new_ptr)
+    }
+}
+
+unsafe impl<A: Allocator + ?Sized> Allocator for &A {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        (**self).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        (**self).deallocate(ptr, layout)
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        (**self).grow(ptr, old_layout, new_layout)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        (**self).shrink(ptr, old_layout, new_layout)
+    }
+}
+
+/// The global allocator registered with `#[global_allocator]`, the default for every container.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Global;
+
+/// A well aligned, non null pointer for zero sized allocations.
+fn dangling(layout: Layout) -> NonNull<u8> {
+    NonNull::new(ptr::without_provenance_mut(layout.align())).unwrap()
+}
+
+unsafe impl Allocator for Global {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        if layout.size() == 0 {
+            return Ok(dangling(layout));
+        }
+        NonNull::new(unsafe { alloc::alloc(layout) }).ok_or(AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            alloc::dealloc(ptr.as_ptr(), layout)
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if old_layout.size() == 0 {
+            return self.allocate(new_layout);
+        }
+        if old_layout.align() != new_layout.align() {
+            let new_ptr = self.allocate(new_layout)?;
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), old_layout.size());
+            self.deallocate(ptr, old_layout);
+            return Ok(new_ptr);
+        }
+        NonNull::new(alloc::realloc(ptr.as_ptr(), old_layout, new_layout.size())).ok_or(AllocError)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        if new_layout.size() == 0 {
+            self.deallocate(ptr, old_layout);
+            return Ok(dangling(new_layout));
+        }
+        if old_layout.align() != new_layout.align() {
+            let new_ptr = self.allocate(new_layout)?;
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), new_layout.size());
+            self.deallocate(ptr, old_layout);
+            return Ok(new_ptr);
+        }
+        NonNull::new(alloc::realloc(ptr.as_ptr(), old_layout, new_layout.size())).ok_or(AllocError)
+    }
+}
+
+/// An allocator that keeps track of how much memory is handed out by `inner`.
+///
+/// Containers take it by reference, e.g. `Vector::new_in(&counter)`, so a test can check
+/// [`live_bytes`](CountingAllocator::live_bytes) after they are dropped to find leaks.
+#[derive(Debug, Default)]
+pub struct CountingAllocator<A: Allocator = Global> {
+    inner: A,
+    live: AtomicUsize,
+    peak: AtomicUsize,
+    allocations: AtomicUsize,
+}
+
+impl CountingAllocator {
+    pub fn new() -> Self {
+        Self::with_inner(Global)
+    }
+}
+
+impl<A: Allocator> CountingAllocator<A> {
+    /// Counts the memory allocated through `inner`.
+    pub fn with_inner(inner: A) -> Self {
+        Self {
+            inner,
+            live: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            allocations: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of bytes currently allocated.
+    pub fn live_bytes(&self) -> usize {
+        self.live.load(Ordering::Relaxed)
+    }
+
+    /// The highest number of bytes that were allocated at the same time.
+    pub fn peak_bytes(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    /// The number of successful calls to `allocate`, `grow` and `shrink`.
+    pub fn allocations(&self) -> usize {
+        self.allocations.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, freed: usize, allocated: usize) {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        let live = self.live.fetch_add(allocated, Ordering::Relaxed) + allocated - freed;
+        self.live.fetch_sub(freed, Ordering::Relaxed);
+        self.peak.fetch_max(live, Ordering::Relaxed);
+    }
+}
+
+unsafe impl<A: Allocator> Allocator for CountingAllocator<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        let ptr = self.inner.allocate(layout)?;
+        self.record(0, layout.size());
+        Ok(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.inner.deallocate(ptr, layout);
+        self.live.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = self.inner.grow(ptr, old_layout, new_layout)?;
+        self.record(old_layout.size(), new_layout.size());
+        Ok(new_ptr)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let new_ptr = self.inner.shrink(ptr, old_layout, new_layout)?;
+        self.record(old_layout.size(), new_layout.size());
+        Ok(new_ptr)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn global_round_trip() {
+        let layout = Layout::array::<u32>(4).unwrap();
+        unsafe {
+            let ptr = Global.allocate(layout).unwrap();
+            ptr.cast::<u32>().write(7);
+
+            let bigger = Layout::array::<u32>(16).unwrap();
+            let ptr = Global.grow(ptr, layout, bigger).unwrap();
+            assert_eq!(ptr.cast::<u32>().read(), 7);
+
+            let smaller = Layout::array::<u32>(1).unwrap();
+            let ptr = Global.shrink(ptr, bigger, smaller).unwrap();
+            assert_eq!(ptr.cast::<u32>().read(), 7);
+            Global.deallocate(ptr, smaller);
+        }
+    }
+
+    #[test]
+    fn zero_sized() {
+        let layout = Layout::new::<[u64; 0]>();
+        let ptr = Global.allocate(layout).unwrap();
+        assert_eq!(ptr.as_ptr() as usize % layout.align(), 0);
+        unsafe { Global.deallocate(ptr, layout) };
+    }
+
+    #[test]
+    fn counting() {
+        let counter = CountingAllocator::new();
+        let small = Layout::array::<u8>(100).unwrap();
+        let large = Layout::array::<u8>(300).unwrap();
+
+        unsafe {
+            let a = counter.allocate(small).unwrap();
+            let b = counter.allocate(small).unwrap();
+            assert_eq!(counter.live_bytes(), 200);
+
+            let b = counter.grow(b, small, large).unwrap();
+            assert_eq!(counter.live_bytes(), 400);
+
+            counter.deallocate(a, small);
+            counter.deallocate(b, large);
+        }
+
+        assert_eq!(counter.live_bytes(), 0);
+        assert_eq!(counter.peak_bytes(), 400);
+        assert_eq!(counter.allocations(), 3);
+    }
+}
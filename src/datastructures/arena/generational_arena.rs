@@ -0,0 +1,435 @@
+use std::{fmt, iter::FusedIterator, mem, ops, slice};
+
+use crate::datastructures::Vector;
+
+/// Refers to a value inserted into a [`GenerationalArena`].
+///
+/// Slots are reused after their value is removed, the generation tells a stale index apart
+/// from the value that took its place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Index {
+    slot: usize,
+    generation: u32,
+}
+
+impl Index {
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+#[derive(Clone)]
+enum Entry<T> {
+    Occupied {
+        generation: u32,
+        value: T,
+    },
+    /// `generation` is the one the next value in this slot gets
+    Free {
+        generation: u32,
+        next_free: Option<usize>,
+    },
+}
+
+/// Stores values under [`Index`]es that never refer to another value once theirs is removed.
+///
+/// Like a [`Slab`](super::Slab), insert and remove are O(1) and reuse freed slots, but every
+/// reuse bumps the slot's generation, so looking up a removed value returns `None`.
+#[derive(Clone)]
+pub struct GenerationalArena<T> {
+    entries: Vector<Entry<T>>,
+    next_free: Option<usize>,
+    len: usize,
+}
+
+impl<T> GenerationalArena<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vector::new(),
+            next_free: None,
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vector::with_capacity(capacity),
+            next_free: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    pub fn insert(&mut self, value: T) -> Index {
+        self.insert_with(|_| value)
+    }
+
+    /// Inserts the value `f` creates from its own index, handy for self-referencing values.
+    pub fn insert_with<F>(&mut self, f: F) -> Index
+    where
+        F: FnOnce(Index) -> T,
+    {
+        // Only claim the slot once `f` returned, a panic must not take it off the free list
+        let (index, next_free) = match self.next_free {
+            Some(slot) => {
+                let Entry::Free {
+                    generation,
+                    next_free,
+                } = self.entries[slot]
+                else {
+                    unreachable!("free list points to an occupied entry");
+                };
+                (Index { slot, generation }, next_free)
+            }
+            None => (
+                Index {
+                    slot: self.entries.len(),
+                    generation: 0,
+                },
+                None,
+            ),
+        };
+
+        let entry = Entry::Occupied {
+            generation: index.generation,
+            value: f(index),
+        };
+        if index.slot == self.entries.len() {
+            self.entries.push(entry);
+        } else {
+            self.entries[index.slot] = entry;
+            self.next_free = next_free;
+        }
+        self.len += 1;
+        index
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        match self.entries.get(index.slot) {
+            Some(Entry::Occupied { generation, value }) if *generation == index.generation => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        match self.entries.get_mut(index.slot) {
+            Some(Entry::Occupied { generation, value }) if *generation == index.generation => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Removes the value of `index`, if it is still in the arena.
+    pub fn remove(&mut self, index: Index) -> Option<T> {
+        self.get(index)?;
+
+        let free = Entry::Free {
+            generation: index.generation.wrapping_add(1),
+            next_free: self.next_free,
+        };
+        let Entry::Occupied { value, .. } = mem::replace(&mut self.entries[index.slot], free)
+        else {
+            unreachable!();
+        };
+        self.next_free = Some(index.slot);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Keeps only the values `f` returns `true` for, the others' indices go stale.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Index, &mut T) -> bool,
+    {
+        for slot in 0..self.entries.len() {
+            if let Entry::Occupied { generation, value } = &mut self.entries[slot] {
+                let index = Index {
+                    slot,
+                    generation: *generation,
+                };
+                if !f(index, value) {
+                    self.remove(index);
+                }
+            }
+        }
+    }
+
+    /// Removes every value, keeping the slots and their generations so no old index comes
+    /// back to life.
+    pub fn clear(&mut self) {
+        for slot in 0..self.entries.len() {
+            if let Entry::Occupied { generation, .. } = self.entries[slot] {
+                self.remove(Index { slot, generation });
+            }
+        }
+    }
+
+    /// Iterates over the values with their indices, in slot order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            entries: self.entries.iter().enumerate(),
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            entries: self.entries.iter_mut().enumerate(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Default for GenerationalArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GenerationalArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> ops::Index<Index> for GenerationalArena<T> {
+    type Output = T;
+
+    fn index(&self, index: Index) -> &Self::Output {
+        self.get(index).expect("stale or invalid arena index")
+    }
+}
+
+impl<T> ops::IndexMut<Index> for GenerationalArena<T> {
+    fn index_mut(&mut self, index: Index) -> &mut Self::Output {
+        self.get_mut(index).expect("stale or invalid arena index")
+    }
+}
+
+/// An iterator over the values of a [`GenerationalArena`] and their indices
+#[derive(Clone)]
+pub struct Iter<'a, T> {
+    entries: std::iter::Enumerate<slice::Iter<'a, Entry<T>>>,
+    /// The values not yielded yet
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Index, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.entries.find_map(|(slot, entry)| match entry {
+            Entry::Occupied { generation, value } => Some((
+                Index {
+                    slot,
+                    generation: *generation,
+                },
+                value,
+            )),
+            Entry::Free { .. } => None,
+        })?;
+        self.len -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over mutable references to the values of a [`GenerationalArena`] and their
+/// indices
+pub struct IterMut<'a, T> {
+    entries: std::iter::Enumerate<slice::IterMut<'a, Entry<T>>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (Index, &'a mut T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.entries.find_map(|(slot, entry)| match entry {
+            Entry::Occupied { generation, value } => Some((
+                Index {
+                    slot,
+                    generation: *generation,
+                },
+                value,
+            )),
+            Entry::Free { .. } => None,
+        })?;
+        self.len -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a GenerationalArena<T> {
+    type Item = (Index, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut GenerationalArena<T> {
+    type Item = (Index, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut arena = GenerationalArena::new();
+        let a = arena.insert("A Game of Thrones");
+        let b = arena.insert("A Clash of Kings");
+
+        assert_eq!(arena[a], "A Game of Thrones");
+        assert_eq!(arena.remove(a), Some("A Game of Thrones"));
+        assert_eq!(arena.remove(a), None);
+        assert_eq!(arena.len(), 1);
+
+        arena[b] = "A Storm of Swords";
+        assert_eq!(arena.get(b), Some(&"A Storm of Swords"));
+    }
+
+    #[test]
+    fn panicking_insert_with_keeps_the_slot() {
+        fn panicking_insert(arena: &mut GenerationalArena<&str>) {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                arena.insert_with(|_| panic!("no value"))
+            }));
+            assert!(result.is_err());
+            assert!(arena.is_empty());
+        }
+
+        // A new slot is not pushed
+        let mut arena = GenerationalArena::new();
+        panicking_insert(&mut arena);
+        let first = arena.insert("The Hedge Knight");
+        assert_eq!(first.slot(), 0);
+
+        // A freed slot stays on the free list
+        arena.remove(first);
+        panicking_insert(&mut arena);
+        let reused = arena.insert("The Sworn Sword");
+        assert_eq!(reused.slot(), 0);
+        assert_eq!(reused.generation(), first.generation() + 1);
+        assert_eq!(arena.insert("The Mystery Knight").slot(), 1);
+    }
+
+    #[test]
+    fn detects_stale_indices() {
+        let mut arena = GenerationalArena::new();
+        let old = arena.insert("A Feast for Crows");
+        arena.remove(old);
+
+        // Reuses the slot of `old`
+        let new = arena.insert("A Dance with Dragons");
+        assert_eq!(new.slot(), old.slot());
+        assert_eq!(new.generation(), old.generation() + 1);
+        assert_eq!(arena.get(old), None);
+        assert!(arena.get_mut(old).is_none());
+        assert_eq!(arena.remove(old), None);
+        assert_eq!(arena[new], "A Dance with Dragons");
+    }
+
+    #[test]
+    #[should_panic(expected = "stale or invalid arena index")]
+    fn index_stale() {
+        let mut arena = GenerationalArena::new();
+        let index = arena.insert(1);
+        arena.clear();
+        arena.insert(2);
+        let _ = arena[index];
+    }
+
+    #[test]
+    fn insert_with_own_index() {
+        let mut arena = GenerationalArena::new();
+        let index = arena.insert_with(|index| (index, "The Winds of Winter"));
+        assert_eq!(arena[index].0, index);
+    }
+
+    #[test]
+    fn iterate_and_retain() {
+        let mut arena = GenerationalArena::new();
+        let indices: Vec<Index> = (0..10).map(|n| arena.insert(n)).collect();
+        arena.retain(|_, n| *n % 2 == 0);
+
+        assert_eq!(arena.iter().len(), 5);
+        assert!(arena.iter().map(|(_, n)| *n).eq([0, 2, 4, 6, 8]));
+        assert!(arena.contains(indices[4]));
+        assert!(!arena.contains(indices[5]));
+
+        for (_, n) in &mut arena {
+            *n += 1;
+        }
+        let (index, n) = arena.iter().nth(1).unwrap();
+        assert_eq!((index, *n), (indices[2], 3));
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = StdRng::seed_from_u64(41);
+        let mut arena = GenerationalArena::new();
+        let mut live: Vec<(Index, u32)> = Vec::new();
+        let mut dead: Vec<Index> = Vec::new();
+
+        for _ in 0..2000 {
+            if live.is_empty() || rng.gen_bool(0.6) {
+                let n = rng.gen();
+                live.push((arena.insert(n), n));
+            } else {
+                let (index, n) = live.swap_remove(rng.gen_range(0..live.len()));
+                assert_eq!(arena.remove(index), Some(n));
+                dead.push(index);
+            }
+        }
+
+        assert_eq!(arena.len(), live.len());
+        assert!(live.iter().all(|(index, n)| arena.get(*index) == Some(n)));
+        assert!(dead.iter().all(|index| !arena.contains(*index)));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod generational_arena;
+pub mod slab;
+
+pub use generational_arena::{GenerationalArena, Index};
+pub use slab::Slab;
@@ -0,0 +1,319 @@
+use std::{fmt, iter::FusedIterator, mem, ops, slice};
+
+use crate::datastructures::Vector;
+
+#[derive(Clone)]
+enum Entry<T> {
+    Occupied(T),
+    /// Links to the next vacant entry, forming the free list
+    Vacant(Option<usize>),
+}
+
+/// Stores values under `usize` keys that stay valid until the value is removed.
+///
+/// Removed entries are put on a free list and reused by later inserts, so both are O(1).
+/// A key can't tell a reused entry apart from the value it used to refer to, use a
+/// [`GenerationalArena`](super::GenerationalArena) when that matters.
+#[derive(Clone)]
+pub struct Slab<T> {
+    entries: Vector<Entry<T>>,
+    /// The most recently freed entry
+    next_free: Option<usize>,
+    len: usize,
+}
+
+impl<T> Slab<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vector::new(),
+            next_free: None,
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vector::with_capacity(capacity),
+            next_free: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// Returns the key the next [`insert`](Slab::insert) will use.
+    pub fn vacant_key(&self) -> usize {
+        self.next_free.unwrap_or(self.entries.len())
+    }
+
+    pub fn insert(&mut self, value: T) -> usize {
+        let key = self.vacant_key();
+        match self.next_free {
+            Some(free) => {
+                let Entry::Vacant(next) =
+                    mem::replace(&mut self.entries[free], Entry::Occupied(value))
+                else {
+                    unreachable!("free list points to an occupied entry");
+                };
+                self.next_free = next;
+            }
+            None => self.entries.push(Entry::Occupied(value)),
+        }
+        self.len += 1;
+        key
+    }
+
+    pub fn get(&self, key: usize) -> Option<&T> {
+        match self.entries.get(key) {
+            Some(Entry::Occupied(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        match self.entries.get_mut(key) {
+            Some(Entry::Occupied(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes the value under `key`, if there is one, and frees the key for reuse.
+    pub fn try_remove(&mut self, key: usize) -> Option<T> {
+        let entry = self.entries.get_mut(key)?;
+        if let Entry::Vacant(_) = entry {
+            return None;
+        }
+
+        let Entry::Occupied(value) = mem::replace(entry, Entry::Vacant(self.next_free)) else {
+            unreachable!();
+        };
+        self.next_free = Some(key);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if there is no value under `key`.
+    pub fn remove(&mut self, key: usize) -> T {
+        self.try_remove(key).expect("invalid slab key")
+    }
+
+    /// Keeps only the values `f` returns `true` for, their keys don't change.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        for key in 0..self.entries.len() {
+            if let Entry::Occupied(value) = &mut self.entries[key] {
+                if !f(key, value) {
+                    self.try_remove(key);
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.next_free = None;
+        self.len = 0;
+    }
+
+    /// Iterates over the values with their keys, in key order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            entries: self.entries.iter().enumerate(),
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            entries: self.entries.iter_mut().enumerate(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Default for Slab<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Slab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> ops::Index<usize> for Slab<T> {
+    type Output = T;
+
+    fn index(&self, key: usize) -> &Self::Output {
+        self.get(key).expect("invalid slab key")
+    }
+}
+
+impl<T> ops::IndexMut<usize> for Slab<T> {
+    fn index_mut(&mut self, key: usize) -> &mut Self::Output {
+        self.get_mut(key).expect("invalid slab key")
+    }
+}
+
+/// An iterator over the values of a [`Slab`] and their keys
+#[derive(Clone)]
+pub struct Iter<'s, T> {
+    entries: std::iter::Enumerate<slice::Iter<'s, Entry<T>>>,
+    /// The values not yielded yet
+    len: usize,
+}
+
+impl<'s, T> Iterator for Iter<'s, T> {
+    type Item = (usize, &'s T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.entries.find_map(|(key, entry)| match entry {
+            Entry::Occupied(value) => Some((key, value)),
+            Entry::Vacant(_) => None,
+        })?;
+        self.len -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over mutable references to the values of a [`Slab`] and their keys
+pub struct IterMut<'s, T> {
+    entries: std::iter::Enumerate<slice::IterMut<'s, Entry<T>>>,
+    len: usize,
+}
+
+impl<'s, T> Iterator for IterMut<'s, T> {
+    type Item = (usize, &'s mut T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.entries.find_map(|(key, entry)| match entry {
+            Entry::Occupied(value) => Some((key, value)),
+            Entry::Vacant(_) => None,
+        })?;
+        self.len -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<'s, T> IntoIterator for &'s Slab<T> {
+    type Item = (usize, &'s T);
+    type IntoIter = Iter<'s, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'s, T> IntoIterator for &'s mut Slab<T> {
+    type Item = (usize, &'s mut T);
+    type IntoIter = IterMut<'s, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert_remove() {
+        let mut slab = Slab::new();
+        let a = slab.insert("A Game of Thrones");
+        let b = slab.insert("A Clash of Kings");
+        let c = slab.insert("A Storm of Swords");
+        assert_eq!((a, b, c), (0, 1, 2));
+
+        assert_eq!(slab.remove(b), "A Clash of Kings");
+        assert_eq!(slab.try_remove(b), None);
+        assert!(!slab.contains(b));
+        assert_eq!(slab.len(), 2);
+        assert_eq!(slab[c], "A Storm of Swords");
+
+        slab[a] = "A Feast for Crows";
+        assert_eq!(slab.get(a), Some(&"A Feast for Crows"));
+        assert_eq!(slab.get(10), None);
+    }
+
+    #[test]
+    fn reuses_keys() {
+        let mut slab: Slab<u32> = Slab::new();
+        (0..5).for_each(|n| {
+            slab.insert(n);
+        });
+        slab.remove(1);
+        slab.remove(3);
+
+        // Last freed, first reused
+        assert_eq!(slab.vacant_key(), 3);
+        assert_eq!(slab.insert(30), 3);
+        assert_eq!(slab.insert(10), 1);
+        assert_eq!(slab.insert(5), 5);
+        assert_eq!(slab.capacity(), 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid slab key")]
+    fn remove_vacant() {
+        let mut slab = Slab::new();
+        let key = slab.insert(());
+        slab.remove(key);
+        slab.remove(key);
+    }
+
+    #[test]
+    fn iterate_and_retain() {
+        let mut slab: Slab<u32> = (0..10).fold(Slab::new(), |mut slab, n| {
+            slab.insert(n);
+            slab
+        });
+        slab.retain(|key, n| {
+            *n *= 10;
+            key % 3 == 0
+        });
+
+        assert_eq!(slab.iter().len(), 4);
+        assert!(slab.iter().eq([(0, &0), (3, &30), (6, &60), (9, &90)]));
+        for (_, n) in &mut slab {
+            *n += 1;
+        }
+        assert_eq!(format!("{slab:?}"), "{0: 1, 3: 31, 6: 61, 9: 91}");
+
+        slab.clear();
+        assert!(slab.is_empty());
+        assert_eq!(slab.insert(7), 0);
+    }
+}
@@ -0,0 +1,691 @@
+use std::{
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ops::{Deref, DerefMut, RangeBounds},
+    ptr, slice,
+};
+
+use crate::datastructures::vector::{
+    len_slice::{LenSlice, RawDrain},
+    vector::slice_range,
+};
+
+/// The error returned when an [`ArrayVec`] is full, it hands back the element that didn't fit.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T = ()> {
+    element: T,
+}
+
+impl<T> CapacityError<T> {
+    pub fn new(element: T) -> Self {
+        Self { element }
+    }
+
+    pub fn element(self) -> T {
+        self.element
+    }
+}
+
+impl<T> fmt::Debug for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CapacityError: {self}")
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "insufficient capacity")
+    }
+}
+
+impl<T> Error for CapacityError<T> {}
+
+/// A vector with a fixed capacity of `N` elements, stored inline.
+///
+/// It never allocates: the `try_` methods return a [`CapacityError`] when the vector is full,
+/// the others panic.
+pub struct ArrayVec<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    pub const CAPACITY: usize = N;
+
+    pub const fn new() -> Self {
+        Self {
+            data: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// How many more elements fit.
+    pub fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.data.as_ptr().cast()
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.data.as_mut_ptr().cast()
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must not exceed `N` and the first `new_len` elements must be initialized.
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        self.len = new_len;
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+
+    /// Checks that `additional` more elements fit, the capacity of an `ArrayVec` never changes.
+    pub fn try_reserve(&self, additional: usize) -> Result<(), CapacityError> {
+        if additional > self.remaining_capacity() {
+            return Err(CapacityError::new(()));
+        }
+        Ok(())
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the vector is full.
+    pub fn push(&mut self, elem: T) {
+        if self.try_push(elem).is_err() {
+            panic!("ArrayVec is full");
+        }
+    }
+
+    /// Like [`push`](ArrayVec::push), but hands `elem` back if the vector is full.
+    pub fn try_push(&mut self, elem: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError::new(elem));
+        }
+
+        self.raw().push(elem);
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.raw().pop()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.last()
+    }
+
+    /// Inserts `elem` at `index`, shifting everything after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or the vector is full.
+    pub fn insert(&mut self, index: usize, elem: T) {
+        if self.try_insert(index, elem).is_err() {
+            panic!("ArrayVec is full");
+        }
+    }
+
+    /// Like [`insert`](ArrayVec::insert), but hands `elem` back if the vector is full.
+    pub fn try_insert(&mut self, index: usize, elem: T) -> Result<(), CapacityError<T>> {
+        let len = self.len;
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+        if self.is_full() {
+            return Err(CapacityError::new(elem));
+        }
+
+        self.raw().insert(index, elem);
+        Ok(())
+    }
+
+    /// Removes the element at `index`, shifting everything after it to the left.
+    pub fn remove(&mut self, index: usize) -> T {
+        self.raw().remove(index)
+    }
+
+    /// Removes the element at `index` in O(1) by moving the last element into its place.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        self.raw().swap_remove(index)
+    }
+
+    /// Shortens the vector to `len` elements, freeing their slots for new ones.
+    pub fn truncate(&mut self, len: usize) {
+        self.raw().truncate(len);
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    /// Keeps only the elements `f` returns `true` for, in their original order.
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        self.raw().retain_mut(f);
+    }
+
+    /// Removes consecutive elements that map to the same key.
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Removes consecutive repeated elements.
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    /// Removes consecutive elements for which `same_bucket(elem, previous_kept)` returns `true`.
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        self.raw().dedup_by(same_bucket);
+    }
+
+    /// Moves every element of `other` to the end of `self`, leaving `other` empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if they don't all fit, before moving any.
+    pub fn append(&mut self, other: &mut Self) {
+        self.reserve(other.len);
+        self.raw().move_from(&mut other.raw(), 0);
+    }
+
+    /// Clones all of `other` onto the end, or nothing if it doesn't fit.
+    pub fn try_extend_from_slice(&mut self, other: &[T]) -> Result<(), CapacityError>
+    where
+        T: Clone,
+    {
+        self.try_reserve(other.len())?;
+        self.raw().extend_from_slice(other);
+        Ok(())
+    }
+
+    /// Resizes the vector to `new_len`, filling new slots with the results of `f`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` exceeds the capacity.
+    pub fn resize_with<F>(&mut self, new_len: usize, f: F)
+    where
+        F: FnMut() -> T,
+    {
+        self.reserve(new_len.saturating_sub(self.len));
+        self.raw().resize_with(new_len, f);
+    }
+
+    /// Resizes the vector to `new_len`, filling new slots with clones of `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` exceeds the capacity.
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        self.reserve(new_len.saturating_sub(self.len));
+        self.raw().resize(new_len, value);
+    }
+
+    /// Splits the vector in two at `at`, returning the elements from `at` onwards.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let mut other = Self::new();
+        other.raw().move_from(&mut self.raw(), at);
+        other
+    }
+
+    /// Removes `range` from the vector, yielding the removed elements.
+    ///
+    /// Elements that are not consumed are dropped with the iterator. If the iterator is leaked,
+    /// the vector is left truncated at the start of `range`.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, N>
+    where
+        R: RangeBounds<usize>,
+    {
+        Drain {
+            raw: self.raw().drain(range),
+            marker: PhantomData,
+        }
+    }
+
+    /// Replaces `range` with the items of `replace_with`, returning the removed elements.
+    ///
+    /// The replacement is collected before the vector is touched, so a panicking iterator
+    /// leaves it unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result doesn't fit, before changing the vector.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Self
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let range = slice_range(range, self.len);
+        let mut replacement: Self = replace_with.into_iter().collect();
+        self.reserve(replacement.len.saturating_sub(range.len()));
+
+        let mut removed = Self::new();
+        self.raw()
+            .splice(range, &mut replacement.raw(), &mut removed.raw());
+
+        removed
+    }
+
+    /// Returns the array if the vector is full, or gives the vector back.
+    pub fn into_inner(self) -> Result<[T; N], Self> {
+        if !self.is_full() {
+            return Err(self);
+        }
+
+        let this = mem::ManuallyDrop::new(self);
+        Ok(unsafe { ptr::read(this.as_ptr().cast()) })
+    }
+
+    fn reserve(&self, additional: usize) {
+        if self.try_reserve(additional).is_err() {
+            panic!("ArrayVec is full");
+        }
+    }
+
+    fn raw(&mut self) -> LenSlice<'_, T> {
+        let ptr = self.as_mut_ptr();
+        unsafe { LenSlice::new(ptr, N, &mut self.len) }
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut v = Self::new();
+        v.try_extend_from_slice(self).unwrap();
+        v
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<ArrayVec<U, M>>
+    for ArrayVec<T, N>
+{
+    fn eq(&self, other: &ArrayVec<U, M>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U]> for ArrayVec<T, N> {
+    fn eq(&self, other: &[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<[U; M]> for ArrayVec<T, N> {
+    fn eq(&self, other: &[U; M]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayVec<T, N> {}
+
+/// Hashes exactly like the equivalent slice.
+impl<T: Hash, const N: usize> Hash for ArrayVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+/// # Panics
+///
+/// Panics if the iterator yields more than `N` elements.
+impl<T, const N: usize> FromIterator<T> for ArrayVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+/// # Panics
+///
+/// Panics if the vector runs full.
+impl<T, const N: usize> Extend<T> for ArrayVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|elem| self.push(elem));
+    }
+}
+
+/// A draining iterator over a range of an [`ArrayVec`], see [`ArrayVec::drain`]
+pub struct Drain<'v, T, const N: usize> {
+    raw: RawDrain<'v, T>,
+    marker: PhantomData<&'v mut ArrayVec<T, N>>,
+}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.raw.next_back()
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Drain<'_, T, N> {}
+impl<T, const N: usize> FusedIterator for Drain<'_, T, N> {}
+
+/// An owning iterator over the elements of an [`ArrayVec`]
+pub struct IntoIter<T, const N: usize> {
+    /// Emptied, the remaining elements are tracked here
+    vec: ArrayVec<T, N>,
+    start: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.vec.as_ptr().add(self.start), self.end - self.start) }
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        let elem = unsafe { self.vec.data[self.start].assume_init_read() };
+        self.start += 1;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { self.vec.data[self.end].assume_init_read() })
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let remaining = ptr::slice_from_raw_parts_mut(
+            unsafe { self.vec.as_mut_ptr().add(self.start) },
+            self.end - self.start,
+        );
+        self.start = self.end;
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let end = mem::replace(&mut self.len, 0);
+        IntoIter {
+            vec: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<'v, T, const N: usize> IntoIterator for &'v ArrayVec<T, N> {
+    type Item = &'v T;
+    type IntoIter = slice::Iter<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'v, T, const N: usize> IntoIterator for &'v mut ArrayVec<T, N> {
+    type Item = &'v mut T;
+    type IntoIter = slice::IterMut<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn push_until_full() {
+        let mut v = ArrayVec::<&str, 2>::new();
+        v.push("A Game of Thrones");
+        assert_eq!(v.try_push("A Clash of Kings"), Ok(()));
+        assert!(v.is_full());
+
+        let err = v.try_push("A Storm of Swords").unwrap_err();
+        assert_eq!(err.element(), "A Storm of Swords");
+        assert_eq!(v, ["A Game of Thrones", "A Clash of Kings"]);
+        assert_eq!(
+            v.try_insert(0, "The Hedge Knight").unwrap_err().to_string(),
+            "insufficient capacity"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ArrayVec is full")]
+    fn push_full_panics() {
+        let mut v = ArrayVec::<u8, 1>::new();
+        v.extend([1, 2]);
+    }
+
+    #[test]
+    fn editing() {
+        let mut v: ArrayVec<i32, 8> = (0..6).collect();
+        v.insert(0, -1);
+        assert_eq!(v.remove(1), 0);
+        assert_eq!(v.swap_remove(0), -1);
+        v.retain(|n| *n % 2 == 1);
+        assert_eq!(v, [5, 1, 3]);
+        assert_eq!(v.peek(), Some(&3));
+
+        assert!(v.try_extend_from_slice(&[7; 6]).is_err());
+        assert_eq!(v.try_extend_from_slice(&[7; 5]), Ok(()));
+        assert_eq!(v.remaining_capacity(), 0);
+        v.truncate(1);
+        assert_eq!(v, [5]);
+    }
+
+    #[test]
+    fn drain_split_and_splice() {
+        let mut v: ArrayVec<u32, 8> = (0..8).collect();
+        let mut drain = v.drain(2..6);
+        assert_eq!(drain.len(), 4);
+        assert_eq!(drain.next_back(), Some(5));
+        drop(drain);
+        assert_eq!(v, [0, 1, 6, 7]);
+
+        let mut tail = v.split_off(2);
+        assert_eq!(tail, [6, 7]);
+        tail.append(&mut v);
+        assert!(v.is_empty());
+        assert_eq!(tail, [6, 7, 0, 1]);
+
+        let removed = tail.splice(1..3, [10, 11, 12]);
+        assert_eq!(removed, [7, 0]);
+        assert_eq!(tail, [6, 10, 11, 12, 1]);
+    }
+
+    #[test]
+    fn dedup_and_resize() {
+        let mut v: ArrayVec<_, 8> = [1, 1, 2, 3, 3, 3, 1].into_iter().collect();
+        v.dedup();
+        assert_eq!(v, [1, 2, 3, 1]);
+        v.dedup_by_key(|n| *n / 2);
+        assert_eq!(v, [1, 2, 1]);
+
+        v.resize(5, 9);
+        assert_eq!(v, [1, 2, 1, 9, 9]);
+        v.resize_with(2, || unreachable!());
+        assert_eq!(v, [1, 2]);
+
+        assert_eq!(v.try_reserve(6), Ok(()));
+        assert!(v.try_reserve(7).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "ArrayVec is full")]
+    fn resize_past_capacity_panics() {
+        let mut v = ArrayVec::<u8, 4>::new();
+        v.resize(5, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ArrayVec is full")]
+    fn splice_past_capacity_panics() {
+        let mut v: ArrayVec<u8, 4> = (0..4).collect();
+        v.splice(..1, [7, 8]);
+    }
+
+    #[test]
+    fn retain_mut_panicking() {
+        let mut v: ArrayVec<u32, 8> = (0..8).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            v.retain_mut(|n| {
+                assert!(*n != 5);
+                *n % 2 == 0
+            })
+        }));
+        assert!(result.is_err());
+        // Like `Vector`: what was processed is filtered, the rest kept in order
+        assert_eq!(v, [0, 2, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn into_inner() {
+        let v: ArrayVec<_, 3> = (1..3).collect();
+        let mut v = v.into_inner().unwrap_err();
+        v.push(3);
+        assert_eq!(v.into_inner(), Ok([1, 2, 3]));
+    }
+
+    #[test]
+    fn drops_elements() {
+        let book = Rc::new("A Dance with Dragons");
+        let v: ArrayVec<_, 4> = (0..4).map(|_| Rc::clone(&book)).collect();
+        let clone = v.clone();
+        assert_eq!(Rc::strong_count(&book), 9);
+
+        drop(clone);
+        let mut iter = v.into_iter();
+        iter.next_back();
+        assert_eq!(iter.as_slice().len(), 3);
+        assert_eq!(Rc::strong_count(&book), 4);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&book), 1);
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut v = ArrayVec::<(), 0>::new();
+        assert!(v.is_full());
+        assert!(v.try_push(()).is_err());
+        assert_eq!(v.pop(), None);
+    }
+}
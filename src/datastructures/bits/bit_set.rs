@@ -0,0 +1,328 @@
+use std::{fmt, iter::FusedIterator};
+
+use super::bit_vec::{words_for, Ones, WORD_BITS};
+use crate::datastructures::Vector;
+
+/// A set of small `usize`s, stored as one bit per possible element.
+///
+/// Takes memory proportional to the largest element, and set operations work a word at a time.
+#[derive(Clone, Default)]
+pub struct BitSet {
+    words: Vector<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty set that can hold every element below `capacity` without allocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            words: Vector::with_capacity(words_for(capacity)),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// One past the largest element the set can hold without allocating.
+    pub fn capacity(&self) -> usize {
+        self.words.capacity().saturating_mul(WORD_BITS)
+    }
+
+    /// Adds `elem`, returns whether it was new.
+    pub fn insert(&mut self, elem: usize) -> bool {
+        let (word, mask) = Self::position(elem);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+
+        let new = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        self.len += new as usize;
+        new
+    }
+
+    /// Removes `elem`, returns whether it was there.
+    pub fn remove(&mut self, elem: usize) -> bool {
+        let (word, mask) = Self::position(elem);
+        let Some(bits) = self.words.get_mut(word) else {
+            return false;
+        };
+
+        let present = *bits & mask != 0;
+        *bits &= !mask;
+        self.len -= present as usize;
+        present
+    }
+
+    pub fn contains(&self, elem: usize) -> bool {
+        let (word, mask) = Self::position(elem);
+        self.words.get(word).is_some_and(|bits| bits & mask != 0)
+    }
+
+    pub fn first(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    pub fn last(&self) -> Option<usize> {
+        let (word, bits) = self
+            .words
+            .iter()
+            .enumerate()
+            .rfind(|(_, &bits)| bits != 0)?;
+        Some(word * WORD_BITS + (WORD_BITS - 1 - bits.leading_zeros() as usize))
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    /// Iterates over the elements in ascending order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            ones: Ones::new(&self.words),
+            remaining: self.len,
+        }
+    }
+
+    /// Adds every element of `other`.
+    pub fn union_with(&mut self, other: &BitSet) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        self.zip_words(other, |a, b| a | b);
+    }
+
+    /// Keeps only the elements also in `other`.
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        self.words.truncate(other.words.len());
+        self.zip_words(other, |a, b| a & b);
+    }
+
+    /// Removes every element of `other`.
+    pub fn difference_with(&mut self, other: &BitSet) {
+        self.zip_words(other, |a, b| a & !b);
+    }
+
+    /// Keeps the elements in exactly one of `self` and `other`.
+    pub fn symmetric_difference_with(&mut self, other: &BitSet) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        self.zip_words(other, |a, b| a ^ b);
+    }
+
+    pub fn is_disjoint(&self, other: &BitSet) -> bool {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .all(|(a, b)| a & b == 0)
+    }
+
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        self.words
+            .iter()
+            .enumerate()
+            .all(|(i, a)| a & !other.words.get(i).copied().unwrap_or(0) == 0)
+    }
+
+    pub fn is_superset(&self, other: &BitSet) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Drops trailing empty words to free unused memory.
+    pub fn shrink_to_fit(&mut self) {
+        let used = self
+            .words
+            .iter()
+            .rposition(|&bits| bits != 0)
+            .map_or(0, |i| i + 1);
+        self.words.truncate(used);
+        self.words.shrink_to_fit();
+    }
+
+    fn position(elem: usize) -> (usize, u64) {
+        (elem / WORD_BITS, 1 << (elem % WORD_BITS))
+    }
+
+    /// Combines the words both sets have in common and recounts the elements.
+    fn zip_words(&mut self, other: &BitSet, f: impl Fn(u64, u64) -> u64) {
+        for (a, &b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a = f(*a, b);
+        }
+        self.len = self.words.iter().map(|w| w.count_ones() as usize).sum();
+    }
+}
+
+/// Sets are equal when they hold the same elements, however much room they have.
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        let (short, long) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+
+        self.len == other.len
+            && long[..short.len()] == short[..]
+            && long[short.len()..].iter().all(|&bits| bits == 0)
+    }
+}
+
+impl Eq for BitSet {}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const N: usize> From<[usize; N]> for BitSet {
+    fn from(elems: [usize; N]) -> Self {
+        elems.into_iter().collect()
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|elem| {
+            self.insert(elem);
+        });
+    }
+}
+
+impl<'s> IntoIterator for &'s BitSet {
+    type Item = usize;
+    type IntoIter = Iter<'s>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a [`BitSet`], in ascending order
+#[derive(Clone)]
+pub struct Iter<'s> {
+    ones: Ones<'s>,
+    remaining: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+    fn next(&mut self) -> Option<Self::Item> {
+        let elem = self.ones.next()?;
+        self.remaining -= 1;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+impl FusedIterator for Iter<'_> {}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn insert_remove() {
+        let mut set = BitSet::new();
+        assert!(set.insert(3));
+        assert!(set.insert(200));
+        assert!(!set.insert(3));
+
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(200));
+        assert!(!set.contains(4000));
+        assert_eq!((set.first(), set.last()), (Some(3), Some(200)));
+        assert_eq!(format!("{set:?}"), "{3, 200}");
+
+        assert!(set.remove(200));
+        assert!(!set.remove(200));
+        assert!(!set.remove(4000));
+        set.shrink_to_fit();
+        assert_eq!(set, BitSet::from([3]));
+        assert_eq!(set.last(), Some(3));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = BitSet::from([1, 2, 3, 4, 5]);
+        let b = BitSet::from([4, 5, 6, 70]);
+
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert!(union.iter().eq([1, 2, 3, 4, 5, 6, 70]));
+
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b);
+        assert_eq!(intersection, BitSet::from([4, 5]));
+
+        let mut difference = b.clone();
+        difference.difference_with(&a);
+        assert_eq!(difference, BitSet::from([6, 70]));
+
+        let mut symmetric = a.clone();
+        symmetric.symmetric_difference_with(&b);
+        assert_eq!(symmetric, BitSet::from([1, 2, 3, 6, 70]));
+        assert_eq!(symmetric.len(), 5);
+
+        let c = BitSet::from([2, 3]);
+        assert!(c.is_subset(&a));
+        assert!(a.is_superset(&c));
+        assert!(!b.is_subset(&a));
+        assert!(c.is_disjoint(&b));
+        assert!(!a.is_disjoint(&b));
+    }
+
+    #[test]
+    fn matches_btree_set() {
+        let mut rng = StdRng::seed_from_u64(45);
+        let mut set = BitSet::new();
+        let mut expected = BTreeSet::new();
+
+        for _ in 0..20_000 {
+            let elem = rng.gen_range(0..5000);
+            if rng.gen_bool(0.6) {
+                assert_eq!(set.insert(elem), expected.insert(elem));
+            } else {
+                assert_eq!(set.remove(elem), expected.remove(&elem));
+            }
+        }
+
+        assert_eq!(set.len(), expected.len());
+        assert!(set.iter().eq(expected.iter().copied()));
+        assert_eq!(set.last(), expected.last().copied());
+
+        let other: BTreeSet<usize> = (0..3000).map(|_| rng.gen_range(0..8000)).collect();
+        let mut union = set.clone();
+        union.union_with(&other.iter().copied().collect());
+        assert!(union.iter().eq(expected.union(&other).copied()));
+        assert_eq!(union.len(), expected.union(&other).count());
+    }
+}
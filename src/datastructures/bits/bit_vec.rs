@@ -0,0 +1,502 @@
+use std::{
+    fmt,
+    iter::FusedIterator,
+    ops::{
+        BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Index, Not, Range,
+        RangeBounds,
+    },
+};
+
+use crate::datastructures::{vector::vector::slice_range, Vector};
+
+pub(crate) const WORD_BITS: usize = u64::BITS as usize;
+
+/// How many words `bits` bits take up.
+pub(crate) fn words_for(bits: usize) -> usize {
+    bits.div_ceil(WORD_BITS)
+}
+
+/// A growable array of bits, packed 64 to a word.
+///
+/// Bits past the length in the last word are always zero, so whole words can be compared,
+/// hashed and counted as they are.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitVec {
+    words: Vector<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty bit vector with room for at least `capacity` bits.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            words: Vector::with_capacity(words_for(capacity)),
+            len: 0,
+        }
+    }
+
+    /// Creates a bit vector of `len` copies of `bit`.
+    pub fn repeat(bit: bool, len: usize) -> Self {
+        let mut bits = Self::new();
+        bits.resize(len, bit);
+        bits
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.words.capacity().saturating_mul(WORD_BITS)
+    }
+
+    /// Returns the bits as words, bit `i` is bit `i % 64` of word `i / 64`.
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    pub fn push(&mut self, bit: bool) {
+        if self.len.is_multiple_of(WORD_BITS) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, bit);
+    }
+
+    pub fn pop(&mut self) -> Option<bool> {
+        let bit = self.get(self.len.checked_sub(1)?)?;
+        self.truncate(self.len - 1);
+        Some(bit)
+    }
+
+    pub fn get(&self, index: usize) -> Option<bool> {
+        (index < self.len).then(|| self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 == 1)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, bit: bool) {
+        let len = self.len;
+        assert!(index < len, "index (is {index}) should be < len (is {len})");
+
+        let mask = 1 << (index % WORD_BITS);
+        let word = &mut self.words[index / WORD_BITS];
+        if bit {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// Flips the bit at `index`.
+    pub fn toggle(&mut self, index: usize) {
+        let bit = self[index];
+        self.set(index, !bit);
+    }
+
+    /// Drops every bit past the first `len`.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        self.len = len;
+        self.words.truncate(words_for(len));
+        self.clear_unused();
+    }
+
+    /// Grows or shrinks the vector to `len` bits, filling new ones with `bit`.
+    pub fn resize(&mut self, len: usize, bit: bool) {
+        if len <= self.len {
+            self.truncate(len);
+            return;
+        }
+
+        // Fill the rest of the current last word, then whole words
+        let old_len = self.len;
+        let fill = if bit { u64::MAX } else { 0 };
+        if !old_len.is_multiple_of(WORD_BITS) && bit {
+            *self.words.last_mut().unwrap() |= u64::MAX << (old_len % WORD_BITS);
+        }
+        self.words.resize(words_for(len), fill);
+        self.len = len;
+        self.clear_unused();
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    /// Sets every bit to `bit`.
+    pub fn fill(&mut self, bit: bool) {
+        let fill = if bit { u64::MAX } else { 0 };
+        self.words.iter_mut().for_each(|word| *word = fill);
+        self.clear_unused();
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&w| w != 0)
+    }
+
+    pub fn all(&self) -> bool {
+        self.count_ones() == self.len
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        self.range(..)
+    }
+
+    /// Iterates over the bits in `range`.
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> Iter<'_> {
+        Iter {
+            bits: self,
+            range: slice_range(range, self.len),
+        }
+    }
+
+    /// Iterates over the indices of the set bits, in ascending order.
+    pub fn iter_ones(&self) -> Ones<'_> {
+        Ones::new(&self.words)
+    }
+
+    /// Zeroes the bits past `len` in the last word.
+    fn clear_unused(&mut self) {
+        if !self.len.is_multiple_of(WORD_BITS) {
+            *self.words.last_mut().unwrap() &= (1 << (self.len % WORD_BITS)) - 1;
+        }
+    }
+
+    /// Combines every word with the one of `other` at the same position.
+    fn zip_words(&mut self, other: &BitVec, f: impl Fn(u64, u64) -> u64) {
+        let (len, other_len) = (self.len, other.len);
+        assert_eq!(
+            len, other_len,
+            "bitwise operation on BitVecs of different lengths"
+        );
+
+        for (a, &b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a = f(*a, b);
+        }
+        self.clear_unused();
+    }
+}
+
+impl Index<usize> for BitVec {
+    type Output = bool;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(true) => &true,
+            Some(false) => &false,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {index}",
+                self.len
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BitVec[")?;
+        for bit in self {
+            write!(f, "{}", bit as u8)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl FromIterator<bool> for BitVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = Self::new();
+        bits.extend(iter);
+        bits
+    }
+}
+
+impl Extend<bool> for BitVec {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.words.reserve(words_for(iter.size_hint().0));
+        iter.for_each(|bit| self.push(bit));
+    }
+}
+
+impl<'b> IntoIterator for &'b BitVec {
+    type Item = bool;
+    type IntoIter = Iter<'b>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+macro_rules! bitwise {
+    ($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident, $f:expr) => {
+        /// # Panics
+        ///
+        /// Panics if the lengths differ.
+        impl $OpAssign<&BitVec> for BitVec {
+            fn $op_assign(&mut self, other: &BitVec) {
+                self.zip_words(other, $f);
+            }
+        }
+
+        impl $Op<&BitVec> for &BitVec {
+            type Output = BitVec;
+
+            fn $op(self, other: &BitVec) -> Self::Output {
+                let mut result = self.clone();
+                $OpAssign::$op_assign(&mut result, other);
+                result
+            }
+        }
+
+        impl $Op<&BitVec> for BitVec {
+            type Output = BitVec;
+
+            fn $op(mut self, other: &BitVec) -> Self::Output {
+                $OpAssign::$op_assign(&mut self, other);
+                self
+            }
+        }
+    };
+}
+
+bitwise!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b);
+bitwise!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b);
+bitwise!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| a ^ b);
+
+impl Not for BitVec {
+    type Output = BitVec;
+
+    fn not(mut self) -> Self::Output {
+        self.words.iter_mut().for_each(|word| *word = !*word);
+        self.clear_unused();
+        self
+    }
+}
+
+impl Not for &BitVec {
+    type Output = BitVec;
+
+    fn not(self) -> Self::Output {
+        !self.clone()
+    }
+}
+
+/// An iterator over a range of the bits of a [`BitVec`]
+#[derive(Clone)]
+pub struct Iter<'b> {
+    bits: &'b BitVec,
+    range: Range<usize>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = bool;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|i| self.bits[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|i| self.bits[i])
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+impl FusedIterator for Iter<'_> {}
+
+/// An iterator over the indices of the set bits in a slice of words
+#[derive(Clone)]
+pub struct Ones<'b> {
+    words: &'b [u64],
+    next_word: usize,
+    /// The bits of the current word not yielded yet
+    current: u64,
+    /// The index of bit 0 of the current word
+    base: usize,
+}
+
+impl<'b> Ones<'b> {
+    pub(crate) fn new(words: &'b [u64]) -> Self {
+        Self {
+            words,
+            next_word: 0,
+            current: 0,
+            base: 0,
+        }
+    }
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current == 0 {
+            self.current = *self.words.get(self.next_word)?;
+            self.base = self.next_word * WORD_BITS;
+            self.next_word += 1;
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        // Clears the lowest set bit
+        self.current &= self.current - 1;
+        Some(self.base + bit)
+    }
+}
+
+impl FusedIterator for Ones<'_> {}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn random(rng: &mut StdRng, len: usize) -> Vec<bool> {
+        (0..len).map(|_| rng.gen_bool(0.3)).collect()
+    }
+
+    #[test]
+    fn push_get_set() {
+        let mut bits = BitVec::new();
+        [true, false, true, true]
+            .into_iter()
+            .for_each(|b| bits.push(b));
+
+        assert_eq!(bits.len(), 4);
+        assert_eq!(bits.get(2), Some(true));
+        assert_eq!(bits.get(4), None);
+        assert!(!bits[1]);
+
+        bits.set(1, true);
+        bits.toggle(0);
+        assert_eq!(format!("{bits:?}"), "BitVec[0111]");
+        assert_eq!(bits.count_ones(), 3);
+        assert_eq!(bits.pop(), Some(true));
+        assert_eq!(bits.as_words(), [0b110]);
+    }
+
+    #[test]
+    #[should_panic(expected = "index (is 3) should be < len (is 3)")]
+    fn set_out_of_bounds() {
+        BitVec::repeat(false, 3).set(3, true);
+    }
+
+    #[test]
+    fn resize_and_fill() {
+        let mut bits = BitVec::repeat(true, 70);
+        assert_eq!(bits.as_words(), [u64::MAX, 0b111111]);
+        assert!(bits.all());
+
+        bits.truncate(3);
+        bits.resize(130, false);
+        assert_eq!(bits.count_ones(), 3);
+        bits.resize(131, true);
+        assert_eq!(bits.iter_ones().collect::<Vec<_>>(), [0, 1, 2, 130]);
+
+        bits.fill(false);
+        assert!(!bits.any());
+        bits.fill(true);
+        assert_eq!(bits.count_zeros(), 0);
+        assert_eq!(bits, BitVec::repeat(true, 131));
+    }
+
+    #[test]
+    fn bitwise() {
+        let a: BitVec = [true, true, false, false].into_iter().collect();
+        let b: BitVec = [true, false, true, false].into_iter().collect();
+
+        assert_eq!(format!("{:?}", &a & &b), "BitVec[1000]");
+        assert_eq!(format!("{:?}", &a | &b), "BitVec[1110]");
+        assert_eq!(format!("{:?}", &a ^ &b), "BitVec[0110]");
+        assert_eq!(format!("{:?}", !&a), "BitVec[0011]");
+        // Negation leaves the unused bits alone
+        assert_eq!((!a).as_words(), [0b1100]);
+    }
+
+    #[test]
+    #[should_panic(expected = "bitwise operation on BitVecs of different lengths")]
+    fn bitwise_different_lengths() {
+        let _ = BitVec::repeat(true, 3) & &BitVec::repeat(true, 4);
+    }
+
+    #[test]
+    fn matches_vec_bool() {
+        let mut rng = StdRng::seed_from_u64(45);
+        let mut bits = BitVec::new();
+        let mut expected: Vec<bool> = Vec::new();
+
+        for _ in 0..20_000 {
+            match rng.gen_range(0..10) {
+                0..=5 => {
+                    let bit = rng.gen();
+                    bits.push(bit);
+                    expected.push(bit);
+                }
+                6 => assert_eq!(bits.pop(), expected.pop()),
+                _ if !expected.is_empty() => {
+                    let i = rng.gen_range(0..expected.len());
+                    let bit = rng.gen();
+                    bits.set(i, bit);
+                    expected[i] = bit;
+                }
+                _ => {}
+            }
+        }
+
+        assert_eq!(bits.len(), expected.len());
+        assert!(bits.iter().eq(expected.iter().copied()));
+        assert_eq!(bits.count_ones(), expected.iter().filter(|&&b| b).count());
+        let (start, end) = (expected.len() / 3, expected.len() / 2);
+        assert!(bits
+            .range(start..end)
+            .rev()
+            .eq(expected[start..end].iter().rev().copied()));
+        assert!(bits
+            .iter_ones()
+            .eq((0..expected.len()).filter(|&i| expected[i])));
+    }
+
+    #[test]
+    fn bitwise_matches_vec_bool() {
+        let mut rng = StdRng::seed_from_u64(45);
+        for len in [0, 1, 63, 64, 65, 1000, 4097] {
+            let (x, y) = (random(&mut rng, len), random(&mut rng, len));
+            let (a, b): (BitVec, BitVec) =
+                (x.iter().copied().collect(), y.iter().copied().collect());
+            let zip = |f: fn(bool, bool) -> bool| -> Vec<bool> {
+                x.iter().zip(&y).map(|(&p, &q)| f(p, q)).collect()
+            };
+
+            assert!((&a & &b).iter().eq(zip(|p, q| p & q)));
+            assert!((&a | &b).iter().eq(zip(|p, q| p | q)));
+            assert!((&a ^ &b).iter().eq(zip(|p, q| p ^ q)));
+            assert!((!&a).iter().eq(x.iter().map(|p| !p)));
+            assert_eq!((!&a).count_ones(), len - a.count_ones());
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod bit_set;
+pub mod bit_vec;
+pub mod rank_select;
+
+pub use bit_set::BitSet;
+pub use bit_vec::BitVec;
+pub use rank_select::RankSelect;
@@ -0,0 +1,258 @@
+use super::bit_vec::{BitVec, WORD_BITS};
+use crate::datastructures::Vector;
+
+/// Words per superblock, each superblock stores the number of ones before it.
+const SUPERBLOCK_WORDS: usize = 8;
+const SUPERBLOCK_BITS: usize = SUPERBLOCK_WORDS * WORD_BITS;
+/// Every `SELECT_SAMPLE`th one remembers its superblock to narrow down `select1`.
+const SELECT_SAMPLE: usize = 4096;
+
+/// A read-only [`BitVec`] with an index that counts and finds ones quickly.
+///
+/// `rank1` takes a superblock count plus at most eight popcounts, and `select1` binary
+/// searches the superblocks between two samples, so both are constant time in practice.
+/// The index costs an eighth of the bits on top of the vector.
+#[derive(Clone, Debug)]
+pub struct RankSelect {
+    bits: BitVec,
+    /// The ones before each superblock, followed by the total
+    superblocks: Vector<usize>,
+    /// The superblock of every `SELECT_SAMPLE`th one
+    samples: Vector<usize>,
+}
+
+impl RankSelect {
+    pub fn new(bits: BitVec) -> Self {
+        let words = bits.as_words();
+        let mut superblocks = Vector::with_capacity(words.len() / SUPERBLOCK_WORDS + 2);
+        let mut samples = Vector::new();
+        let mut ones = 0;
+
+        for (superblock, chunk) in words.chunks(SUPERBLOCK_WORDS).enumerate() {
+            superblocks.push(ones);
+            let count: usize = chunk.iter().map(|w| w.count_ones() as usize).sum();
+            while samples.len() * SELECT_SAMPLE < ones + count {
+                samples.push(superblock);
+            }
+            ones += count;
+        }
+        superblocks.push(ones);
+
+        Self {
+            bits,
+            superblocks,
+            samples,
+        }
+    }
+
+    pub fn bits(&self) -> &BitVec {
+        &self.bits
+    }
+
+    pub fn into_inner(self) -> BitVec {
+        self.bits
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.bits.get(index)
+    }
+
+    pub fn count_ones(&self) -> usize {
+        *self.superblocks.last().unwrap()
+    }
+
+    pub fn count_zeros(&self) -> usize {
+        self.len() - self.count_ones()
+    }
+
+    /// The number of ones before `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn rank1(&self, index: usize) -> usize {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "index (is {index}) should be <= len (is {len})"
+        );
+
+        let words = self.bits.as_words();
+        let (word, bit) = (index / WORD_BITS, index % WORD_BITS);
+        let superblock = index / SUPERBLOCK_BITS;
+
+        let mut rank = self.superblocks[superblock];
+        rank += words[superblock * SUPERBLOCK_WORDS..word]
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum::<usize>();
+        if bit != 0 {
+            rank += (words[word] & ((1 << bit) - 1)).count_ones() as usize;
+        }
+        rank
+    }
+
+    /// The number of zeros before `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn rank0(&self, index: usize) -> usize {
+        index - self.rank1(index)
+    }
+
+    /// The position of the `k`th one, counting from zero.
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        if k >= self.count_ones() {
+            return None;
+        }
+
+        // The one is somewhere between the superblocks of the samples around it
+        let sample = k / SELECT_SAMPLE;
+        let lo = self.samples[sample];
+        let hi = self
+            .samples
+            .get(sample + 1)
+            .map_or(self.superblocks.len() - 1, |&s| s + 1);
+        let superblock = lo + self.superblocks[lo..hi].partition_point(|&r| r <= k) - 1;
+
+        self.select_in_superblock(superblock, k - self.superblocks[superblock], |w| w)
+    }
+
+    /// The position of the `k`th zero, counting from zero.
+    pub fn select0(&self, k: usize) -> Option<usize> {
+        if k >= self.count_zeros() {
+            return None;
+        }
+
+        // The last superblock with at most `k` zeros before it, zeros are not sampled
+        let zeros_before = |s: usize| s * SUPERBLOCK_BITS - self.superblocks[s];
+        let (mut lo, mut hi) = (0, self.superblocks.len() - 1);
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if zeros_before(mid) <= k {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let superblock = lo;
+
+        // Zeros past the end never count, they are not in any word
+        self.select_in_superblock(superblock, k - zeros_before(superblock), |w| !w)
+    }
+
+    /// Finds the `k`th set bit of `map(word)` in the words of `superblock`.
+    fn select_in_superblock(
+        &self,
+        superblock: usize,
+        mut k: usize,
+        map: impl Fn(u64) -> u64,
+    ) -> Option<usize> {
+        let words = self.bits.as_words();
+        let start = superblock * SUPERBLOCK_WORDS;
+        let end = (start + SUPERBLOCK_WORDS).min(words.len());
+
+        for (i, &word) in words[start..end].iter().enumerate() {
+            let word = map(word);
+            let count = word.count_ones() as usize;
+            if k < count {
+                let position = (start + i) * WORD_BITS + select_in_word(word, k);
+                return (position < self.len()).then_some(position);
+            }
+            k -= count;
+        }
+        None
+    }
+}
+
+impl From<BitVec> for RankSelect {
+    fn from(bits: BitVec) -> Self {
+        Self::new(bits)
+    }
+}
+
+/// The position of the `k`th set bit of `word`, which must have more than `k` of them.
+fn select_in_word(mut word: u64, k: usize) -> usize {
+    for _ in 0..k {
+        word &= word - 1;
+    }
+    word.trailing_zeros() as usize
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// Checks every rank and select against a plain scan of `expected`.
+    fn check(expected: &[bool]) {
+        let index = RankSelect::new(expected.iter().copied().collect());
+
+        let mut ones = 0;
+        for (i, &bit) in expected.iter().enumerate() {
+            assert_eq!(index.rank1(i), ones);
+            assert_eq!(index.rank0(i), i - ones);
+            ones += bit as usize;
+        }
+        assert_eq!(index.rank1(expected.len()), ones);
+        assert_eq!(index.count_ones(), ones);
+
+        let positions = |bit: bool| (0..expected.len()).filter(move |&i| expected[i] == bit);
+        for (k, position) in positions(true).enumerate() {
+            assert_eq!(index.select1(k), Some(position));
+        }
+        for (k, position) in positions(false).enumerate() {
+            assert_eq!(index.select0(k), Some(position));
+        }
+        assert_eq!(index.select1(ones), None);
+        assert_eq!(index.select0(expected.len() - ones), None);
+    }
+
+    #[test]
+    fn small() {
+        let bits: BitVec = [false, true, true, false, true].into_iter().collect();
+        let index = RankSelect::from(bits);
+
+        assert_eq!(index.rank1(0), 0);
+        assert_eq!(index.rank1(3), 2);
+        assert_eq!(index.rank1(5), 3);
+        assert_eq!(index.select1(2), Some(4));
+        assert_eq!(index.select0(1), Some(3));
+        assert_eq!(index.select0(2), None);
+        assert_eq!(index.into_inner().len(), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "index (is 6) should be <= len (is 5)")]
+    fn rank_out_of_bounds() {
+        RankSelect::new(BitVec::repeat(true, 5)).rank1(6);
+    }
+
+    #[test]
+    fn edges() {
+        check(&[]);
+        check(&[true; 1000]);
+        check(&[false; 1000]);
+        check(&[true; SUPERBLOCK_BITS]);
+        check(&[true; SELECT_SAMPLE * 2 + 1]);
+    }
+
+    #[test]
+    fn matches_vec_bool() {
+        let mut rng = StdRng::seed_from_u64(45);
+        for (len, density) in [(20_000, 0.5), (50_000, 0.01), (30_000, 0.97), (777, 0.3)] {
+            let expected: Vec<bool> = (0..len).map(|_| rng.gen_bool(density)).collect();
+            check(&expected);
+        }
+    }
+}
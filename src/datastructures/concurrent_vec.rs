@@ -0,0 +1,387 @@
+use std::{
+    alloc::{self, Layout},
+    cell::UnsafeCell,
+    fmt,
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ops,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+};
+
+use crate::datastructures::{
+    segments::{self, SEGMENTS},
+    Allocator, Global,
+};
+
+/// A slot in a bucket, `active` once its value is written
+struct Entry<T> {
+    active: AtomicBool,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// An append-only vector that threads can push to through a shared reference, without locks.
+///
+/// Elements live in buckets laid out like the segments of a
+/// [`SegmentedVec`](super::SegmentedVec), so they never move. A push claims its index with
+/// one atomic increment and only races other pushes for the allocation of a new bucket.
+///
+/// Indices are handed out in order, but pushes finish in any order: while some are still
+/// writing their value, later indices may already be readable and earlier ones not.
+/// [`get`](ConcurrentVec::get) and [`iter`](ConcurrentVec::iter) only see finished pushes.
+pub struct ConcurrentVec<T> {
+    /// Bucket `k` holds `2^k` entries and is allocated by whichever push needs it first
+    buckets: [AtomicPtr<Entry<T>>; SEGMENTS],
+    /// The next index to hand out
+    reserved: AtomicUsize,
+    /// The number of finished pushes
+    len: AtomicUsize,
+    _marker: PhantomData<T>,
+}
+
+// Values are pushed from one thread and read or dropped by others
+unsafe impl<T: Send> Send for ConcurrentVec<T> {}
+unsafe impl<T: Send + Sync> Sync for ConcurrentVec<T> {}
+
+impl<T> ConcurrentVec<T> {
+    pub const fn new() -> Self {
+        Self {
+            buckets: [const { AtomicPtr::new(ptr::null_mut()) }; SEGMENTS],
+            reserved: AtomicUsize::new(0),
+            len: AtomicUsize::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of finished pushes.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends `value` and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector already handed out `usize::MAX` indices.
+    pub fn push(&self, value: T) -> usize {
+        let index = self.reserved.fetch_add(1, Ordering::Relaxed);
+        assert!(index < usize::MAX, "capacity overflow");
+
+        let (segment, offset) = segments::location(index);
+        // Allocate the next bucket a little early, so pushes rarely wait for one
+        let len = segments::segment_len(segment);
+        if offset == len - len / 8 && segment + 1 < SEGMENTS {
+            self.bucket(segment + 1);
+        }
+
+        let entry = unsafe { &*self.bucket(segment).add(offset) };
+        // The index is ours alone, nobody else touches the value until `active` is set
+        unsafe { (*entry.value.get()).write(value) };
+        entry.active.store(true, Ordering::Release);
+        self.len.fetch_add(1, Ordering::Release);
+        index
+    }
+
+    /// The value pushed at `index`, if that push has finished.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let entry = self.entry(index)?;
+        entry
+            .active
+            .load(Ordering::Acquire)
+            .then(|| unsafe { (*entry.value.get()).assume_init_ref() })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let entry = self.entry(index)?;
+        entry
+            .active
+            .load(Ordering::Relaxed)
+            .then(|| unsafe { (*entry.value.get()).assume_init_mut() })
+    }
+
+    /// Iterates over the finished pushes with their indices, in index order.
+    ///
+    /// Pushes that start after the iterator is created are not visited.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            vec: self,
+            index: 0,
+            end: self.reserved.load(Ordering::Relaxed).min(usize::MAX - 1),
+        }
+    }
+
+    fn entry(&self, index: usize) -> Option<&Entry<T>> {
+        if index == usize::MAX {
+            return None;
+        }
+        let (segment, offset) = segments::location(index);
+        let bucket = self.buckets[segment].load(Ordering::Acquire);
+        (!bucket.is_null()).then(|| unsafe { &*bucket.add(offset) })
+    }
+
+    /// Returns bucket `segment`, allocating it if nobody did yet.
+    fn bucket(&self, segment: usize) -> *mut Entry<T> {
+        let bucket = self.buckets[segment].load(Ordering::Acquire);
+        if !bucket.is_null() {
+            return bucket;
+        }
+
+        let layout = Self::layout(segment);
+        let new = Global
+            .allocate(layout)
+            .unwrap_or_else(|_| alloc::handle_alloc_error(layout))
+            .cast::<Entry<T>>()
+            .as_ptr();
+        for i in 0..segments::segment_len(segment) {
+            unsafe { ptr::addr_of_mut!((*new.add(i)).active).write(AtomicBool::new(false)) };
+        }
+
+        match self.buckets[segment].compare_exchange(
+            ptr::null_mut(),
+            new,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => new,
+            Err(winner) => {
+                // Another push got there first, its bucket is as good as ours
+                unsafe { Global.deallocate(NonNull::new_unchecked(new.cast()), layout) };
+                winner
+            }
+        }
+    }
+
+    fn layout(segment: usize) -> Layout {
+        Layout::array::<Entry<T>>(segments::segment_len(segment)).expect("capacity overflow")
+    }
+}
+
+impl<T> Drop for ConcurrentVec<T> {
+    fn drop(&mut self) {
+        for (segment, bucket) in self.buckets.iter_mut().enumerate() {
+            let bucket = *bucket.get_mut();
+            if bucket.is_null() {
+                continue;
+            }
+            for i in 0..segments::segment_len(segment) {
+                let entry = unsafe { &mut *bucket.add(i) };
+                if *entry.active.get_mut() {
+                    unsafe { entry.value.get_mut().assume_init_drop() };
+                }
+            }
+            let bucket = unsafe { NonNull::new_unchecked(bucket.cast()) };
+            unsafe { Global.deallocate(bucket, Self::layout(segment)) };
+        }
+    }
+}
+
+impl<T> Default for ConcurrentVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ConcurrentVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> ops::Index<usize> for ConcurrentVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T> FromIterator<T> for ConcurrentVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T> Extend<T> for ConcurrentVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| {
+            self.push(value);
+        });
+    }
+}
+
+/// An iterator over the finished pushes of a [`ConcurrentVec`] and their indices
+pub struct Iter<'v, T> {
+    vec: &'v ConcurrentVec<T>,
+    index: usize,
+    end: usize,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self { ..*self }
+    }
+}
+
+impl<'v, T> Iterator for Iter<'v, T> {
+    type Item = (usize, &'v T);
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.end {
+            self.index += 1;
+            if let Some(value) = self.vec.get(self.index - 1) {
+                return Some((self.index - 1, value));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.index))
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'v, T> IntoIterator for &'v ConcurrentVec<T> {
+    type Item = (usize, &'v T);
+    type IntoIter = Iter<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{Arc, Barrier},
+        thread,
+    };
+
+    use super::*;
+
+    const THREADS: usize = 8;
+    const PUSHES: usize = if cfg!(miri) { 50 } else { 10_000 };
+
+    #[test]
+    fn push_and_get() {
+        let vec = ConcurrentVec::new();
+        assert_eq!(vec.push("A Game of Thrones".to_string()), 0);
+        let first = vec.get(0).unwrap();
+
+        for n in 1..100 {
+            assert_eq!(vec.push(n.to_string()), n);
+        }
+        assert_eq!(first, "A Game of Thrones");
+        assert_eq!(vec.len(), 100);
+        assert_eq!(vec[42], "42");
+        assert_eq!(vec.get(100), None);
+        assert_eq!(vec.get(usize::MAX), None);
+        assert_eq!(vec.iter().map(|(i, _)| i).sum::<usize>(), 4950);
+        assert_eq!(
+            format!("{:?}", ConcurrentVec::from_iter(["A Clash of Kings"])),
+            r#"{0: "A Clash of Kings"}"#
+        );
+
+        let mut vec = vec;
+        vec.get_mut(1).unwrap().push_str(" pushed");
+        assert_eq!(vec[1], "1 pushed");
+    }
+
+    #[test]
+    fn concurrent_pushes() {
+        let vec = ConcurrentVec::new();
+        let barrier = Barrier::new(THREADS);
+
+        let indices: Vec<Vec<usize>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..THREADS)
+                .map(|thread| {
+                    let (vec, barrier) = (&vec, &barrier);
+                    s.spawn(move || {
+                        barrier.wait();
+                        (0..PUSHES)
+                            .map(|n| {
+                                let index = vec.push((thread, n));
+                                assert_eq!(vec[index], (thread, n));
+                                index
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        assert_eq!(vec.len(), THREADS * PUSHES);
+        let mut seen = vec![false; THREADS * PUSHES];
+        for (thread, indices) in indices.iter().enumerate() {
+            // Each thread's pushes land in the order it made them
+            assert!(indices.windows(2).all(|w| w[0] < w[1]));
+            for (n, &index) in indices.iter().enumerate() {
+                assert_eq!(vec[index], (thread, n));
+                assert!(!seen[index]);
+                seen[index] = true;
+            }
+        }
+        assert_eq!(vec.iter().count(), THREADS * PUSHES);
+    }
+
+    #[test]
+    fn read_while_pushing() {
+        let vec = ConcurrentVec::new();
+
+        thread::scope(|s| {
+            for thread in 0..THREADS / 2 {
+                let vec = &vec;
+                s.spawn(move || {
+                    for n in 0..PUSHES {
+                        vec.push(format!("{thread}:{n}"));
+                    }
+                });
+            }
+            for _ in 0..THREADS / 2 {
+                let vec = &vec;
+                s.spawn(move || {
+                    let mut last = [None; THREADS / 2];
+                    while vec.len() < THREADS / 2 * PUSHES {
+                        // Only whole values show up, and a thread's values in order
+                        last.fill(None);
+                        for (_, value) in vec.iter() {
+                            let (thread, n) = value.split_once(':').unwrap();
+                            let (thread, n): (usize, usize) =
+                                (thread.parse().unwrap(), n.parse().unwrap());
+                            assert!(last[thread].is_none_or(|last| last < n));
+                            last[thread] = Some(n);
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(vec.iter().count(), THREADS / 2 * PUSHES);
+    }
+
+    #[test]
+    fn drops_every_value() {
+        let counter = Arc::new(());
+        let vec = ConcurrentVec::new();
+        thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    for _ in 0..PUSHES / 10 {
+                        vec.push(Arc::clone(&counter));
+                    }
+                });
+            }
+        });
+        assert_eq!(Arc::strong_count(&counter), THREADS * (PUSHES / 10) + 1);
+        drop(vec);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+}
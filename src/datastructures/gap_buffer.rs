@@ -0,0 +1,429 @@
+use std::{
+    fmt,
+    iter::FusedIterator,
+    ops::{Index, IndexMut},
+    ptr, slice,
+};
+
+use crate::datastructures::{Allocator, Global, RawVec};
+
+/// A buffer with a gap at the cursor, for editing sequences in place.
+///
+/// The elements before the cursor sit at the start of the buffer and the ones after it at the
+/// end, with the free capacity in between. Inserting or removing at the cursor is amortized
+/// O(1), moving the cursor is O(distance).
+pub struct GapBuffer<T, A: Allocator = Global> {
+    buf: RawVec<T, A>,
+    /// The cursor, and the number of elements before it
+    gap_start: usize,
+    /// Where the elements after the cursor start
+    gap_end: usize,
+}
+
+impl<T> GapBuffer<T> {
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, A: Allocator> GapBuffer<T, A> {
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        let buf = RawVec::with_capacity_in(capacity, alloc);
+        Self {
+            gap_start: 0,
+            gap_end: buf.capacity(),
+            buf,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.capacity() - (self.gap_end - self.gap_start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The position of the cursor, between `cursor - 1` and `cursor`.
+    pub fn cursor(&self) -> usize {
+        self.gap_start
+    }
+
+    /// Moves the cursor in front of the element at `position`, or to the end.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position > len`.
+    pub fn set_cursor(&mut self, position: usize) {
+        let len = self.len();
+        assert!(
+            position <= len,
+            "cursor (is {position}) should be <= len (is {len})"
+        );
+
+        let p = self.buf.ptr();
+        if position < self.gap_start {
+            // The elements between move from before the gap to after it
+            let count = self.gap_start - position;
+            unsafe { ptr::copy(p.add(position), p.add(self.gap_end - count), count) };
+            self.gap_start -= count;
+            self.gap_end -= count;
+        } else {
+            let count = position - self.gap_start;
+            unsafe { ptr::copy(p.add(self.gap_end), p.add(self.gap_start), count) };
+            self.gap_start += count;
+            self.gap_end += count;
+        }
+    }
+
+    /// Moves the cursor back by one, returns whether it moved.
+    pub fn move_left(&mut self) -> bool {
+        let moved = self.gap_start > 0;
+        if moved {
+            self.set_cursor(self.gap_start - 1);
+        }
+        moved
+    }
+
+    /// Moves the cursor forward by one, returns whether it moved.
+    pub fn move_right(&mut self) -> bool {
+        let moved = self.gap_start < self.len();
+        if moved {
+            self.set_cursor(self.gap_start + 1);
+        }
+        moved
+    }
+
+    /// Inserts `elem` at the cursor and moves the cursor past it.
+    pub fn insert(&mut self, elem: T) {
+        if self.gap_start == self.gap_end {
+            self.reserve(1);
+        }
+
+        unsafe { self.buf.ptr().add(self.gap_start).write(elem) };
+        self.gap_start += 1;
+    }
+
+    /// Removes the element before the cursor, like backspace.
+    pub fn remove_before(&mut self) -> Option<T> {
+        if self.gap_start == 0 {
+            return None;
+        }
+
+        self.gap_start -= 1;
+        Some(unsafe { self.buf.ptr().add(self.gap_start).read() })
+    }
+
+    /// Removes the element after the cursor, like delete.
+    pub fn remove_after(&mut self) -> Option<T> {
+        if self.gap_end == self.capacity() {
+            return None;
+        }
+
+        self.gap_end += 1;
+        Some(unsafe { self.buf.ptr().add(self.gap_end - 1).read() })
+    }
+
+    /// Makes room for at least `additional` more elements.
+    pub fn reserve(&mut self, additional: usize) {
+        let old_cap = self.capacity();
+        self.buf.reserve(self.len(), additional);
+
+        // The elements after the gap move to the end of the new buffer
+        let after = old_cap - self.gap_end;
+        let new_end = self.capacity() - after;
+        let p = self.buf.ptr();
+        unsafe { ptr::copy(p.add(self.gap_end), p.add(new_end), after) };
+        self.gap_end = new_end;
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        (index < self.len()).then(|| unsafe { &*self.slot(index) })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        (index < self.len()).then(|| unsafe { &mut *self.slot(index) })
+    }
+
+    /// Returns the slot of the element at `index`, skipping the gap.
+    fn slot(&self, index: usize) -> *mut T {
+        let physical = if index < self.gap_start {
+            index
+        } else {
+            index + (self.gap_end - self.gap_start)
+        };
+        unsafe { self.buf.ptr().add(physical) }
+    }
+
+    /// The elements before and after the cursor.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let p = self.buf.ptr();
+        unsafe {
+            (
+                slice::from_raw_parts(p, self.gap_start),
+                slice::from_raw_parts(p.add(self.gap_end), self.capacity() - self.gap_end),
+            )
+        }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let p = self.buf.ptr();
+        unsafe {
+            (
+                slice::from_raw_parts_mut(p, self.gap_start),
+                slice::from_raw_parts_mut(p.add(self.gap_end), self.capacity() - self.gap_end),
+            )
+        }
+    }
+
+    pub fn clear(&mut self) {
+        let (before, after) = self.as_mut_slices();
+        let (before, after): (*mut [T], *mut [T]) = (before, after);
+        // Widen the gap over everything first, whatever lies outside it counts as initialized
+        self.gap_start = 0;
+        self.gap_end = self.capacity();
+        unsafe {
+            let _after = DropSlice(after);
+            ptr::drop_in_place(before);
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (before, after) = self.as_slices();
+        Iter {
+            before: before.iter(),
+            after: after.iter(),
+        }
+    }
+}
+
+/// Drops the part after the gap even if dropping the part before it panics
+struct DropSlice<T>(*mut [T]);
+
+impl<T> Drop for DropSlice<T> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.0) }
+    }
+}
+
+impl<T, A: Allocator> Drop for GapBuffer<T, A> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for GapBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for GapBuffer<T> {
+    fn clone(&self) -> Self {
+        let mut buffer = Self::with_capacity(self.len());
+        buffer.extend(self.iter().cloned());
+        buffer.set_cursor(self.cursor());
+        buffer
+    }
+}
+
+impl<T: PartialEq, A: Allocator> PartialEq for GapBuffer<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for GapBuffer<T, A> {}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for GapBuffer<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, A: Allocator> Index<usize> for GapBuffer<T, A> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T, A: Allocator> IndexMut<usize> for GapBuffer<T, A> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
+
+/// Inserts the elements at the cursor, leaving the cursor after them.
+impl<T, A: Allocator> Extend<T> for GapBuffer<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|elem| self.insert(elem));
+    }
+}
+
+impl<T> FromIterator<T> for GapBuffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut buffer = Self::new();
+        buffer.extend(iter);
+        buffer
+    }
+}
+
+impl<'b, T, A: Allocator> IntoIterator for &'b GapBuffer<T, A> {
+    type Item = &'b T;
+    type IntoIter = Iter<'b, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over references to the elements of a [`GapBuffer`], front to back
+#[derive(Clone)]
+pub struct Iter<'b, T> {
+    before: slice::Iter<'b, T>,
+    after: slice::Iter<'b, T>,
+}
+
+impl<'b, T> Iterator for Iter<'b, T> {
+    type Item = &'b T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.before.next().or_else(|| self.after.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.before.len() + self.after.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.after.next_back().or_else(|| self.before.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn edit_at_cursor() {
+        let mut text: GapBuffer<char> = "A Game of Thrones".chars().collect();
+        assert_eq!(text.cursor(), 17);
+
+        text.set_cursor(2);
+        "Clash of Kings".chars().for_each(|c| text.insert(c));
+        while text.remove_after().is_some() {}
+        assert_eq!(text.iter().collect::<String>(), "A Clash of Kings");
+        assert_eq!(text.cursor(), 16);
+        assert_eq!(text.remove_after(), None);
+
+        assert!(text.move_left());
+        assert_eq!(text.remove_before(), Some('g'));
+        assert_eq!(text.as_slices().1, ['s']);
+        text.set_cursor(0);
+        assert!(!text.move_left());
+        assert_eq!(text.remove_before(), None);
+        assert_eq!(text[2], 'C');
+        assert_eq!(text.iter().next_back(), Some(&'s'));
+    }
+
+    #[test]
+    fn grows_around_the_gap() {
+        let mut buffer = GapBuffer::with_capacity(4);
+        buffer.extend([1, 2, 3, 4]);
+        buffer.set_cursor(1);
+        buffer.insert(10);
+        assert!(buffer.capacity() > 4);
+        assert!(buffer.iter().copied().eq([1, 10, 2, 3, 4]));
+        assert_eq!(format!("{buffer:?}"), "[1, 10, 2, 3, 4]");
+        assert_eq!(buffer.clone(), buffer);
+    }
+
+    #[test]
+    #[should_panic(expected = "cursor (is 4) should be <= len (is 3)")]
+    fn cursor_out_of_bounds() {
+        GapBuffer::from_iter([1, 2, 3]).set_cursor(4);
+    }
+
+    #[test]
+    fn drops_elements() {
+        let book = Rc::new("A Dance with Dragons");
+        let mut buffer: GapBuffer<_> = (0..10).map(|_| Rc::clone(&book)).collect();
+        buffer.set_cursor(4);
+        drop(buffer.remove_before());
+        drop(buffer.remove_after());
+        assert_eq!(Rc::strong_count(&book), 9);
+        drop(buffer);
+        assert_eq!(Rc::strong_count(&book), 1);
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut buffer = GapBuffer::new();
+        (0..5).for_each(|_| buffer.insert(()));
+        buffer.set_cursor(2);
+        assert_eq!(buffer.remove_after(), Some(()));
+        assert_eq!(buffer.len(), 4);
+    }
+
+    #[test]
+    fn matches_vec() {
+        let mut rng = StdRng::seed_from_u64(47);
+        let mut buffer = GapBuffer::new();
+        let (mut expected, mut cursor) = (Vec::new(), 0);
+
+        for _ in 0..10_000 {
+            match rng.gen_range(0..8) {
+                0..=3 => {
+                    let elem = rng.gen::<u32>();
+                    buffer.insert(elem);
+                    expected.insert(cursor, elem);
+                    cursor += 1;
+                }
+                4 => {
+                    let removed = (cursor > 0).then(|| {
+                        cursor -= 1;
+                        expected.remove(cursor)
+                    });
+                    assert_eq!(buffer.remove_before(), removed);
+                }
+                5 => {
+                    let removed = (cursor < expected.len()).then(|| expected.remove(cursor));
+                    assert_eq!(buffer.remove_after(), removed);
+                }
+                _ => {
+                    cursor = rng.gen_range(0..=expected.len());
+                    buffer.set_cursor(cursor);
+                }
+            }
+            assert_eq!(buffer.len(), expected.len());
+        }
+
+        assert!(buffer.iter().eq(expected.iter()));
+        assert_eq!(buffer.as_slices().0, &expected[..cursor]);
+    }
+}
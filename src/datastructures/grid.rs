@@ -0,0 +1,781 @@
+use std::{
+    fmt,
+    iter::FusedIterator,
+    mem,
+    ops::{Index, IndexMut, Range},
+    slice,
+};
+
+use crate::datastructures::Vector;
+
+/// A two dimensional grid, stored row by row in one [`Vector`].
+///
+/// Cells are addressed as `(row, column)`, with `(0, 0)` in the top left corner.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct Grid<T> {
+    cells: Vector<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid with the result of `f(row, column)` in every cell.
+    pub fn from_fn<F>(rows: usize, cols: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> T,
+    {
+        let mut cells = Vector::with_capacity(Self::area(rows, cols));
+        for row in 0..rows {
+            cells.extend((0..cols).map(|col| f(row, col)));
+        }
+        Self { cells, rows, cols }
+    }
+
+    /// Wraps `cells`, which hold the grid row by row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are not exactly `rows * cols` cells.
+    pub fn from_vector(rows: usize, cols: usize, cells: Vector<T>) -> Self {
+        let (len, area) = (cells.len(), Self::area(rows, cols));
+        assert_eq!(len, area, "{len} cells do not make a {rows}x{cols} grid");
+        Self { cells, rows, cols }
+    }
+
+    pub fn into_vector(self) -> Vector<T> {
+        self.cells
+    }
+
+    fn area(rows: usize, cols: usize) -> usize {
+        rows.checked_mul(cols).expect("grid size overflow")
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The number of cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.contains(row, col)
+            .then(|| &self.cells[row * self.cols + col])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.contains(row, col)
+            .then(|| &mut self.cells[row * self.cols + col])
+    }
+
+    /// The cells of row `row`, left to right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is out of bounds.
+    pub fn row(&self, row: usize) -> &[T] {
+        self.check_row(row);
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `row` is out of bounds.
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        self.check_row(row);
+        &mut self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// The cells of column `col`, top to bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `col` is out of bounds.
+    pub fn column(&self, col: usize) -> Column<'_, T> {
+        let cols = self.cols;
+        assert!(col < cols, "column (is {col}) should be < cols (is {cols})");
+        Column {
+            cells: &self.cells,
+            cols,
+            col,
+            rows: 0..self.rows,
+        }
+    }
+
+    /// Iterates over the rows, top to bottom.
+    pub fn iter_rows(&self) -> Rows<'_, T> {
+        Rows {
+            grid: self,
+            rows: 0..self.rows,
+        }
+    }
+
+    /// Iterates over the columns, left to right.
+    pub fn iter_columns(&self) -> impl ExactSizeIterator<Item = Column<'_, T>> {
+        (0..self.cols).map(|col| self.column(col))
+    }
+
+    /// Iterates over the cells row by row.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// Iterates over the cells row by row, with their positions.
+    pub fn indexed_iter(&self) -> impl ExactSizeIterator<Item = ((usize, usize), &T)> {
+        let cols = self.cols;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i / cols, i % cols), cell))
+    }
+
+    /// The positions above, below, left and right of `(row, col)` that are in the grid.
+    pub fn neighbors4(&self, row: usize, col: usize) -> Neighbors {
+        const OFFSETS: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+        self.neighbors(row, col, &OFFSETS)
+    }
+
+    /// Like [`neighbors4`](Self::neighbors4), but with the diagonals too.
+    pub fn neighbors8(&self, row: usize, col: usize) -> Neighbors {
+        const OFFSETS: [(isize, isize); 8] = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ];
+        self.neighbors(row, col, &OFFSETS)
+    }
+
+    fn neighbors(&self, row: usize, col: usize, offsets: &'static [(isize, isize)]) -> Neighbors {
+        Neighbors {
+            center: (row, col),
+            size: (self.rows, self.cols),
+            offsets: offsets.iter(),
+        }
+    }
+
+    /// A view of the cells in `rows` and `cols`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ranges reach past the grid.
+    pub fn view(&self, rows: Range<usize>, cols: Range<usize>) -> View<'_, T> {
+        self.check_view(&rows, &cols);
+        View {
+            grid: self,
+            rows,
+            cols,
+        }
+    }
+
+    /// A mutable view of the cells in `rows` and `cols`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ranges reach past the grid.
+    pub fn view_mut(&mut self, rows: Range<usize>, cols: Range<usize>) -> ViewMut<'_, T> {
+        self.check_view(&rows, &cols);
+        ViewMut {
+            grid: self,
+            rows,
+            cols,
+        }
+    }
+
+    /// Appends a row at the bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row doesn't have `cols` cells, unless the grid is empty and sets its
+    /// width from it.
+    pub fn push_row<I: IntoIterator<Item = T>>(&mut self, row: I) {
+        // Collected first, so a bad row or a panicking iterator leaves the grid as it was
+        let mut row: Vector<T> = row.into_iter().collect();
+        let len = row.len();
+        let cols = if self.rows == 0 { len } else { self.cols };
+        assert_eq!(
+            len, cols,
+            "row of {len} cells in a grid with {cols} columns"
+        );
+
+        self.cells.append(&mut row);
+        self.cols = cols;
+        self.rows += 1;
+    }
+
+    /// Flips the grid upside down, in place.
+    pub fn flip_vertical(&mut self) {
+        for row in 0..self.rows / 2 {
+            let (top, bottom) = self.cells.split_at_mut((self.rows - 1 - row) * self.cols);
+            top[row * self.cols..(row + 1) * self.cols].swap_with_slice(&mut bottom[..self.cols]);
+        }
+    }
+
+    /// Mirrors the grid left to right, in place.
+    pub fn flip_horizontal(&mut self) {
+        for row in 0..self.rows {
+            self.row_mut(row).reverse();
+        }
+    }
+
+    /// Turns the grid upside down, in place.
+    pub fn rotate_180(&mut self) {
+        self.cells.reverse();
+    }
+
+    /// Changes the size to `rows` by `cols`, keeping the cells that still fit where they are
+    /// and filling the new ones with `value`.
+    pub fn resize(&mut self, rows: usize, cols: usize, value: T)
+    where
+        T: Clone,
+    {
+        let area = Self::area(rows, cols);
+        if cols == self.cols {
+            self.cells.resize(area, value);
+            self.rows = rows;
+            return;
+        }
+
+        let old_cols = self.cols;
+        let mut old = mem::replace(&mut self.cells, Vector::with_capacity(area)).into_iter();
+        for _ in 0..rows.min(self.rows) {
+            self.cells.extend(old.by_ref().take(old_cols).take(cols));
+            // `take` stops early when shrinking, skip the rest of the row
+            old.by_ref()
+                .take(old_cols.saturating_sub(cols))
+                .for_each(drop);
+            self.cells.resize(
+                self.cells.len() + cols.saturating_sub(old_cols),
+                value.clone(),
+            );
+        }
+        self.cells.resize(area, value);
+        self.rows = rows;
+        self.cols = cols;
+    }
+
+    fn check_row(&self, row: usize) {
+        let rows = self.rows;
+        assert!(row < rows, "row (is {row}) should be < rows (is {rows})");
+    }
+
+    fn check_view(&self, rows: &Range<usize>, cols: &Range<usize>) {
+        assert!(
+            rows.start <= rows.end && rows.end <= self.rows,
+            "rows {rows:?} out of bounds for {} rows",
+            self.rows
+        );
+        assert!(
+            cols.start <= cols.end && cols.end <= self.cols,
+            "columns {cols:?} out of bounds for {} columns",
+            self.cols
+        );
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with `value` in every cell.
+    pub fn filled(rows: usize, cols: usize, value: T) -> Self {
+        let mut cells = Vector::with_capacity(Self::area(rows, cols));
+        cells.resize(rows * cols, value);
+        Self { cells, rows, cols }
+    }
+
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.cols, self.rows, |row, col| self[(col, row)].clone())
+    }
+
+    /// Turns the grid a quarter clockwise.
+    pub fn rotate_clockwise(&self) -> Self {
+        Self::from_fn(self.cols, self.rows, |row, col| {
+            self[(self.rows - 1 - col, row)].clone()
+        })
+    }
+
+    /// Turns the grid a quarter counterclockwise.
+    pub fn rotate_counterclockwise(&self) -> Self {
+        Self::from_fn(self.cols, self.rows, |row, col| {
+            self[(col, self.cols - 1 - row)].clone()
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        self.get(row, col).expect("Out of bounds access")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(row, col).expect("Out of bounds access")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter_rows()).finish()
+    }
+}
+
+/// Prints one row per line with the columns aligned, for small grids.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vector<String> = self.cells.iter().map(|cell| cell.to_string()).collect();
+        let widths: Vector<usize> = (0..self.cols)
+            .map(|col| {
+                (0..self.rows)
+                    .map(|row| cells[row * self.cols + col].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        for row in 0..self.rows {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for (col, width) in widths.iter().enumerate() {
+                let separator = if col > 0 { " " } else { "" };
+                write!(f, "{separator}{:>width$}", cells[row * self.cols + col])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'g, T> IntoIterator for &'g Grid<T> {
+    type Item = &'g T;
+    type IntoIter = slice::Iter<'g, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'g, T> IntoIterator for &'g mut Grid<T> {
+    type Item = &'g mut T;
+    type IntoIter = slice::IterMut<'g, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the rows of a [`Grid`], top to bottom
+#[derive(Clone)]
+pub struct Rows<'g, T> {
+    grid: &'g Grid<T>,
+    rows: Range<usize>,
+}
+
+impl<'g, T> Iterator for Rows<'g, T> {
+    type Item = &'g [T];
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next().map(|row| self.grid.row(row))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Rows<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rows.next_back().map(|row| self.grid.row(row))
+    }
+}
+
+impl<T> ExactSizeIterator for Rows<'_, T> {}
+impl<T> FusedIterator for Rows<'_, T> {}
+
+/// An iterator over the cells of a column of a [`Grid`], top to bottom
+#[derive(Clone)]
+pub struct Column<'g, T> {
+    cells: &'g [T],
+    cols: usize,
+    col: usize,
+    rows: Range<usize>,
+}
+
+impl<'g, T> Iterator for Column<'g, T> {
+    type Item = &'g T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows
+            .next()
+            .map(|row| &self.cells[row * self.cols + self.col])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Column<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rows
+            .next_back()
+            .map(|row| &self.cells[row * self.cols + self.col])
+    }
+}
+
+impl<T> ExactSizeIterator for Column<'_, T> {}
+impl<T> FusedIterator for Column<'_, T> {}
+
+/// An iterator over the positions next to a cell that are inside a [`Grid`]
+#[derive(Clone)]
+pub struct Neighbors {
+    center: (usize, usize),
+    size: (usize, usize),
+    offsets: slice::Iter<'static, (isize, isize)>,
+}
+
+impl Iterator for Neighbors {
+    type Item = (usize, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.offsets.find_map(|&(dr, dc)| {
+            let row = self.center.0.checked_add_signed(dr)?;
+            let col = self.center.1.checked_add_signed(dc)?;
+            (row < self.size.0 && col < self.size.1).then_some((row, col))
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.offsets.len()))
+    }
+}
+
+impl FusedIterator for Neighbors {}
+
+/// A rectangular part of a [`Grid`], with positions relative to its top left corner
+#[derive(Clone)]
+pub struct View<'g, T> {
+    grid: &'g Grid<T>,
+    rows: Range<usize>,
+    cols: Range<usize>,
+}
+
+impl<'g, T> View<'g, T> {
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols.len()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&'g T> {
+        (row < self.rows() && col < self.cols())
+            .then(|| &self.grid[(self.rows.start + row, self.cols.start + col)])
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `row` is out of bounds.
+    pub fn row(&self, row: usize) -> &'g [T] {
+        let rows = self.rows();
+        assert!(row < rows, "row (is {row}) should be < rows (is {rows})");
+        &self.grid.row(self.rows.start + row)[self.cols.clone()]
+    }
+
+    /// Iterates over the rows of the view, top to bottom.
+    pub fn iter_rows(&self) -> impl ExactSizeIterator<Item = &'g [T]> + '_ {
+        (0..self.rows()).map(|row| self.row(row))
+    }
+
+    /// Copies the view into a grid of its own.
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.rows(), self.cols(), |row, col| {
+            self[(row, col)].clone()
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for View<'_, T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        self.get(row, col).expect("Out of bounds access")
+    }
+}
+
+/// A mutable rectangular part of a [`Grid`], see [`View`]
+pub struct ViewMut<'g, T> {
+    grid: &'g mut Grid<T>,
+    rows: Range<usize>,
+    cols: Range<usize>,
+}
+
+impl<T> ViewMut<'_, T> {
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols.len()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        (row < self.rows() && col < self.cols())
+            .then(|| &self.grid[(self.rows.start + row, self.cols.start + col)])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        (row < self.rows() && col < self.cols())
+            .then(|| &mut self.grid[(self.rows.start + row, self.cols.start + col)])
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `row` is out of bounds.
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        let rows = self.rows();
+        assert!(row < rows, "row (is {row}) should be < rows (is {rows})");
+        &mut self.grid.row_mut(self.rows.start + row)[self.cols.clone()]
+    }
+
+    /// Sets every cell of the view to `value`.
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for row in 0..self.rows() {
+            self.row_mut(row).fill(value.clone());
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for ViewMut<'_, T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        self.get(row, col).expect("Out of bounds access")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for ViewMut<'_, T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(row, col).expect("Out of bounds access")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::*;
+    use crate::vector;
+
+    /// 0 1 2 3
+    /// 4 5 6 7
+    /// 8 9 10 11
+    fn numbers() -> Grid<usize> {
+        Grid::from_fn(3, 4, |row, col| row * 4 + col)
+    }
+
+    #[test]
+    fn index_rows_and_columns() {
+        let mut grid = numbers();
+        assert_eq!((grid.rows(), grid.cols(), grid.len()), (3, 4, 12));
+        assert_eq!(grid[(1, 2)], 6);
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 4), None);
+
+        assert_eq!(grid.row(2), [8, 9, 10, 11]);
+        assert!(grid.column(1).copied().eq([1, 5, 9]));
+        assert!(grid.column(3).rev().copied().eq([11, 7, 3]));
+        assert_eq!(grid.iter_rows().len(), 3);
+        assert_eq!(
+            grid.iter_columns()
+                .map(|c| c.sum::<usize>())
+                .collect::<Vec<_>>(),
+            [12, 15, 18, 21]
+        );
+        assert_eq!(grid.indexed_iter().nth(6), Some(((1, 2), &6)));
+
+        grid[(0, 0)] = 100;
+        grid.row_mut(1).fill(0);
+        assert_eq!(
+            grid.iter().sum::<usize>(),
+            100 + 1 + 2 + 3 + 8 + 9 + 10 + 11
+        );
+        assert_eq!(
+            format!("{:?}", Grid::from_fn(2, 2, |r, c| r + c)),
+            "[[0, 1], [1, 2]]"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Out of bounds access")]
+    fn index_out_of_bounds() {
+        let _ = numbers()[(0, 4)];
+    }
+
+    #[test]
+    #[should_panic(expected = "5 cells do not make a 2x3 grid")]
+    fn wrong_number_of_cells() {
+        Grid::from_vector(2, 3, vector![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn transpose_and_rotate() {
+        let grid = numbers();
+        let transposed = grid.transpose();
+        assert_eq!((transposed.rows(), transposed.cols()), (4, 3));
+        assert_eq!(transposed.row(1), [1, 5, 9]);
+        assert_eq!(transposed.transpose(), grid);
+
+        let clockwise = grid.rotate_clockwise();
+        assert_eq!(clockwise.row(0), [8, 4, 0]);
+        assert_eq!(clockwise.row(3), [11, 7, 3]);
+        assert_eq!(clockwise.rotate_counterclockwise(), grid);
+
+        // Four quarter turns, or two and a half one, make a full one
+        let mut turned = grid.clone();
+        (0..4).for_each(|_| turned = turned.rotate_clockwise());
+        assert_eq!(turned, grid);
+        let mut half = grid.clone();
+        half.rotate_180();
+        assert_eq!(half, clockwise.rotate_clockwise());
+
+        // A transpose is a quarter turn and a mirror
+        let mut mirrored = grid.rotate_clockwise();
+        mirrored.flip_horizontal();
+        assert_eq!(mirrored, transposed);
+        let mut flipped = grid.clone();
+        flipped.flip_vertical();
+        assert!(flipped.column(0).copied().eq([8, 4, 0]));
+    }
+
+    #[test]
+    fn views() {
+        let mut grid = numbers();
+        let view = grid.view(1..3, 1..4);
+        assert_eq!((view.rows(), view.cols()), (2, 3));
+        assert_eq!(view[(0, 0)], 5);
+        assert_eq!(view.get(2, 0), None);
+        assert_eq!(view.row(1), [9, 10, 11]);
+        assert_eq!(
+            view.iter_rows().flatten().sum::<usize>(),
+            5 + 6 + 7 + 9 + 10 + 11
+        );
+        assert_eq!(
+            view.to_grid(),
+            Grid::from_vector(2, 3, vector![5, 6, 7, 9, 10, 11])
+        );
+
+        let mut view = grid.view_mut(0..2, 2..4);
+        view[(1, 1)] = 0;
+        view.row_mut(0).fill(1);
+        assert_eq!(grid.row(0), [0, 1, 1, 1]);
+        assert_eq!(grid.row(1), [4, 5, 6, 0]);
+
+        grid.view_mut(1..1, 0..4).fill(7);
+        assert!(grid.view(0..3, 2..2).iter_rows().all(<[usize]>::is_empty));
+    }
+
+    #[test]
+    #[should_panic(expected = "columns 2..5 out of bounds for 4 columns")]
+    fn view_out_of_bounds() {
+        numbers().view(0..1, 2..5);
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = numbers();
+        let collect = |it: Neighbors| it.collect::<Vec<_>>();
+
+        assert_eq!(collect(grid.neighbors4(0, 0)), [(0, 1), (1, 0)]);
+        assert_eq!(
+            collect(grid.neighbors4(1, 1)),
+            [(0, 1), (1, 0), (1, 2), (2, 1)]
+        );
+        assert_eq!(collect(grid.neighbors4(2, 3)), [(1, 3), (2, 2)]);
+        assert_eq!(collect(grid.neighbors8(0, 0)), [(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(grid.neighbors8(1, 1).count(), 8);
+        assert_eq!(grid.neighbors8(0, 2).count(), 5);
+        assert_eq!(Grid::filled(1, 1, 0).neighbors8(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn resize() {
+        let mut grid = numbers();
+        grid.resize(4, 4, 0);
+        assert_eq!(grid.row(3), [0; 4]);
+        assert_eq!(grid.row(2), [8, 9, 10, 11]);
+
+        grid.resize(2, 2, 0);
+        assert_eq!(grid, Grid::from_vector(2, 2, vector![0, 1, 4, 5]));
+
+        grid.resize(3, 5, 9);
+        assert_eq!(grid.row(0), [0, 1, 9, 9, 9]);
+        assert_eq!(grid.row(1), [4, 5, 9, 9, 9]);
+        assert_eq!(grid.row(2), [9; 5]);
+
+        grid.resize(0, 3, 0);
+        assert!(grid.is_empty());
+        grid.push_row([1, 2, 3]);
+        assert_eq!((grid.rows(), grid.cols()), (1, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "row of 2 cells in a grid with 3 columns")]
+    fn push_short_row() {
+        let mut grid = Grid::filled(1, 3, 0);
+        grid.push_row([1, 2]);
+    }
+
+    #[test]
+    fn failed_push_leaves_grid_unchanged() {
+        let mut grid = Grid::filled(1, 3, 0);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| grid.push_row([1, 2, 3, 4])));
+        assert!(result.is_err());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            grid.push_row((1..=3).inspect(|&n| assert!(n < 3)))
+        }));
+        assert!(result.is_err());
+        assert_eq!(grid, Grid::filled(1, 3, 0));
+
+        grid.push_row([1, 2, 3]);
+        assert_eq!((grid.rows(), grid.cols()), (2, 3));
+        assert_eq!(grid.row(1), [1, 2, 3]);
+    }
+
+    #[test]
+    fn display() {
+        let mut grid = Grid::from_fn(3, 3, |row, col| row * 3 + col);
+        grid[(1, 1)] = 1000;
+        assert_eq!(grid.to_string(), "0    1 2\n3 1000 5\n6    7 8");
+
+        let map: Grid<char> = Grid::from_vector(2, 3, "#.#..#".chars().collect());
+        assert_eq!(map.to_string(), "# . #\n. . #");
+        assert_eq!(Grid::<u8>::default().to_string(), "");
+    }
+}
@@ -0,0 +1,683 @@
+use std::{
+    fs::{File, OpenOptions},
+    hash::Hasher,
+    io::{self, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use super::StableHasher;
+
+pub const PAGE_SIZE: usize = 4096;
+
+const MAGIC: &[u8; 8] = b"DSAEXTH1";
+const HASH_SEED: u64 = 0x6578_7468_6173_6831;
+/// Caps the directory at 64 MiB, past this the keys are colliding on purpose, not by bad luck
+const MAX_GLOBAL_DEPTH: u32 = 24;
+const BUCKET_HEADER: usize = 8;
+const RECORD_HEADER: usize = 4;
+const DIR_ENTRIES_PER_PAGE: usize = PAGE_SIZE / 4;
+
+/// The largest `key.len() + value.len()` that fits in a page.
+pub const MAX_RECORD: usize = PAGE_SIZE - BUCKET_HEADER - RECORD_HEADER;
+
+/// Page 0 of the file
+///
+/// ```text
+/// magic[8] | global_depth u32 | page_count u32 | dir_page u32 | dir_pages u32 | len u64
+/// ```
+#[derive(Clone, Copy)]
+struct Header {
+    global_depth: u32,
+    page_count: u32,
+    dir_page: u32,
+    dir_pages: u32,
+    len: u64,
+}
+
+impl Header {
+    fn encode(&self) -> Vec<u8> {
+        let mut page = vec![0; PAGE_SIZE];
+        page[0..8].copy_from_slice(MAGIC);
+        page[8..12].copy_from_slice(&self.global_depth.to_le_bytes());
+        page[12..16].copy_from_slice(&self.page_count.to_le_bytes());
+        page[16..20].copy_from_slice(&self.dir_page.to_le_bytes());
+        page[20..24].copy_from_slice(&self.dir_pages.to_le_bytes());
+        page[24..32].copy_from_slice(&self.len.to_le_bytes());
+        page
+    }
+
+    fn decode(page: &[u8]) -> io::Result<Self> {
+        if &page[0..8] != MAGIC {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Not a DiskHashMap file",
+            ));
+        }
+
+        let global_depth = u32_at(page, 8);
+        if global_depth > MAX_GLOBAL_DEPTH {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Corrupt header page",
+            ));
+        }
+
+        Ok(Self {
+            global_depth,
+            page_count: u32_at(page, 12),
+            dir_page: u32_at(page, 16),
+            dir_pages: u32_at(page, 20),
+            len: u64::from_le_bytes(page[24..32].try_into().unwrap()),
+        })
+    }
+}
+
+/// A bucket page, decoded
+///
+/// ```text
+/// local_depth u32 | count u32 | (key_len u16 | value_len u16 | key | value)*
+/// ```
+struct Bucket {
+    local_depth: u32,
+    records: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Bucket {
+    fn encoded_len(&self) -> usize {
+        BUCKET_HEADER
+            + self
+                .records
+                .iter()
+                .map(|(k, v)| RECORD_HEADER + k.len() + v.len())
+                .sum::<usize>()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut page = Vec::with_capacity(PAGE_SIZE);
+        page.extend_from_slice(&self.local_depth.to_le_bytes());
+        page.extend_from_slice(&(self.records.len() as u32).to_le_bytes());
+        for (k, v) in &self.records {
+            page.extend_from_slice(&(k.len() as u16).to_le_bytes());
+            page.extend_from_slice(&(v.len() as u16).to_le_bytes());
+            page.extend_from_slice(k);
+            page.extend_from_slice(v);
+        }
+        page.resize(PAGE_SIZE, 0);
+        page
+    }
+
+    /// Decodes a bucket of a file whose directory has depth `global_depth`.
+    fn decode(page: &[u8], global_depth: u32) -> io::Result<Self> {
+        let corrupt = || io::Error::new(ErrorKind::InvalidData, "Corrupt bucket page");
+
+        let local_depth = u32_at(page, 0);
+        if local_depth > global_depth {
+            return Err(corrupt());
+        }
+        let count = u32_at(page, 4) as usize;
+        // Checked before it sizes an allocation, even empty records take a header each
+        if count > (PAGE_SIZE - BUCKET_HEADER) / RECORD_HEADER {
+            return Err(corrupt());
+        }
+        let mut records = Vec::with_capacity(count);
+        let mut at = BUCKET_HEADER;
+
+        for _ in 0..count {
+            let header = page.get(at..at + RECORD_HEADER).ok_or_else(corrupt)?;
+            let key_len = u16::from_le_bytes([header[0], header[1]]) as usize;
+            let value_len = u16::from_le_bytes([header[2], header[3]]) as usize;
+            at += RECORD_HEADER;
+
+            let key = page.get(at..at + key_len).ok_or_else(corrupt)?;
+            let value = page
+                .get(at + key_len..at + key_len + value_len)
+                .ok_or_else(corrupt)?;
+            records.push((key.to_vec(), value.to_vec()));
+            at += key_len + value_len;
+        }
+
+        Ok(Self {
+            local_depth,
+            records,
+        })
+    }
+}
+
+fn u32_at(page: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(page[at..at + 4].try_into().unwrap())
+}
+
+fn hash(key: &[u8]) -> u64 {
+    let mut hasher = StableHasher::with_seed(HASH_SEED);
+    hasher.write(key);
+    hasher.finish()
+}
+
+/// A persistent hash index of byte keys and values, stored in fixed size pages of a file.
+///
+/// It grows by extendible hashing: a directory of `2^global_depth` page numbers is indexed by the
+/// low bits of the key's hash, a full bucket is split in two by the next hash bit, and the
+/// directory doubles when a bucket already uses every bit of it.
+/// Keys are hashed with a seeded [`StableHasher`], so a file can be reopened on any platform.
+///
+/// Every change is written through to the file immediately, [`sync`](DiskHashMap::sync) only
+/// asks the OS to flush it to the disk.
+/// Buckets are never merged and pages are never reclaimed, the file only grows.
+pub struct DiskHashMap {
+    file: File,
+    header: Header,
+    directory: Vec<u32>,
+}
+
+impl DiskHashMap {
+    /// Opens the index stored at `path`, creating an empty one if the file doesn't exist or is empty.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        if file.metadata()?.len() == 0 {
+            Self::create(file)
+        } else {
+            Self::load(file)
+        }
+    }
+
+    fn create(file: File) -> io::Result<Self> {
+        let mut map = Self {
+            file,
+            header: Header {
+                global_depth: 0,
+                page_count: 3,
+                dir_page: 1,
+                dir_pages: 1,
+                len: 0,
+            },
+            directory: vec![2],
+        };
+
+        let empty = Bucket {
+            local_depth: 0,
+            records: Vec::new(),
+        };
+        map.write_bucket(2, &empty)?;
+        map.write_directory()?;
+        map.write_header()?;
+        Ok(map)
+    }
+
+    fn load(mut file: File) -> io::Result<Self> {
+        let mut page = vec![0; PAGE_SIZE];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut page)?;
+        let header = Header::decode(&page)?;
+        let corrupt = || io::Error::new(ErrorKind::InvalidData, "Corrupt directory");
+
+        // The directory must hold every entry and lie within the allocated pages
+        let entries = 1usize << header.global_depth;
+        let dir_end = header.dir_page as u64 + header.dir_pages as u64;
+        if (header.dir_pages as usize) < entries.div_ceil(DIR_ENTRIES_PER_PAGE)
+            || header.dir_page == 0
+            || dir_end > header.page_count as u64
+        {
+            return Err(corrupt());
+        }
+
+        let mut raw = vec![0; entries.div_ceil(DIR_ENTRIES_PER_PAGE) * PAGE_SIZE];
+        file.seek(SeekFrom::Start(header.dir_page as u64 * PAGE_SIZE as u64))?;
+        file.read_exact(&mut raw)?;
+        let directory: Vec<u32> = raw
+            .chunks_exact(4)
+            .take(entries)
+            .map(|entry| u32_at(entry, 0))
+            .collect();
+
+        // Every entry must name a bucket page, not the header, the directory or past the end
+        let is_bucket = |page: u32| {
+            page != 0
+                && page < header.page_count
+                && !(header.dir_page..dir_end as u32).contains(&page)
+        };
+        if !directory.iter().all(|&page| is_bucket(page)) {
+            return Err(corrupt());
+        }
+
+        Ok(Self {
+            file,
+            header,
+            directory,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.header.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.header.len == 0
+    }
+
+    pub fn global_depth(&self) -> u32 {
+        self.header.global_depth
+    }
+
+    /// Flushes every write to the disk.
+    pub fn sync(&self) -> io::Result<()> {
+        self.file.sync_all()
+    }
+
+    pub fn get(&mut self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        let page = self.directory[self.dir_index(hash(key))];
+        let bucket = self.read_bucket(page)?;
+
+        Ok(bucket
+            .records
+            .into_iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v))
+    }
+
+    pub fn contains_key(&mut self, key: &[u8]) -> io::Result<bool> {
+        self.get(key).map(|v| v.is_some())
+    }
+
+    /// Inserts a record, returning the previous value of `key`.
+    ///
+    /// Fails with [`ErrorKind::InvalidInput`] if the record is larger than [`MAX_RECORD`] bytes.
+    pub fn insert(&mut self, key: &[u8], value: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        if key.len() + value.len() > MAX_RECORD {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "Record does not fit in a page",
+            ));
+        }
+
+        let hash = hash(key);
+
+        loop {
+            let page = self.directory[self.dir_index(hash)];
+            let mut bucket = self.read_bucket(page)?;
+
+            let prev = match bucket.records.iter().position(|(k, _)| k == key) {
+                Some(pos) => Some(std::mem::replace(
+                    &mut bucket.records[pos].1,
+                    value.to_vec(),
+                )),
+                None => {
+                    bucket.records.push((key.to_vec(), value.to_vec()));
+                    None
+                }
+            };
+
+            if bucket.encoded_len() <= PAGE_SIZE {
+                self.write_bucket(page, &bucket)?;
+                if prev.is_none() {
+                    self.header.len += 1;
+                    self.write_header()?;
+                }
+                return Ok(prev);
+            }
+
+            // Doesn't fit, split the bucket as it is on disk and try again
+            self.split(page)?;
+        }
+    }
+
+    pub fn remove(&mut self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        let page = self.directory[self.dir_index(hash(key))];
+        let mut bucket = self.read_bucket(page)?;
+
+        match bucket.records.iter().position(|(k, _)| k == key) {
+            Some(pos) => {
+                let (_, value) = bucket.records.swap_remove(pos);
+                self.write_bucket(page, &bucket)?;
+                self.header.len -= 1;
+                self.write_header()?;
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn dir_index(&self, hash: u64) -> usize {
+        (hash & ((1u64 << self.header.global_depth) - 1)) as usize
+    }
+
+    /// Moves the records of `page` whose next hash bit is set to a new page.
+    ///
+    /// The writes are ordered so that a crash between any two of them leaves a file in which
+    /// every record can still be found:
+    ///
+    /// 1. the new page, which nothing points at yet
+    /// 2. the header with the new pages allocated, but the old directory
+    /// 3. the directory, either in place or in the pages allocated by 2.
+    /// 4. the header pointing at the new directory
+    /// 5. the old page without the moved records
+    ///
+    /// Until 5. the old page still holds copies of the moved records, but no key that hashes
+    /// to them leads there anymore. Its stale local depth only makes its next split move
+    /// nothing, after which it is split again with the right depth.
+    fn split(&mut self, page: u32) -> io::Result<()> {
+        let bucket = self.read_bucket(page)?;
+        let depth = bucket.local_depth;
+        let committed = self.header;
+
+        if depth == self.header.global_depth {
+            self.double_directory()?;
+        }
+
+        let (high, low): (Vec<_>, Vec<_>) = bucket
+            .records
+            .into_iter()
+            .partition(|(k, _)| hash(k) >> depth & 1 == 1);
+
+        let new_page = self.allocate_pages(1);
+        self.write_bucket(
+            new_page,
+            &Bucket {
+                local_depth: depth + 1,
+                records: high,
+            },
+        )?;
+        let allocated = Header {
+            page_count: self.header.page_count,
+            ..committed
+        };
+        self.write_at(0, &allocated.encode())?;
+
+        for (i, entry) in self.directory.iter_mut().enumerate() {
+            if *entry == page && i >> depth & 1 == 1 {
+                *entry = new_page;
+            }
+        }
+        self.write_directory()?;
+        self.write_header()?;
+
+        self.write_bucket(
+            page,
+            &Bucket {
+                local_depth: depth + 1,
+                records: low,
+            },
+        )
+    }
+
+    fn double_directory(&mut self) -> io::Result<()> {
+        if self.header.global_depth == MAX_GLOBAL_DEPTH {
+            return Err(io::Error::other("Too many keys with colliding hashes"));
+        }
+
+        self.directory.extend_from_within(..);
+        self.header.global_depth += 1;
+
+        let needed = self.directory.len().div_ceil(DIR_ENTRIES_PER_PAGE) as u32;
+        if needed > self.header.dir_pages {
+            // The directory moves to the end of the file, its old pages are abandoned
+            self.header.dir_page = self.allocate_pages(needed);
+            self.header.dir_pages = needed;
+        }
+
+        Ok(())
+    }
+
+    fn allocate_pages(&mut self, pages: u32) -> u32 {
+        let first = self.header.page_count;
+        self.header.page_count += pages;
+        first
+    }
+
+    fn read_bucket(&mut self, page: u32) -> io::Result<Bucket> {
+        let mut raw = vec![0; PAGE_SIZE];
+        self.file
+            .seek(SeekFrom::Start(page as u64 * PAGE_SIZE as u64))?;
+        self.file.read_exact(&mut raw)?;
+        Bucket::decode(&raw, self.header.global_depth)
+    }
+
+    fn write_bucket(&mut self, page: u32, bucket: &Bucket) -> io::Result<()> {
+        self.write_at(page as u64 * PAGE_SIZE as u64, &bucket.encode())
+    }
+
+    fn write_directory(&mut self) -> io::Result<()> {
+        let mut raw = Vec::with_capacity(self.header.dir_pages as usize * PAGE_SIZE);
+        self.directory
+            .iter()
+            .for_each(|entry| raw.extend_from_slice(&entry.to_le_bytes()));
+        raw.resize(self.header.dir_pages as usize * PAGE_SIZE, 0);
+        self.write_at(self.header.dir_page as u64 * PAGE_SIZE as u64, &raw)
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        self.write_at(0, &self.header.encode())
+    }
+
+    fn write_at(&mut self, offset: u64, bytes: &[u8]) -> io::Result<()> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        env, fs,
+        path::{Path, PathBuf},
+        process,
+    };
+
+    use super::*;
+
+    /// A file in the temp directory that is deleted when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let path = env::temp_dir().join(format!("dsa_rust_{}_{name}.db", process::id()));
+            let _ = fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl AsRef<Path> for TempFile {
+        fn as_ref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn key(i: u32) -> Vec<u8> {
+        format!("key-{i}").into_bytes()
+    }
+
+    fn value(i: u32) -> Vec<u8> {
+        format!("value of {i} ")
+            .repeat(i as usize % 7 + 1)
+            .into_bytes()
+    }
+
+    #[test]
+    fn insert_get_remove() {
+        let file = TempFile::new("insert_get_remove");
+        let mut map = DiskHashMap::open(&file).unwrap();
+
+        assert_eq!(map.get(b"A Game of Thrones").unwrap(), None);
+        assert_eq!(map.insert(b"A Game of Thrones", b"1996").unwrap(), None);
+        assert_eq!(map.insert(b"A Clash of Kings", b"1998").unwrap(), None);
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(
+            map.insert(b"A Game of Thrones", b"August 1996").unwrap(),
+            Some(b"1996".to_vec())
+        );
+        assert_eq!(
+            map.get(b"A Game of Thrones").unwrap(),
+            Some(b"August 1996".to_vec())
+        );
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(
+            map.remove(b"A Clash of Kings").unwrap(),
+            Some(b"1998".to_vec())
+        );
+        assert_eq!(map.remove(b"A Clash of Kings").unwrap(), None);
+        assert!(!map.contains_key(b"A Clash of Kings").unwrap());
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn splits_and_reopens() {
+        let file = TempFile::new("splits_and_reopens");
+
+        {
+            let mut map = DiskHashMap::open(&file).unwrap();
+            for i in 0..20_000 {
+                map.insert(&key(i), &value(i)).unwrap();
+            }
+            for i in (0..20_000).step_by(3) {
+                assert_eq!(map.remove(&key(i)).unwrap(), Some(value(i)));
+            }
+            assert!(map.global_depth() > 6);
+            map.sync().unwrap();
+        }
+
+        let mut map = DiskHashMap::open(&file).unwrap();
+        assert_eq!(map.len(), 20_000 - 6_667);
+        for i in 0..20_000 {
+            let expected = if i % 3 == 0 { None } else { Some(value(i)) };
+            assert_eq!(map.get(&key(i)).unwrap(), expected);
+        }
+
+        // Keeps working after reopening
+        map.insert(&key(0), b"back").unwrap();
+        assert_eq!(map.get(&key(0)).unwrap(), Some(b"back".to_vec()));
+    }
+
+    #[test]
+    fn large_records() {
+        let file = TempFile::new("large_records");
+        let mut map = DiskHashMap::open(&file).unwrap();
+
+        let big = vec![7; MAX_RECORD - 2];
+        assert_eq!(map.insert(b"ok", &big).unwrap(), None);
+        assert_eq!(map.insert(b"ko", &big).unwrap(), None);
+        assert_eq!(map.get(b"ok").unwrap(), Some(big.clone()));
+
+        let err = map.insert(b"too big", &big).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn split_interrupted_before_old_page() {
+        let file = TempFile::new("split_interrupted_before_old_page");
+        let mut map = DiskHashMap::open(&file).unwrap();
+
+        // Insert until a split happens, remembering the page it split
+        let mut i = 0;
+        let (page, before) = loop {
+            let page = map.directory[map.dir_index(hash(&key(i)))];
+            let page_count = map.header.page_count;
+            let before = map.read_bucket(page).unwrap().encode();
+            map.insert(&key(i), &value(i)).unwrap();
+            if map.header.page_count > page_count {
+                break (page, before);
+            }
+            i += 1;
+        };
+
+        // As if the process died before step 5, the records of the last insert may be lost
+        map.write_at(page as u64 * PAGE_SIZE as u64, &before)
+            .unwrap();
+        drop(map);
+
+        let mut map = DiskHashMap::open(&file).unwrap();
+        for j in 0..i {
+            assert_eq!(map.get(&key(j)).unwrap(), Some(value(j)));
+        }
+
+        // The stale page heals on its next split
+        for j in i..i + 2_000 {
+            map.insert(&key(j), &value(j)).unwrap();
+        }
+        for j in 0..i + 2_000 {
+            assert_eq!(map.get(&key(j)).unwrap(), Some(value(j)), "key {j}");
+        }
+    }
+
+    #[test]
+    fn rejects_corrupt_local_depth() {
+        let file = TempFile::new("rejects_corrupt_local_depth");
+        let mut map = DiskHashMap::open(&file).unwrap();
+        map.insert(b"A Storm of Swords", b"2000").unwrap();
+
+        // The only bucket claims to use more hash bits than the directory has
+        map.write_at(2 * PAGE_SIZE as u64, &64u32.to_le_bytes())
+            .unwrap();
+        drop(map);
+
+        let mut map = DiskHashMap::open(&file).unwrap();
+        let err = map.get(b"A Storm of Swords").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = map.insert(b"A Feast for Crows", b"2005").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_short_directory() {
+        let file = TempFile::new("rejects_short_directory");
+        let mut map = DiskHashMap::open(&file).unwrap();
+        map.insert(b"A Dance with Dragons", b"2011").unwrap();
+        drop(map);
+
+        let corrupt = |offset: u64, value: u32| {
+            let mut map = DiskHashMap::open(&file).unwrap();
+            let original = map.header;
+            map.write_at(offset, &value.to_le_bytes()).unwrap();
+            let err = DiskHashMap::open(&file).err().unwrap();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+            map.write_at(0, &original.encode()).unwrap();
+            map.write_directory().unwrap();
+        };
+
+        // 4096 entries in a single directory page
+        corrupt(8, 12);
+        // A directory past the allocated pages, then one covering the bucket page
+        corrupt(16, 3);
+        corrupt(20, 2);
+        // Entries pointing past the end, at the header and at the directory itself
+        corrupt(PAGE_SIZE as u64, 3);
+        corrupt(PAGE_SIZE as u64, 0);
+        corrupt(PAGE_SIZE as u64, 1);
+
+        let mut map = DiskHashMap::open(&file).unwrap();
+        assert_eq!(map.get(b"A Dance with Dragons").unwrap().unwrap(), b"2011");
+    }
+
+    #[test]
+    fn rejects_oversized_record_count() {
+        let file = TempFile::new("rejects_oversized_record_count");
+        let mut map = DiskHashMap::open(&file).unwrap();
+        map.write_at(2 * PAGE_SIZE as u64 + 4, &u32::MAX.to_le_bytes())
+            .unwrap();
+
+        let err = map.get(b"The Winds of Winter").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_foreign_file() {
+        let file = TempFile::new("rejects_foreign_file");
+        fs::write(&file, vec![1; PAGE_SIZE]).unwrap();
+
+        let err = DiskHashMap::open(&file).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
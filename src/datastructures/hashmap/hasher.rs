@@ -0,0 +1,153 @@
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hasher};
+
+/// The hasher [`HashMap::new`](super::HashMap::new) uses.
+///
+/// `DefaultHasher` is deterministic, but its algorithm may change between Rust releases.
+pub type DefaultHashBuilder = BuildHasherDefault<DefaultHasher>;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A hasher whose output only depends on the seed and the bytes written to it.
+///
+/// The algorithm is 64 bit FNV-1a, with the seed folded into the offset basis,
+/// followed by the SplitMix64 finalizer so the low bits used for bucket selection are well mixed:
+///
+/// ```text
+/// state = FNV_OFFSET_BASIS ^ seed
+/// for each byte b: state = (state ^ b) * FNV_PRIME
+/// finish = splitmix64(state)
+/// ```
+///
+/// Integers are always written in little endian byte order and `usize`/`isize` are widened to
+/// 64 bits, so the same values hash the same on every platform.
+/// This is **not** resistant to HashDoS, only use it where reproducibility matters more.
+#[derive(Debug, Clone)]
+pub struct StableHasher {
+    state: u64,
+}
+
+impl StableHasher {
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS ^ seed,
+        }
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::with_seed(0)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        let mut z = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state = (self.state ^ *byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+/// Builds [`StableHasher`]s with a fixed seed, see [`HashMap::with_seed`](super::HashMap::with_seed).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SeededState {
+    seed: u64,
+}
+
+impl SeededState {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl BuildHasher for SeededState {
+    type Hasher = StableHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        StableHasher::with_seed(self.seed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pinned_values() {
+        // These must never change, snapshot tests of seeded maps rely on them
+        let state = SeededState::new(0);
+        assert_eq!(state.hash_one(0u8), 0x71b8_262b_b6e2_e086);
+        assert_eq!(state.hash_one(42u32), 0x4e66_d39f_7ced_2bc9);
+        assert_eq!(state.hash_one(42usize), 0xa4e6_579f_d9ba_8f6d);
+        assert_eq!(state.hash_one("A Game of Thrones"), 0x3df9_4a7c_e370_f04c);
+
+        let state = SeededState::new(7);
+        assert_eq!(state.hash_one(42u32), 0xc633_043c_0087_8bf1);
+    }
+
+    #[test]
+    fn usize_hashes_like_u64() {
+        let state = SeededState::new(1);
+        assert_eq!(state.hash_one(1234usize), state.hash_one(1234u64));
+        assert_eq!(state.hash_one(-1234isize), state.hash_one(-1234i64));
+    }
+
+    #[test]
+    fn seed_changes_hash() {
+        let a = SeededState::new(1).hash_one("A Clash of Kings");
+        let b = SeededState::new(2).hash_one("A Clash of Kings");
+        assert_ne!(a, b);
+    }
+}
@@ -3,79 +3,93 @@
 use core::panic;
 use std::{
     borrow::Borrow,
-    hash::{DefaultHasher, Hash, Hasher},
+    hash::{BuildHasher, Hash},
     ops::Index,
 };
 
-const INIT_BUCKETS: usize = 4;
+use super::{DefaultHashBuilder, HashTable, SeededState};
+use crate::datastructures::{Allocator, Global};
 
-pub struct HashMap<K, V> {
-    pub(crate) buckets: Vec<Vec<(K, V)>>,
-    items: usize,
+pub struct HashMap<K, V, S = DefaultHashBuilder, A: Allocator = Global> {
+    pub(crate) table: HashTable<(K, V), A>,
+    pub(crate) hash_builder: S,
 }
 
-impl<K, V> HashMap<K, V>
-where
-    K: Eq + Hash,
-{
+impl<K, V> HashMap<K, V, DefaultHashBuilder> {
     pub fn new() -> Self {
+        Self::with_hasher(DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V, A: Allocator> HashMap<K, V, DefaultHashBuilder, A> {
+    /// Creates a map that allocates its buckets from `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_hasher_in(DefaultHashBuilder::default(), alloc)
+    }
+}
+
+impl<K, V> HashMap<K, V, SeededState> {
+    /// Creates a map hashing with a [`StableHasher`](super::StableHasher) seeded with `seed`.
+    ///
+    /// Two maps with the same seed that see the same sequence of operations have identical
+    /// bucket layouts, and so iterate in the same order, across runs and platforms.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(SeededState::new(seed))
+    }
+}
+
+impl<K, V, S> HashMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_hasher_in(hash_builder, Global)
+    }
+}
+
+impl<K, V, S, A: Allocator> HashMap<K, V, S, A> {
+    pub fn with_hasher_in(hash_builder: S, alloc: A) -> Self {
         Self {
-            buckets: Vec::new(),
-            items: 0,
+            table: HashTable::new_in(alloc),
+            hash_builder,
         }
     }
 
+    pub fn allocator(&self) -> &A {
+        self.table.allocator()
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
     pub fn len(&self) -> usize {
-        self.items
+        self.table.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.items == 0
+        self.table.is_empty()
     }
+}
 
-    fn key_to_index<Q: ?Sized>(key: &Q, len: usize) -> usize
+impl<K, V, S, A> HashMap<K, V, S, A>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    A: Allocator + Clone,
+{
+    fn hash_key<Q>(&self, key: &Q) -> u64
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
-        let mut hasher = DefaultHasher::new();
-        key.hash(&mut hasher);
-        (hasher.finish() % len as u64) as usize
-    }
-
-    fn resize(&mut self) {
-        let new_size = match self.buckets.len() {
-            0 => INIT_BUCKETS,
-            l => l * 2,
-        };
-
-        let mut new_buckets: Vec<Vec<(K, V)>> = Vec::with_capacity(new_size);
-
-        (0..new_size).for_each(|_| new_buckets.push(Vec::with_capacity(new_size)));
-
-        for mut bucket in self.buckets.drain(..) {
-            for (key, value) in bucket.drain(..) {
-                let index = Self::key_to_index(&key, new_buckets.len());
-                new_buckets[index].push((key, value));
-            }
-        }
-
-        let _ = std::mem::replace(&mut self.buckets, new_buckets);
+        self.hash_builder.hash_one(key)
     }
 
     pub fn insert(&mut self, key: K, value: V) -> Option<V> {
-        if self.buckets.is_empty() || self.items > 3 * self.buckets.len() / 4 {
-            self.resize();
-        }
-
-        let index = Self::key_to_index(&key, self.buckets.len());
-        let bucket = self.buckets.get_mut(index).unwrap();
+        let hash = self.hash_key(&key);
 
-        if let Some((_, prev_v)) = bucket.iter_mut().find(|(k, _)| *k == key) {
+        if let Some((_, prev_v)) = self.table.find_mut(hash, |(k, _)| *k == key) {
             Some(std::mem::replace(prev_v, value))
         } else {
-            bucket.push((key, value));
-            self.items += 1;
+            self.table.insert_unique(hash, (key, value));
             None
         }
     }
@@ -85,11 +99,17 @@ where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        let index = Self::key_to_index(key, self.buckets.len());
-        self.buckets[index]
-            .iter()
-            .find(|(k, _)| k.borrow() == key)
-            .map(|(_, v)| v)
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.table
+            .find(self.hash_key(key), |(k, _)| k.borrow() == key)
+            .map(|(k, v)| (k, v))
     }
 
     pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
@@ -97,10 +117,8 @@ where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        let index = Self::key_to_index(key, self.buckets.len());
-        self.buckets[index]
-            .iter_mut()
-            .find(|(k, _)| k.borrow() == key)
+        self.table
+            .find_mut(self.hash_key(key), |(k, _)| k.borrow() == key)
             .map(|(_, v)| v)
     }
 
@@ -109,15 +127,17 @@ where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        let index = Self::key_to_index(key, self.buckets.len());
+        self.remove_entry(key).map(|(_, v)| v)
+    }
 
-        self.buckets[index]
-            .iter()
-            .position(|(k, _)| k.borrow() == key)
-            .map(|pos| {
-                self.items -= 1;
-                self.buckets[index].swap_remove(pos).1
-            })
+    /// Removes a key from the map, returning the stored key and value.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.table
+            .remove(self.hash_key(key), |(k, _)| k.borrow() == key)
     }
 
     pub fn contains_key<Q>(&self, key: &Q) -> bool
@@ -125,19 +145,25 @@ where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        let index = Self::key_to_index(key, self.buckets.len());
+        self.get_key_value(key).is_some()
+    }
+}
 
-        self.buckets[index]
-            .iter()
-            .find(|(k, _)| k.borrow() == key)
-            .is_some()
+impl<K, V, S> Default for HashMap<K, V, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
     }
 }
 
-impl<K, V, Q> Index<&Q> for HashMap<K, V>
+impl<K, V, S, A, Q> Index<&Q> for HashMap<K, V, S, A>
 where
     Q: Hash + Eq + ?Sized,
     K: Hash + Eq + Borrow<Q>,
+    S: BuildHasher,
+    A: Allocator + Clone,
 {
     type Output = V;
     fn index(&self, index: &Q) -> &Self::Output {
@@ -148,8 +174,10 @@ where
     }
 }
 
+#[cfg(test)]
 mod test {
     use super::HashMap;
+    use crate::datastructures::CountingAllocator;
 
     #[test]
     fn insert() {
@@ -219,4 +247,46 @@ mod test {
             println!("{book}: \"{review}\"");
         }
     }
+
+    #[test]
+    fn seeded_iteration_order() {
+        let words = "A Dance With Dragons A Feast For Crows Two Words";
+
+        let mut a = HashMap::with_seed(7);
+        let mut b = HashMap::with_seed(7);
+        for (i, word) in words.split(' ').enumerate() {
+            a.insert(word, i);
+            b.insert(word, i);
+        }
+
+        let order: Vec<&str> = a.keys().copied().collect();
+        assert_eq!(order, b.keys().copied().collect::<Vec<_>>());
+
+        // Pinned, so any change to the hasher or the bucket layout shows up here
+        assert_eq!(
+            order,
+            ["Dance", "A", "Crows", "Two", "Words", "Feast", "For", "Dragons", "With"]
+        );
+        assert_eq!(a.hasher().seed(), 7);
+    }
+
+    #[test]
+    fn counting_allocator() {
+        let counter = CountingAllocator::new();
+        {
+            let mut map = HashMap::new_in(&counter);
+            for i in 0..1000 {
+                map.insert(i, i.to_string());
+            }
+            for i in (0..1000).step_by(2) {
+                map.remove(&i);
+            }
+            assert_eq!(map.len(), 500);
+            assert_eq!(map[&999], "999");
+            assert!(counter.live_bytes() > 0);
+        }
+
+        assert_eq!(counter.live_bytes(), 0);
+        assert!(counter.peak_bytes() > 0);
+    }
 }
@@ -1,23 +1,27 @@
 use std::hash::Hash;
 
-use crate::datastructures::hashmap::HashMap;
+use crate::datastructures::{
+    hashmap::{HashMap, HashTable},
+    Allocator, Global,
+};
 
-pub struct Pairs<'m, K, V> {
-    map: &'m HashMap<K, V>,
+pub struct Pairs<'m, K, V, A: Allocator = Global> {
+    table: &'m HashTable<(K, V), A>,
     bucket: usize,
     pair: usize,
 }
 
-impl<'m, K, V> Iterator for Pairs<'m, K, V>
+impl<'m, K, V, A> Iterator for Pairs<'m, K, V, A>
 where
     K: Eq + Hash,
+    A: Allocator,
 {
     type Item = (&'m K, &'m V);
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            match self.map.buckets.get(self.bucket) {
+            match self.table.buckets.get(self.bucket) {
                 Some(bucket) => match bucket.get(self.pair) {
-                    Some((k, v)) => {
+                    Some((_, (k, v))) => {
                         self.pair += 1;
                         return Some((k, v));
                     }
@@ -32,34 +36,35 @@ where
     }
 }
 
-impl<'m, K, V> IntoIterator for &'m HashMap<K, V>
+impl<'m, K, V, S, A> IntoIterator for &'m HashMap<K, V, S, A>
 where
     K: Eq + Hash,
+    A: Allocator,
 {
     type Item = (&'m K, &'m V);
-    type IntoIter = Pairs<'m, K, V>;
+    type IntoIter = Pairs<'m, K, V, A>;
 
     fn into_iter(self) -> Self::IntoIter {
         Pairs {
-            map: self,
+            table: &self.table,
             bucket: 0,
             pair: 0,
         }
     }
 }
 
-pub struct IntoPairs<K, V> {
-    map: HashMap<K, V>,
+pub struct IntoPairs<K, V, A: Allocator = Global> {
+    table: HashTable<(K, V), A>,
     bucket: usize,
 }
 
-impl<K, V> Iterator for IntoPairs<K, V> {
+impl<K, V, A: Allocator> Iterator for IntoPairs<K, V, A> {
     type Item = (K, V);
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            match self.map.buckets.get_mut(self.bucket) {
+            match self.table.buckets.get_mut(self.bucket) {
                 Some(bucket) => match bucket.pop() {
-                    Some(pair) => return Some(pair),
+                    Some((_, pair)) => return Some(pair),
                     None => {
                         self.bucket += 1;
                     }
@@ -70,37 +75,39 @@ impl<K, V> Iterator for IntoPairs<K, V> {
     }
 }
 
-impl<K, V> IntoIterator for HashMap<K, V>
+impl<K, V, S, A> IntoIterator for HashMap<K, V, S, A>
 where
     K: Eq + Hash,
+    A: Allocator,
 {
     type Item = (K, V);
-    type IntoIter = IntoPairs<K, V>;
+    type IntoIter = IntoPairs<K, V, A>;
     fn into_iter(self) -> Self::IntoIter {
         IntoPairs {
-            map: self,
+            table: self.table,
             bucket: 0,
         }
     }
 }
 
 /// An iterator over references to keys
-pub struct Keys<'m, K, V> {
-    map: &'m HashMap<K, V>,
+pub struct Keys<'m, K, V, A: Allocator = Global> {
+    table: &'m HashTable<(K, V), A>,
     bucket: usize,
     pair: usize,
 }
 
-impl<'m, K, V> Iterator for Keys<'m, K, V>
+impl<'m, K, V, A> Iterator for Keys<'m, K, V, A>
 where
     K: Eq + Hash,
+    A: Allocator,
 {
     type Item = &'m K;
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            match self.map.buckets.get(self.bucket) {
+            match self.table.buckets.get(self.bucket) {
                 Some(bucket) => match bucket.get(self.pair) {
-                    Some((k, _)) => {
+                    Some((_, (k, _))) => {
                         self.pair += 1;
                         return Some(k);
                     }
@@ -115,13 +122,14 @@ where
     }
 }
 
-impl<'m, K, V> HashMap<K, V>
+impl<'m, K, V, S, A> HashMap<K, V, S, A>
 where
     K: Hash + Eq,
+    A: Allocator,
 {
-    pub fn keys(&'m self) -> Keys<'m, K, V> {
+    pub fn keys(&'m self) -> Keys<'m, K, V, A> {
         Keys {
-            map: self,
+            table: &self.table,
             bucket: 0,
             pair: 0,
         }
@@ -129,22 +137,23 @@ where
 }
 
 /// An iterator over references to values
-pub struct Values<'m, K, V> {
-    map: &'m HashMap<K, V>,
+pub struct Values<'m, K, V, A: Allocator = Global> {
+    table: &'m HashTable<(K, V), A>,
     bucket: usize,
     pair: usize,
 }
 
-impl<'m, K, V> Iterator for Values<'m, K, V>
+impl<'m, K, V, A> Iterator for Values<'m, K, V, A>
 where
     K: Eq + Hash,
+    A: Allocator,
 {
     type Item = &'m V;
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            match self.map.buckets.get(self.bucket) {
+            match self.table.buckets.get(self.bucket) {
                 Some(bucket) => match bucket.get(self.pair) {
-                    Some((_, v)) => {
+                    Some((_, (_, v))) => {
                         self.pair += 1;
                         return Some(v);
                     }
@@ -159,13 +168,14 @@ where
     }
 }
 
-impl<'m, K, V> HashMap<K, V>
+impl<'m, K, V, S, A> HashMap<K, V, S, A>
 where
     K: Hash + Eq,
+    A: Allocator,
 {
-    pub fn values(&'m self) -> Values<'m, K, V> {
+    pub fn values(&'m self) -> Values<'m, K, V, A> {
         Values {
-            map: self,
+            table: &self.table,
             bucket: 0,
             pair: 0,
         }
@@ -176,7 +186,7 @@ where
 mod test {
     use super::*;
 
-    const BOOKS: &'static [(u8, &'static str)] = &[
+    const BOOKS: &[(u8, &str)] = &[
         (1, "A Game of Thrones"),
         (2, "A Clash of Kings"),
         (3, "A Storm of Swords"),
@@ -211,10 +221,10 @@ mod test {
             map.insert(*k, v.to_string());
         }
 
-        let keys_only: Vec<u8> = BOOKS.iter().map(|(k, v)| *k).collect();
+        let keys_only: Vec<u8> = BOOKS.iter().map(|(k, _)| *k).collect();
         for k in map.keys() {
             // println!("#{i}: {k} - {v}");
-            assert!(keys_only.contains(&k));
+            assert!(keys_only.contains(k));
         }
     }
 
@@ -1,7 +1,17 @@
 //pub mod entry;
+pub mod disk;
+pub mod hasher;
+#[allow(clippy::module_inception)]
 pub mod hashmap;
 pub mod iter;
+pub mod parallel;
+pub mod table;
+pub mod transaction;
 
 //pub use entry::*;
+pub use disk::DiskHashMap;
+pub use hasher::{DefaultHashBuilder, SeededState, StableHasher};
 pub use hashmap::HashMap;
 pub use iter::*;
+pub use table::HashTable;
+pub use transaction::{Checkpoint, TransactionalHashMap};
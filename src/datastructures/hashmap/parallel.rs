@@ -0,0 +1,336 @@
+use std::{
+    hash::{BuildHasher, Hash},
+    num::NonZeroUsize,
+    thread,
+};
+
+use super::{DefaultHashBuilder, HashMap, HashTable};
+use crate::datastructures::{Allocator, Vector};
+
+type Bucket<K, V> = Vector<(u64, (K, V))>;
+
+/// Number of threads to use for `items` units of work.
+fn workers_for(items: usize) -> usize {
+    thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(items)
+        .max(1)
+}
+
+/// Splits `items` into at most `parts` consecutive owned chunks, keeping their order.
+fn split_owned<T>(mut items: Vector<T>, parts: usize) -> Vec<Vector<T>> {
+    let chunk_len = items.len().div_ceil(parts).max(1);
+    let mut chunks = Vec::with_capacity(parts);
+
+    while items.len() > chunk_len {
+        let rest = items.split_off(chunk_len);
+        chunks.push(items);
+        items = rest;
+    }
+    chunks.push(items);
+
+    chunks
+}
+
+impl<K, V, S, A: Allocator + Sync> HashMap<K, V, S, A> {
+    /// Calls `f` on every pair, in parallel.
+    ///
+    /// The buckets are split into one chunk per available thread.
+    pub fn par_for_each<F>(&self, f: F)
+    where
+        K: Sync,
+        V: Sync,
+        F: Fn(&K, &V) + Sync,
+    {
+        let buckets = &self.table.buckets;
+        let chunk_len = buckets.len().div_ceil(workers_for(buckets.len())).max(1);
+        let f = &f;
+
+        thread::scope(|s| {
+            for chunk in buckets.chunks(chunk_len) {
+                s.spawn(move || {
+                    for (_, (k, v)) in chunk.iter().flatten() {
+                        f(k, v);
+                    }
+                });
+            }
+        });
+    }
+
+    /// Folds every chunk of buckets starting from `identity()`, then combines the partial
+    /// results with `reduce` in bucket order.
+    pub fn par_fold<B, ID, F, R>(&self, identity: ID, fold: F, reduce: R) -> B
+    where
+        K: Sync,
+        V: Sync,
+        B: Send,
+        ID: Fn() -> B + Sync,
+        F: Fn(B, &K, &V) -> B + Sync,
+        R: Fn(B, B) -> B,
+    {
+        let buckets = &self.table.buckets;
+        let chunk_len = buckets.len().div_ceil(workers_for(buckets.len())).max(1);
+        let (identity, fold) = (&identity, &fold);
+
+        let partials: Vec<B> = thread::scope(|s| {
+            let handles: Vec<_> = buckets
+                .chunks(chunk_len)
+                .map(|chunk| {
+                    s.spawn(move || {
+                        chunk
+                            .iter()
+                            .flatten()
+                            .fold(identity(), |acc, (_, (k, v))| fold(acc, k, v))
+                    })
+                })
+                .collect();
+
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        partials.into_iter().fold(identity(), reduce)
+    }
+}
+
+impl<K, V, S> HashMap<K, V, S> {
+    /// Transforms every value in parallel.
+    ///
+    /// Keys keep their hashes, so the new map has the same bucket layout as this one.
+    pub fn par_map_values<W, F>(self, f: F) -> HashMap<K, W, S>
+    where
+        K: Send,
+        V: Send,
+        W: Send,
+        F: Fn(&K, V) -> W + Sync,
+    {
+        let items = self.table.len();
+        let buckets = self.table.buckets;
+        let workers = workers_for(buckets.len());
+        let f = &f;
+
+        let mapped: Vec<Vector<Bucket<K, W>>> = thread::scope(|s| {
+            let handles: Vec<_> = split_owned(buckets, workers)
+                .into_iter()
+                .map(|chunk| {
+                    s.spawn(move || {
+                        chunk
+                            .into_iter()
+                            .map(|bucket| {
+                                bucket
+                                    .into_iter()
+                                    .map(|(hash, (k, v))| {
+                                        let w = f(&k, v);
+                                        (hash, (k, w))
+                                    })
+                                    .collect()
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        HashMap {
+            table: HashTable::from_buckets(mapped.into_iter().flatten().collect(), items),
+            hash_builder: self.hash_builder,
+        }
+    }
+}
+
+impl<K, V> HashMap<K, V, DefaultHashBuilder>
+where
+    K: Eq + Hash + Send,
+    V: Send,
+{
+    /// Builds a map from `pairs` in parallel, see [`HashMap::par_from_vec_with_hasher`].
+    pub fn par_from_vec(pairs: Vec<(K, V)>) -> Self {
+        Self::par_from_vec_with_hasher(pairs, DefaultHashBuilder::default())
+    }
+}
+
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Eq + Hash + Send,
+    V: Send,
+    S: BuildHasher + Sync,
+{
+    /// Builds a map from `pairs` in parallel.
+    ///
+    /// Workers first hash their share of the input and partition it by bucket range,
+    /// then every range is filled in by its own worker, so merging is just concatenation.
+    /// Like repeated [`insert`](HashMap::insert)s, a later pair overwrites the value of an earlier
+    /// one with the same key.
+    pub fn par_from_vec_with_hasher(pairs: Vec<(K, V)>, hash_builder: S) -> Self {
+        let bucket_count = HashTable::<(K, V)>::buckets_for(pairs.len());
+        let workers = workers_for(pairs.len());
+        let range_len = bucket_count.div_ceil(workers);
+        let hash_builder_ref = &hash_builder;
+
+        // Phase 1: hash and partition by bucket range
+        let partitioned: Vec<Vector<Bucket<K, V>>> = thread::scope(|s| {
+            let handles: Vec<_> = split_owned(pairs.into_iter().collect(), workers)
+                .into_iter()
+                .map(|chunk| {
+                    s.spawn(move || {
+                        let mut parts: Vector<Bucket<K, V>> =
+                            (0..workers).map(|_| Vector::new()).collect();
+                        for (k, v) in chunk {
+                            let hash = hash_builder_ref.hash_one(&k);
+                            let bucket = HashTable::<(K, V)>::bucket_index(hash, bucket_count);
+                            parts[bucket / range_len].push((hash, (k, v)));
+                        }
+                        parts
+                    })
+                })
+                .collect();
+
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        // Group the parts by range, keeping input order within each range
+        let mut ranges: Vec<Vec<Bucket<K, V>>> = (0..workers).map(|_| Vec::new()).collect();
+        for parts in partitioned {
+            for (range, part) in parts.into_iter().enumerate() {
+                ranges[range].push(part);
+            }
+        }
+
+        // Phase 2: every worker fills in the buckets of its own range
+        let filled: Vec<(Vector<Bucket<K, V>>, usize)> = thread::scope(|s| {
+            let handles: Vec<_> = ranges
+                .into_iter()
+                .enumerate()
+                .map(|(range, parts)| {
+                    s.spawn(move || {
+                        let first = range * range_len;
+                        let len = range_len.min(bucket_count.saturating_sub(first));
+                        let mut buckets: Vector<Bucket<K, V>> =
+                            (0..len).map(|_| Vector::new()).collect();
+                        let mut items = 0;
+
+                        for (hash, (k, v)) in parts.into_iter().flatten() {
+                            let index = HashTable::<(K, V)>::bucket_index(hash, bucket_count);
+                            let bucket = &mut buckets[index - first];
+
+                            match bucket
+                                .iter_mut()
+                                .find(|(h, (key, _))| *h == hash && *key == k)
+                            {
+                                Some((_, (_, prev))) => *prev = v,
+                                None => {
+                                    bucket.push((hash, (k, v)));
+                                    items += 1;
+                                }
+                            }
+                        }
+
+                        (buckets, items)
+                    })
+                })
+                .collect();
+
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let items = filled.iter().map(|(_, items)| items).sum();
+        let buckets = filled
+            .into_iter()
+            .flat_map(|(buckets, _)| buckets)
+            .collect();
+
+        HashMap {
+            table: HashTable::from_buckets(buckets, items),
+            hash_builder,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn squares(n: u64) -> HashMap<u64, u64> {
+        let mut map = HashMap::new();
+        for i in 0..n {
+            map.insert(i, i * i);
+        }
+        map
+    }
+
+    #[test]
+    fn for_each() {
+        let map = squares(10_000);
+        let count = AtomicUsize::new(0);
+        let sum = AtomicU64::new(0);
+
+        map.par_for_each(|_, v| {
+            count.fetch_add(1, Ordering::Relaxed);
+            sum.fetch_add(*v, Ordering::Relaxed);
+        });
+
+        assert_eq!(count.into_inner(), 10_000);
+        assert_eq!(sum.into_inner(), map.values().sum::<u64>());
+    }
+
+    #[test]
+    fn fold() {
+        let map = squares(10_000);
+        let sum = map.par_fold(|| 0, |acc, _, v| acc + v, |a, b| a + b);
+        assert_eq!(sum, map.values().sum::<u64>());
+
+        let empty = HashMap::<u64, u64>::new();
+        assert_eq!(empty.par_fold(|| 0, |acc, _, v| acc + v, |a, b| a + b), 0);
+    }
+
+    #[test]
+    fn map_values() {
+        let map = squares(10_000);
+        let order: Vec<u64> = map.keys().copied().collect();
+
+        let map = map.par_map_values(|k, v| format!("{k}^2 = {v}"));
+
+        assert_eq!(map.len(), 10_000);
+        assert_eq!(map[&12], "12^2 = 144");
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), order);
+    }
+
+    #[test]
+    fn from_vec_matches_sequential() {
+        let pairs: Vec<(u64, u64)> = (0..100_000).map(|i| (i, i * 3)).collect();
+        let parallel = HashMap::par_from_vec(pairs);
+        let sequential = {
+            let mut map = HashMap::new();
+            (0..100_000).for_each(|i| {
+                map.insert(i, i * 3);
+            });
+            map
+        };
+
+        assert_eq!(parallel.len(), sequential.len());
+        assert_eq!(parallel.table.buckets.len(), sequential.table.buckets.len());
+        for (k, v) in &sequential {
+            assert_eq!(parallel.get(k), Some(v));
+        }
+    }
+
+    #[test]
+    fn from_vec_later_pair_wins() {
+        let pairs = vec![
+            (1, "A Game of Thrones"),
+            (2, "A Clash of Kings"),
+            (1, "A Storm of Swords"),
+        ];
+        let map = HashMap::par_from_vec(pairs);
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&1], "A Storm of Swords");
+
+        let map = HashMap::<u8, u8>::par_from_vec(Vec::new());
+        assert!(map.is_empty());
+        assert_eq!(map.get(&0), None);
+    }
+}
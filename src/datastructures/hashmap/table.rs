@@ -0,0 +1,291 @@
+use std::mem;
+
+use crate::datastructures::{Allocator, Global, Vector};
+
+const INIT_BUCKETS: usize = 4;
+
+/// A low level hash table of separately chained buckets.
+///
+/// The table never hashes or compares anything by itself, every lookup takes the hash value
+/// and an equality closure from the caller. This allows storing values that carry their own key,
+/// [`HashMap`](super::HashMap) is built on top of a `HashTable<(K, V)>`.
+///
+/// Hashes are kept next to the values, so growing the table doesn't need the hasher.
+/// The buckets and their contents are allocated from `A`.
+pub struct HashTable<T, A: Allocator = Global> {
+    pub(crate) buckets: Vector<Vector<(u64, T), A>, A>,
+    items: usize,
+}
+
+impl<T> HashTable<T> {
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T, A: Allocator> HashTable<T, A> {
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            buckets: Vector::new_in(alloc),
+            items: 0,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.buckets.allocator()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items == 0
+    }
+
+    pub(crate) fn bucket_index(hash: u64, len: usize) -> usize {
+        (hash % len as u64) as usize
+    }
+
+    /// The number of buckets a table ends up with after `items` unique insertions.
+    pub(crate) fn buckets_for(items: usize) -> usize {
+        let mut buckets = INIT_BUCKETS;
+        while items > 0 && items - 1 > 3 * buckets / 4 {
+            buckets *= 2;
+        }
+        buckets
+    }
+
+    /// Assembles a table from buckets that already hold every value at its `bucket_index`.
+    pub(crate) fn from_buckets(buckets: Vector<Vector<(u64, T), A>, A>, items: usize) -> Self {
+        Self { buckets, items }
+    }
+
+    /// Finds the position of a value as (bucket, index into bucket).
+    fn position(&self, hash: u64, mut eq: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        if self.buckets.is_empty() {
+            return None;
+        }
+
+        let index = Self::bucket_index(hash, self.buckets.len());
+        self.buckets[index]
+            .iter()
+            .position(|(h, value)| *h == hash && eq(value))
+            .map(|pos| (index, pos))
+    }
+
+    pub fn find(&self, hash: u64, eq: impl FnMut(&T) -> bool) -> Option<&T> {
+        self.position(hash, eq)
+            .map(|(bucket, pos)| &self.buckets[bucket][pos].1)
+    }
+
+    pub fn find_mut(&mut self, hash: u64, eq: impl FnMut(&T) -> bool) -> Option<&mut T> {
+        self.position(hash, eq)
+            .map(|(bucket, pos)| &mut self.buckets[bucket][pos].1)
+    }
+
+    pub fn remove(&mut self, hash: u64, eq: impl FnMut(&T) -> bool) -> Option<T> {
+        self.position(hash, eq).map(|(bucket, pos)| {
+            self.items -= 1;
+            self.buckets[bucket].swap_remove(pos).1
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(Vector::clear);
+        self.items = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, A> {
+        Iter {
+            table: self,
+            bucket: 0,
+            pos: 0,
+        }
+    }
+}
+
+impl<T, A: Allocator + Clone> HashTable<T, A> {
+    fn resize(&mut self) {
+        let new_size = match self.buckets.len() {
+            0 => INIT_BUCKETS,
+            l => l * 2,
+        };
+
+        let alloc = self.allocator().clone();
+        let mut new_buckets = Vector::with_capacity_in(new_size, alloc.clone());
+
+        (0..new_size).for_each(|_| new_buckets.push(Vector::new_in(alloc.clone())));
+
+        for mut bucket in self.buckets.drain(..) {
+            for (hash, value) in bucket.drain(..) {
+                let index = Self::bucket_index(hash, new_buckets.len());
+                new_buckets[index].push((hash, value));
+            }
+        }
+
+        let _ = mem::replace(&mut self.buckets, new_buckets);
+    }
+
+    /// Inserts `value`, replacing and returning the first value `eq` matches with.
+    pub fn insert(&mut self, hash: u64, value: T, eq: impl FnMut(&T) -> bool) -> Option<T> {
+        match self.find_mut(hash, eq) {
+            Some(prev) => Some(mem::replace(prev, value)),
+            None => {
+                self.insert_unique(hash, value);
+                None
+            }
+        }
+    }
+
+    /// Inserts `value` without checking whether an equal value is already present.
+    pub fn insert_unique(&mut self, hash: u64, value: T) -> &mut T {
+        if self.buckets.is_empty() || self.items > 3 * self.buckets.len() / 4 {
+            self.resize();
+        }
+
+        let index = Self::bucket_index(hash, self.buckets.len());
+        let bucket = &mut self.buckets[index];
+        bucket.push((hash, value));
+        self.items += 1;
+
+        &mut bucket.last_mut().unwrap().1
+    }
+}
+
+impl<T> Default for HashTable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iterator over references to the values of a [`HashTable`]
+pub struct Iter<'t, T, A: Allocator = Global> {
+    table: &'t HashTable<T, A>,
+    bucket: usize,
+    pos: usize,
+}
+
+impl<'t, T, A: Allocator> Iterator for Iter<'t, T, A> {
+    type Item = &'t T;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.table.buckets.get(self.bucket) {
+                Some(bucket) => match bucket.get(self.pos) {
+                    Some((_, value)) => {
+                        self.pos += 1;
+                        return Some(value);
+                    }
+                    None => {
+                        self.bucket += 1;
+                        self.pos = 0;
+                    }
+                },
+                None => break None,
+            }
+        }
+    }
+}
+
+impl<'t, T, A: Allocator> IntoIterator for &'t HashTable<T, A> {
+    type Item = &'t T;
+    type IntoIter = Iter<'t, T, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    use super::HashTable;
+
+    #[derive(Debug, PartialEq)]
+    struct Book {
+        isbn: u64,
+        title: &'static str,
+    }
+
+    fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn insert_book(table: &mut HashTable<Book>, isbn: u64, title: &'static str) -> Option<Book> {
+        table.insert(hash(&isbn), Book { isbn, title }, |b| b.isbn == isbn)
+    }
+
+    #[test]
+    fn key_inside_value() {
+        let mut table = HashTable::new();
+        assert_eq!(
+            insert_book(&mut table, 553103547, "A Game of Thrones"),
+            None
+        );
+        assert_eq!(insert_book(&mut table, 553108034, "A Clash of Kings"), None);
+        assert_eq!(table.len(), 2);
+
+        let book = table.find(hash(&553103547u64), |b| b.isbn == 553103547);
+        assert_eq!(book.map(|b| b.title), Some("A Game of Thrones"));
+
+        let prev = insert_book(&mut table, 553103547, "A Game of Thrones (Illustrated)");
+        assert_eq!(prev.map(|b| b.title), Some("A Game of Thrones"));
+        assert_eq!(table.len(), 2);
+
+        let removed = table.remove(hash(&553108034u64), |b| b.isbn == 553108034);
+        assert_eq!(removed.map(|b| b.title), Some("A Clash of Kings"));
+        assert_eq!(table.len(), 1);
+        assert_eq!(
+            table.find(hash(&553108034u64), |b| b.isbn == 553108034),
+            None
+        );
+    }
+
+    #[test]
+    fn empty_table() {
+        let mut table = HashTable::<u32>::new();
+        assert_eq!(table.find(0, |_| true), None);
+        assert_eq!(table.remove(0, |_| true), None);
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn colliding_hashes() {
+        // Every value shares the same hash, so only `eq` tells them apart
+        let mut table = HashTable::new();
+        for i in 0..100u32 {
+            table.insert_unique(42, i);
+        }
+
+        assert_eq!(table.len(), 100);
+        for i in 0..100u32 {
+            assert_eq!(table.find(42, |v| *v == i), Some(&i));
+        }
+        assert_eq!(table.find(42, |v| *v == 100), None);
+    }
+
+    #[test]
+    fn grows_and_iterates() {
+        let mut table = HashTable::new();
+        for word in "A Dance With Dragons A Feast For Crows Two Words".split(' ') {
+            table.insert(hash(word), word, |w| *w == word);
+        }
+
+        assert_eq!(table.len(), 9);
+        assert!(table.buckets.len() > 4);
+
+        let mut words: Vec<&str> = table.iter().copied().collect();
+        words.sort();
+        assert_eq!(
+            words,
+            ["A", "Crows", "Dance", "Dragons", "Feast", "For", "Two", "With", "Words"]
+        );
+
+        table.clear();
+        assert!(table.is_empty());
+        assert_eq!(table.iter().next(), None);
+    }
+}
@@ -0,0 +1,352 @@
+use std::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash},
+    ops::Deref,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use super::{DefaultHashBuilder, HashMap};
+
+/// Hands out checkpoint ids across all maps, so a checkpoint of one map is never open in another
+static NEXT_CHECKPOINT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A single step needed to revert one effect on the map
+enum Undo<K, V> {
+    /// The key was not present before, undoing removes it
+    Remove(K),
+    /// The key held this value before it was overwritten, removed or borrowed mutably
+    Restore(K, V),
+}
+
+/// A point in a transaction that the map can be rolled back to.
+///
+/// Returned by [`TransactionalHashMap::checkpoint`], it becomes invalid once it is rolled back or
+/// the transaction is committed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Unique per process, so neither a closed checkpoint nor one of another map can pass for
+    /// an open one at the same depth
+    id: u64,
+    depth: usize,
+}
+
+/// A [`HashMap`] wrapper that can undo every change made since a [`Checkpoint`].
+///
+/// Changes are only recorded while at least one checkpoint is open, outside of a transaction
+/// every operation goes straight to the underlying map.
+/// Read-only access to the map is available through `Deref`.
+pub struct TransactionalHashMap<K, V, S = DefaultHashBuilder> {
+    map: HashMap<K, V, S>,
+    undo_log: Vec<Undo<K, V>>,
+    /// Id and length of the undo log of each open checkpoint, innermost last
+    checkpoints: Vec<(u64, usize)>,
+}
+
+impl<K, V> TransactionalHashMap<K, V, DefaultHashBuilder> {
+    pub fn new() -> Self {
+        Self::from(HashMap::new())
+    }
+}
+
+impl<K, V, S> TransactionalHashMap<K, V, S>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasher,
+{
+    /// Returns `true` if there is at least one open checkpoint.
+    pub fn in_transaction(&self) -> bool {
+        !self.checkpoints.is_empty()
+    }
+
+    /// Opens a new (possibly nested) checkpoint.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let cp = Checkpoint {
+            id: NEXT_CHECKPOINT_ID.fetch_add(1, Ordering::Relaxed),
+            depth: self.checkpoints.len(),
+        };
+        self.checkpoints.push((cp.id, self.undo_log.len()));
+        cp
+    }
+
+    /// Reverts every change made since `cp` was taken, closing `cp` and every checkpoint nested in it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cp` was already rolled back or committed, or belongs to another map.
+    pub fn rollback_to(&mut self, cp: Checkpoint) {
+        let undo_len = match self.checkpoints.get(cp.depth) {
+            Some(&(id, undo_len)) if id == cp.id => undo_len,
+            _ => panic!("Checkpoint is no longer open"),
+        };
+
+        while self.undo_log.len() > undo_len {
+            match self.undo_log.pop().unwrap() {
+                Undo::Remove(key) => {
+                    self.map.remove(&key);
+                }
+                Undo::Restore(key, value) => {
+                    self.map.insert(key, value);
+                }
+            }
+        }
+
+        self.checkpoints.truncate(cp.depth);
+    }
+
+    /// Makes every change permanent and closes all open checkpoints.
+    pub fn commit(&mut self) {
+        self.undo_log.clear();
+        self.checkpoints.clear();
+    }
+
+    /// Commits any open transaction and returns the underlying map.
+    pub fn into_inner(self) -> HashMap<K, V, S> {
+        self.map
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if !self.in_transaction() {
+            return self.map.insert(key, value);
+        }
+
+        let prev = self.map.insert(key.clone(), value);
+        self.undo_log.push(match &prev {
+            Some(prev) => Undo::Restore(key, prev.clone()),
+            None => Undo::Remove(key),
+        });
+        prev
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (key, value) = self.map.remove_entry(key)?;
+
+        if self.in_transaction() {
+            self.undo_log.push(Undo::Restore(key, value.clone()));
+        }
+
+        Some(value)
+    }
+
+    /// Inside a transaction this saves a copy of the current value first,
+    /// as there is no telling what the caller does with the reference.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.in_transaction() {
+            let (k, v) = self.map.get_key_value(key)?;
+            self.undo_log.push(Undo::Restore(k.clone(), v.clone()));
+        }
+
+        self.map.get_mut(key)
+    }
+}
+
+impl<K, V, S> From<HashMap<K, V, S>> for TransactionalHashMap<K, V, S> {
+    fn from(map: HashMap<K, V, S>) -> Self {
+        Self {
+            map,
+            undo_log: Vec::new(),
+            checkpoints: Vec::new(),
+        }
+    }
+}
+
+impl<K, V, S> Default for TransactionalHashMap<K, V, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Self::from(HashMap::default())
+    }
+}
+
+impl<K, V, S> Deref for TransactionalHashMap<K, V, S> {
+    type Target = HashMap<K, V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BOOKS: &[(u8, &str)] = &[
+        (1, "A Game of Thrones"),
+        (2, "A Clash of Kings"),
+        (3, "A Storm of Swords"),
+        (4, "A Feast for Crows"),
+        (5, "A Dance with Dragons"),
+    ];
+
+    fn books() -> TransactionalHashMap<u8, String> {
+        let mut map = TransactionalHashMap::new();
+        for (k, v) in BOOKS {
+            map.insert(*k, v.to_string());
+        }
+        map
+    }
+
+    fn assert_books(map: &TransactionalHashMap<u8, String>) {
+        assert_eq!(map.len(), BOOKS.len());
+        for (k, v) in BOOKS {
+            assert_eq!(map.get(k).map(String::as_str), Some(*v));
+        }
+    }
+
+    #[test]
+    fn no_log_outside_transaction() {
+        let mut map = books();
+        map.remove(&1);
+        *map.get_mut(&2).unwrap() = "The Winds of Winter".to_string();
+
+        assert!(!map.in_transaction());
+        assert!(map.undo_log.is_empty());
+    }
+
+    #[test]
+    fn rollback() {
+        let mut map = books();
+
+        let cp = map.checkpoint();
+        map.insert(6, "The Winds of Winter".to_string());
+        map.insert(1, "A Game of Thrones (Illustrated)".to_string());
+        map.remove(&3);
+        map.get_mut(&4).unwrap().push_str(", Vol. 1");
+        assert_eq!(map.len(), 5);
+
+        map.rollback_to(cp);
+
+        assert!(!map.in_transaction());
+        assert_books(&map);
+    }
+
+    #[test]
+    fn nested_rollback() {
+        let mut map = books();
+
+        let outer = map.checkpoint();
+        map.remove(&1);
+        let inner = map.checkpoint();
+        map.remove(&2);
+        map.insert(6, "The Winds of Winter".to_string());
+
+        map.rollback_to(inner);
+        assert!(map.in_transaction());
+        assert_eq!(map.len(), 4);
+        assert!(!map.contains_key(&1));
+        assert!(map.contains_key(&2));
+        assert!(!map.contains_key(&6));
+
+        map.rollback_to(outer);
+        assert_books(&map);
+    }
+
+    #[test]
+    fn rollback_outer_closes_inner() {
+        let mut map = books();
+
+        let outer = map.checkpoint();
+        map.remove(&1);
+        let _inner = map.checkpoint();
+        map.remove(&2);
+
+        map.rollback_to(outer);
+        assert!(!map.in_transaction());
+        assert_books(&map);
+    }
+
+    #[test]
+    fn commit() {
+        let mut map = books();
+
+        map.checkpoint();
+        map.remove(&1);
+        map.commit();
+
+        assert!(!map.in_transaction());
+        assert!(!map.contains_key(&1));
+        assert!(map.undo_log.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Checkpoint is no longer open")]
+    fn rollback_after_commit() {
+        let mut map = books();
+
+        let cp = map.checkpoint();
+        map.commit();
+        map.rollback_to(cp);
+    }
+
+    #[test]
+    #[should_panic(expected = "Checkpoint is no longer open")]
+    fn stale_after_rollback() {
+        let mut map = books();
+
+        let stale = map.checkpoint();
+        map.rollback_to(stale);
+
+        // Same depth and undo log length as `stale`
+        let _fresh = map.checkpoint();
+        map.rollback_to(stale);
+    }
+
+    #[test]
+    #[should_panic(expected = "Checkpoint is no longer open")]
+    fn stale_after_commit() {
+        let mut map = books();
+
+        let stale = map.checkpoint();
+        map.commit();
+
+        let _fresh = map.checkpoint();
+        map.remove(&1);
+        map.rollback_to(stale);
+    }
+
+    #[test]
+    #[should_panic(expected = "Checkpoint is no longer open")]
+    fn checkpoint_of_another_map() {
+        let mut map = books();
+        let mut other = books();
+
+        let _ours = map.checkpoint();
+        let theirs = other.checkpoint();
+        map.remove(&1);
+        map.rollback_to(theirs);
+    }
+
+    #[test]
+    fn fresh_checkpoint_after_rollback() {
+        let mut map = books();
+
+        let stale = map.checkpoint();
+        map.rollback_to(stale);
+        let fresh = map.checkpoint();
+        map.remove(&1);
+        map.rollback_to(fresh);
+        assert_books(&map);
+    }
+
+    #[test]
+    fn remove_missing() {
+        let mut map = TransactionalHashMap::<u8, String>::new();
+        let cp = map.checkpoint();
+
+        assert_eq!(map.remove(&1), None);
+        assert_eq!(map.get_mut(&1), None);
+        assert!(map.undo_log.is_empty());
+
+        map.rollback_to(cp);
+        assert!(map.is_empty());
+    }
+}
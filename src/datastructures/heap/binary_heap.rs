@@ -0,0 +1,389 @@
+use std::{
+    fmt, mem,
+    ops::{Deref, DerefMut},
+    slice,
+};
+
+use crate::datastructures::{vector, Vector};
+
+/// A max-heap: [`pop`](BinaryHeap::pop) and [`peek`](BinaryHeap::peek) return the greatest
+/// element.
+///
+/// The elements are stored as an implicit binary tree in a [`Vector`], the children of `i`
+/// are at `2i + 1` and `2i + 2`.
+pub struct BinaryHeap<T> {
+    data: Vector<T>,
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    pub fn new() -> Self {
+        Self {
+            data: Vector::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vector::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.data.push(item);
+        self.sift_up(self.data.len() - 1);
+    }
+
+    /// Removes the greatest element.
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.data.len();
+        if len == 0 {
+            return None;
+        }
+
+        self.data.swap(0, len - 1);
+        let item = self.data.pop();
+        self.sift_down(0);
+        item
+    }
+
+    /// Returns the greatest element.
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Returns a mutable reference to the greatest element, the heap is fixed up when it is
+    /// dropped.
+    ///
+    /// If the guard is leaked the heap order may be broken, but nothing unsafe happens.
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T>> {
+        (!self.is_empty()).then_some(PeekMut { heap: self })
+    }
+
+    /// Moves every element of `other` into `self`, leaving `other` empty.
+    ///
+    /// The smaller heap is pushed into the larger one, unless rebuilding from scratch
+    /// is cheaper.
+    pub fn append(&mut self, other: &mut Self) {
+        if self.len() < other.len() {
+            mem::swap(self, other);
+        }
+
+        let start = self.data.len();
+        // Rebuilding takes about 2(n + m) comparisons, pushing m elements about m log2(n)
+        let rebuild = 2 * (start + other.len()) < other.len() * log2(start);
+
+        self.data.append(&mut other.data);
+        if rebuild {
+            self.rebuild();
+        } else {
+            (start..self.data.len()).for_each(|i| self.sift_up(i));
+        }
+    }
+
+    /// Returns the elements in ascending order.
+    pub fn into_sorted_vec(mut self) -> Vector<T> {
+        let mut end = self.data.len();
+        while end > 1 {
+            end -= 1;
+            self.data.swap(0, end);
+            self.sift_down_range(0, end);
+        }
+        self.data
+    }
+
+    /// Moves the element at `pos` up until its parent is not smaller.
+    fn sift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.data[pos] <= self.data[parent] {
+                break;
+            }
+            self.data.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    fn sift_down(&mut self, pos: usize) {
+        self.sift_down_range(pos, self.data.len());
+    }
+
+    /// Moves the element at `pos` down until no child in `..end` is greater.
+    fn sift_down_range(&mut self, mut pos: usize, end: usize) {
+        loop {
+            let mut child = 2 * pos + 1;
+            if child >= end {
+                break;
+            }
+            if child + 1 < end && self.data[child + 1] > self.data[child] {
+                child += 1;
+            }
+            if self.data[pos] >= self.data[child] {
+                break;
+            }
+            self.data.swap(pos, child);
+            pos = child;
+        }
+    }
+
+    /// Restores the heap order bottom up, in O(n).
+    fn rebuild(&mut self) {
+        let len = self.data.len();
+        (0..len / 2)
+            .rev()
+            .for_each(|i| self.sift_down_range(i, len));
+    }
+}
+
+impl<T> BinaryHeap<T> {
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Iterates over the elements in heap order, which is not sorted.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Returns the underlying vector in heap order.
+    pub fn into_vec(self) -> Vector<T> {
+        self.data
+    }
+}
+
+fn log2(n: usize) -> usize {
+    (usize::BITS - n.leading_zeros()) as usize
+}
+
+/// A mutable reference to the greatest element of a [`BinaryHeap`], see
+/// [`BinaryHeap::peek_mut`].
+pub struct PeekMut<'h, T: Ord> {
+    heap: &'h mut BinaryHeap<T>,
+}
+
+impl<T: Ord> PeekMut<'_, T> {
+    /// Removes the peeked element from the heap.
+    pub fn pop(this: Self) -> T {
+        // Popping already restores the order, so skip the sift in `drop`
+        let mut this = mem::ManuallyDrop::new(this);
+        this.heap.pop().unwrap()
+    }
+}
+
+impl<T: Ord> Deref for PeekMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.heap.data[0]
+    }
+}
+
+impl<T: Ord> DerefMut for PeekMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.heap.data[0]
+    }
+}
+
+impl<T: Ord> Drop for PeekMut<'_, T> {
+    fn drop(&mut self) {
+        self.heap.sift_down(0);
+    }
+}
+
+/// Builds a heap in place in O(n).
+impl<T: Ord> From<Vector<T>> for BinaryHeap<T> {
+    fn from(data: Vector<T>) -> Self {
+        let mut heap = Self { data };
+        heap.rebuild();
+        heap
+    }
+}
+
+impl<T: Ord, const N: usize> From<[T; N]> for BinaryHeap<T> {
+    fn from(items: [T; N]) -> Self {
+        items.into_iter().collect()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BinaryHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vector<_>>())
+    }
+}
+
+impl<T: Ord> Extend<T> for BinaryHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.data.reserve(iter.size_hint().0);
+        iter.for_each(|item| self.push(item));
+    }
+}
+
+/// Yields the elements in heap order, see [`BinaryHeap::into_sorted_vec`] for sorted output.
+impl<T> IntoIterator for BinaryHeap<T> {
+    type Item = T;
+    type IntoIter = vector::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'h, T> IntoIterator for &'h BinaryHeap<T> {
+    type Item = &'h T;
+    type IntoIter = slice::Iter<'h, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Ord> Default for BinaryHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for BinaryHeap<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BinaryHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Reverse;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::vector;
+
+    fn assert_heap<T: Ord>(heap: &BinaryHeap<T>) {
+        for i in 1..heap.len() {
+            assert!(heap.data[(i - 1) / 2] >= heap.data[i]);
+        }
+    }
+
+    #[test]
+    fn push_pop_peek() {
+        let mut heap = BinaryHeap::new();
+        assert_eq!(heap.peek(), None);
+
+        for n in [3, 1, 4, 1, 5, 9, 2, 6] {
+            heap.push(n);
+            assert_heap(&heap);
+        }
+        assert_eq!(heap.len(), 8);
+        assert_eq!(heap.peek(), Some(&9));
+
+        let popped: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(popped, [9, 6, 5, 4, 3, 2, 1, 1]);
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn min_heap_with_reverse() {
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse("A Storm of Swords"));
+        heap.push(Reverse("A Clash of Kings"));
+        heap.push(Reverse("A Game of Thrones"));
+
+        assert_eq!(heap.pop(), Some(Reverse("A Clash of Kings")));
+        assert_eq!(heap.peek(), Some(&Reverse("A Game of Thrones")));
+    }
+
+    #[test]
+    fn peek_mut_sifts() {
+        let mut heap = BinaryHeap::from([2, 7, 5]);
+        {
+            let mut top = heap.peek_mut().unwrap();
+            assert_eq!(*top, 7);
+            *top = 1;
+        }
+        assert_heap(&heap);
+        assert_eq!(heap.peek(), Some(&5));
+
+        let top = heap.peek_mut().unwrap();
+        assert_eq!(PeekMut::pop(top), 5);
+        assert_eq!(heap.into_sorted_vec(), [1, 2]);
+
+        let mut empty = BinaryHeap::<u8>::new();
+        assert!(empty.peek_mut().is_none());
+    }
+
+    #[test]
+    fn heapify_and_sort() {
+        let mut rng = StdRng::seed_from_u64(39);
+        let items: Vector<u32> = (0..1000).map(|_| rng.gen_range(0..100)).collect();
+
+        let heap = BinaryHeap::from(items.clone());
+        assert_heap(&heap);
+        assert_eq!(heap.len(), 1000);
+
+        let mut expected: Vec<u32> = items.iter().copied().collect();
+        expected.sort();
+        assert_eq!(heap.into_sorted_vec(), expected[..]);
+    }
+
+    #[test]
+    fn append() {
+        // Few into many: pushes
+        let mut a: BinaryHeap<_> = (0..100).collect();
+        let mut b = BinaryHeap::from([500, -1]);
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_heap(&a);
+        assert_eq!(a.len(), 102);
+        assert_eq!(a.peek(), Some(&500));
+
+        // Many into few: swaps first, then still pushes the single element
+        let mut c = BinaryHeap::from([1000]);
+        let mut d: BinaryHeap<_> = (0..1000).rev().collect();
+        c.append(&mut d);
+        assert_heap(&c);
+        assert_eq!(c.len(), 1001);
+        assert!(c.into_sorted_vec().iter().copied().eq(0..=1000));
+
+        // Equal sizes: 2 * 2048 < 1024 * log2(1024), so it rebuilds
+        let mut evens: BinaryHeap<_> = (0..2048).step_by(2).collect();
+        let mut odds: BinaryHeap<_> = (1..2048).step_by(2).collect();
+        evens.append(&mut odds);
+        assert!(odds.is_empty());
+        assert_heap(&evens);
+        assert_eq!(evens.len(), 2048);
+        assert!(evens.into_sorted_vec().iter().copied().eq(0..2048));
+    }
+
+    #[test]
+    fn iterators() {
+        let heap: BinaryHeap<_> = vector![1, 3, 2].into_iter().collect();
+        assert_eq!(heap.iter().sum::<i32>(), 6);
+        assert_eq!((&heap).into_iter().count(), 3);
+        assert_eq!(format!("{heap:?}"), "[3, 1, 2]");
+
+        let mut all: Vec<_> = heap.clone().into_iter().collect();
+        all.sort();
+        assert_eq!(all, [1, 2, 3]);
+        assert_eq!(heap.into_vec(), [3, 1, 2]);
+    }
+}
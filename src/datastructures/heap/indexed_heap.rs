@@ -0,0 +1,363 @@
+use std::{cmp::Ordering, mem};
+
+use crate::datastructures::Vector;
+
+/// Refers to an item pushed into an [`IndexedHeap`].
+///
+/// Handles stay valid while their item is in the heap. Slots are reused after an item leaves,
+/// the generation tells a stale handle apart from the new item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    slot: usize,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    generation: u32,
+    /// Where the item sits in the heap, `None` while the slot is free
+    position: Option<usize>,
+}
+
+/// A min-heap whose items can be found again through the [`Handle`] returned by `push`.
+///
+/// Every handle maps to the item's current heap position, so besides popping the smallest item,
+/// any item can be reprioritized or removed in O(log n), as graph searches like Dijkstra's need.
+#[derive(Debug, Clone)]
+pub struct IndexedHeap<T> {
+    /// The items with their slots, as an implicit binary tree
+    heap: Vector<(T, usize)>,
+    slots: Vector<Slot>,
+    /// Slots without an item, reused before new ones are added
+    free: Vector<usize>,
+}
+
+impl<T: Ord> IndexedHeap<T> {
+    pub fn new() -> Self {
+        Self {
+            heap: Vector::new(),
+            slots: Vector::new(),
+            free: Vector::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn push(&mut self, item: T) -> Handle {
+        let pos = self.heap.len();
+        let slot = match self.free.pop() {
+            Some(slot) => slot,
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    position: None,
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.slots[slot].position = Some(pos);
+        self.heap.push((item, slot));
+        self.sift_up(pos);
+
+        Handle {
+            slot,
+            generation: self.slots[slot].generation,
+        }
+    }
+
+    /// Returns the smallest item.
+    pub fn peek(&self) -> Option<(Handle, &T)> {
+        self.heap
+            .first()
+            .map(|(item, slot)| (self.handle(*slot), item))
+    }
+
+    /// Removes the smallest item.
+    pub fn pop(&mut self) -> Option<(Handle, T)> {
+        (!self.is_empty()).then(|| self.remove_at(0))
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.position(handle).is_some()
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.position(handle).map(|pos| &self.heap[pos].0)
+    }
+
+    /// Replaces the item of `handle` with a smaller or equal one, returning the old item.
+    ///
+    /// Returns `None` if the handle's item is no longer in the heap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `item` is greater than the current item.
+    pub fn decrease_key(&mut self, handle: Handle, item: T) -> Option<T> {
+        let pos = self.position(handle)?;
+        assert!(
+            item <= self.heap[pos].0,
+            "decrease_key called with a greater item"
+        );
+
+        let old = mem::replace(&mut self.heap[pos].0, item);
+        self.sift_up(pos);
+        Some(old)
+    }
+
+    /// Replaces the item of `handle`, moving it up or down as needed, and returns the old item.
+    ///
+    /// Returns `None` if the handle's item is no longer in the heap.
+    pub fn change_priority(&mut self, handle: Handle, item: T) -> Option<T> {
+        let pos = self.position(handle)?;
+        let old = mem::replace(&mut self.heap[pos].0, item);
+
+        match self.heap[pos].0.cmp(&old) {
+            Ordering::Less => self.sift_up(pos),
+            Ordering::Greater => self.sift_down(pos),
+            Ordering::Equal => {}
+        }
+        Some(old)
+    }
+
+    /// Removes the item of `handle`, if it is still in the heap.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let pos = self.position(handle)?;
+        Some(self.remove_at(pos).1)
+    }
+
+    /// Iterates over the items in heap order, which is not sorted.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> + '_ {
+        self.heap
+            .iter()
+            .map(|(item, slot)| (self.handle(*slot), item))
+    }
+
+    fn handle(&self, slot: usize) -> Handle {
+        Handle {
+            slot,
+            generation: self.slots[slot].generation,
+        }
+    }
+
+    fn position(&self, handle: Handle) -> Option<usize> {
+        self.slots
+            .get(handle.slot)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.position)
+    }
+
+    /// Takes the item at `pos` out and frees its slot, moving the last item into the hole.
+    fn remove_at(&mut self, pos: usize) -> (Handle, T) {
+        let last = self.heap.len() - 1;
+        self.swap(pos, last);
+        let (item, slot) = self.heap.pop().unwrap();
+
+        let handle = self.handle(slot);
+        let freed = &mut self.slots[slot];
+        freed.position = None;
+        freed.generation = freed.generation.wrapping_add(1);
+        self.free.push(slot);
+
+        if pos < self.heap.len() {
+            // The moved item can be out of order in either direction
+            self.sift_up(pos);
+            self.sift_down(pos);
+        }
+        (handle, item)
+    }
+
+    /// Swaps two items and updates their slots.
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.slots[self.heap[a].1].position = Some(a);
+        self.slots[self.heap[b].1].position = Some(b);
+    }
+
+    fn sift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.heap[pos].0 >= self.heap[parent].0 {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut pos: usize) {
+        let len = self.heap.len();
+        loop {
+            let mut child = 2 * pos + 1;
+            if child >= len {
+                break;
+            }
+            if child + 1 < len && self.heap[child + 1].0 < self.heap[child].0 {
+                child += 1;
+            }
+            if self.heap[pos].0 <= self.heap[child].0 {
+                break;
+            }
+            self.swap(pos, child);
+            pos = child;
+        }
+    }
+}
+
+impl<T: Ord> Default for IndexedHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn assert_heap<T: Ord>(heap: &IndexedHeap<T>) {
+        for i in 1..heap.len() {
+            assert!(heap.heap[(i - 1) / 2].0 <= heap.heap[i].0);
+        }
+        for (pos, (_, slot)) in heap.heap.iter().enumerate() {
+            assert_eq!(heap.slots[*slot].position, Some(pos));
+        }
+    }
+
+    #[test]
+    fn push_pop() {
+        let mut heap = IndexedHeap::new();
+        let handles: Vec<Handle> = [5, 3, 8, 1].into_iter().map(|n| heap.push(n)).collect();
+        assert_heap(&heap);
+
+        assert_eq!(heap.peek(), Some((handles[3], &1)));
+        assert_eq!(heap.pop(), Some((handles[3], 1)));
+        assert_eq!(heap.pop(), Some((handles[1], 3)));
+        assert!(!heap.contains(handles[1]));
+        assert_eq!(heap.get(handles[2]), Some(&8));
+        assert_eq!(heap.len(), 2);
+    }
+
+    #[test]
+    fn decrease_key() {
+        let mut heap = IndexedHeap::new();
+        let a = heap.push((10, "A Game of Thrones"));
+        let b = heap.push((20, "A Clash of Kings"));
+        heap.push((15, "A Storm of Swords"));
+
+        assert_eq!(heap.decrease_key(b, (5, "A Clash of Kings")).unwrap().0, 20);
+        assert_heap(&heap);
+        assert_eq!(heap.peek().map(|(h, _)| h), Some(b));
+
+        heap.remove(a);
+        assert_eq!(heap.decrease_key(a, (0, "")), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "decrease_key called with a greater item")]
+    fn decrease_key_greater() {
+        let mut heap = IndexedHeap::new();
+        let h = heap.push(1);
+        heap.decrease_key(h, 2);
+    }
+
+    #[test]
+    fn change_priority_and_remove() {
+        let mut heap = IndexedHeap::new();
+        let handles: Vec<Handle> = (0..10).map(|n| heap.push(n)).collect();
+
+        assert_eq!(heap.change_priority(handles[0], 100), Some(0));
+        assert_eq!(heap.change_priority(handles[9], -1), Some(9));
+        assert_heap(&heap);
+        assert_eq!(heap.remove(handles[5]), Some(5));
+        assert_eq!(heap.remove(handles[5]), None);
+        assert_heap(&heap);
+
+        let order: Vec<i32> = std::iter::from_fn(|| heap.pop().map(|(_, n)| n)).collect();
+        assert_eq!(order, [-1, 1, 2, 3, 4, 6, 7, 8, 100]);
+    }
+
+    #[test]
+    fn stale_handles() {
+        let mut heap = IndexedHeap::new();
+        let old = heap.push("A Feast for Crows");
+        heap.pop();
+
+        // Reuses the slot of `old`
+        let new = heap.push("A Dance with Dragons");
+        assert_ne!(old, new);
+        assert_eq!(heap.get(old), None);
+        assert_eq!(heap.remove(old), None);
+        assert_eq!(heap.get(new), Some(&"A Dance with Dragons"));
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = StdRng::seed_from_u64(39);
+        let mut heap = IndexedHeap::new();
+        let mut live: Vec<(Handle, u32)> = Vec::new();
+
+        for _ in 0..2000 {
+            match rng.gen_range(0..4) {
+                0 | 1 => {
+                    let n = rng.gen_range(0..1000);
+                    live.push((heap.push(n), n));
+                }
+                2 if !live.is_empty() => {
+                    let i = rng.gen_range(0..live.len());
+                    let n = rng.gen_range(0..1000);
+                    assert_eq!(heap.change_priority(live[i].0, n), Some(live[i].1));
+                    live[i].1 = n;
+                }
+                3 if !live.is_empty() => {
+                    let (handle, n) = live.swap_remove(rng.gen_range(0..live.len()));
+                    assert_eq!(heap.remove(handle), Some(n));
+                }
+                _ => {}
+            }
+        }
+        assert_heap(&heap);
+
+        live.sort_by_key(|(_, n)| *n);
+        let popped: Vec<u32> = std::iter::from_fn(|| heap.pop().map(|(_, n)| n)).collect();
+        assert!(popped.into_iter().eq(live.into_iter().map(|(_, n)| n)));
+    }
+
+    #[test]
+    fn dijkstra() {
+        // Edges as (from, to, weight)
+        let edges = [(0, 1, 7), (0, 2, 9), (0, 5, 14), (1, 2, 10), (1, 3, 15)];
+        let edges = edges
+            .iter()
+            .chain(&[(2, 3, 11), (2, 5, 2), (3, 4, 6), (4, 5, 9)]);
+        let mut adjacent = vec![Vec::new(); 6];
+        for &(a, b, w) in edges {
+            adjacent[a].push((b, w));
+            adjacent[b].push((a, w));
+        }
+
+        let mut dist = [u32::MAX; 6];
+        let mut heap = IndexedHeap::new();
+        let handles: Vec<Handle> = (0..6).map(|n| heap.push((u32::MAX, n))).collect();
+        heap.decrease_key(handles[0], (0, 0));
+
+        while let Some((_, (d, node))) = heap.pop() {
+            dist[node] = d;
+            for &(next, w) in &adjacent[node] {
+                if let Some(&(current, _)) = heap.get(handles[next]) {
+                    if d + w < current {
+                        heap.decrease_key(handles[next], (d + w, next));
+                    }
+                }
+            }
+        }
+
+        assert_eq!(dist, [0, 7, 9, 20, 20, 11]);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod binary_heap;
+pub mod indexed_heap;
+
+pub use binary_heap::{BinaryHeap, PeekMut};
+pub use indexed_heap::{Handle, IndexedHeap};
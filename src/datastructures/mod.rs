@@ -1,5 +1,36 @@
-mod hashmap;
-mod vector;
+pub mod allocator;
+pub mod arena;
+pub mod array_vec;
+pub mod bits;
+pub mod concurrent_vec;
+pub mod gap_buffer;
+pub mod grid;
+pub mod hashmap;
+pub mod heap;
+pub mod persistent_vec;
+pub mod rope;
+pub mod segmented_vec;
+pub(crate) mod segments;
+pub mod shared_vec;
+pub mod small_vec;
+pub mod sorted_vec;
+pub mod vec_deque;
+pub mod vector;
 
+pub use allocator::{AllocError, Allocator, CountingAllocator, Global};
+pub use arena::{GenerationalArena, Slab};
+pub use array_vec::{ArrayVec, CapacityError};
+pub use bits::{BitSet, BitVec, RankSelect};
+pub use concurrent_vec::ConcurrentVec;
+pub use gap_buffer::GapBuffer;
+pub use grid::Grid;
 pub use hashmap::*;
-pub use vector::Vector;
+pub use heap::{BinaryHeap, IndexedHeap};
+pub use persistent_vec::PersistentVec;
+pub use rope::Rope;
+pub use segmented_vec::SegmentedVec;
+pub use shared_vec::SharedVec;
+pub use small_vec::SmallVec;
+pub use sorted_vec::{SortedVecMap, SortedVecSet};
+pub use vec_deque::VecDeque;
+pub use vector::{GrowthPolicy, RawVec, TryReserveError, Vector};
@@ -0,0 +1,149 @@
+use std::{iter::FusedIterator, slice};
+
+use super::node::Node;
+use crate::datastructures::Vector;
+
+/// Walks the leaves of a tree from both ends, followed by the tail.
+///
+/// Each stack holds the branches on the way down with the next child to visit, the back
+/// one counting children from the end.
+#[derive(Clone)]
+struct Leaves<'v, T> {
+    front: Vector<(&'v Node<T>, usize)>,
+    back: Vector<(&'v Node<T>, usize)>,
+    tail: Option<&'v [T]>,
+    /// The elements in leaves neither end has reached, stops the ends from crossing
+    remaining: usize,
+}
+
+impl<'v, T> Leaves<'v, T> {
+    fn take(&mut self, leaf: &'v [T]) -> Option<&'v [T]> {
+        self.remaining -= leaf.len();
+        Some(leaf)
+    }
+}
+
+impl<'v, T> Iterator for Leaves<'v, T> {
+    type Item = &'v [T];
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            let Some(&(node, next)) = self.front.last() else {
+                let tail = self.tail.take()?;
+                return self.take(tail);
+            };
+            match node {
+                Node::Leaf(elems) => {
+                    self.front.pop();
+                    if !elems.is_empty() {
+                        return self.take(elems);
+                    }
+                }
+                Node::Branch { children, .. } => match children.get(next) {
+                    Some(child) => {
+                        self.front.last_mut().unwrap().1 += 1;
+                        self.front.push((child, 0));
+                    }
+                    None => {
+                        self.front.pop();
+                    }
+                },
+            }
+        }
+        None
+    }
+}
+
+impl<T> DoubleEndedIterator for Leaves<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if let Some(tail) = self.tail.take().filter(|tail| !tail.is_empty()) {
+            return self.take(tail);
+        }
+
+        while self.remaining > 0 {
+            let &(node, left) = self.back.last()?;
+            match node {
+                Node::Leaf(elems) => {
+                    self.back.pop();
+                    if !elems.is_empty() {
+                        return self.take(elems);
+                    }
+                }
+                Node::Branch { children, .. } if left > 0 => {
+                    self.back.last_mut().unwrap().1 -= 1;
+                    let child = &children[left - 1];
+                    self.back.push((child, child.children().len()));
+                }
+                Node::Branch { .. } => {
+                    self.back.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over references to the elements of a
+/// [`PersistentVec`](super::PersistentVec), front to back
+#[derive(Clone)]
+pub struct Iter<'v, T> {
+    leaves: Leaves<'v, T>,
+    front: slice::Iter<'v, T>,
+    back: slice::Iter<'v, T>,
+    len: usize,
+}
+
+impl<'v, T> Iter<'v, T> {
+    pub(crate) fn new(root: &'v Node<T>, tail: &'v [T], len: usize) -> Self {
+        Self {
+            leaves: Leaves {
+                front: Vector::from_iter([(root, 0)]),
+                back: Vector::from_iter([(root, root.children().len())]),
+                tail: Some(tail),
+                remaining: len,
+            },
+            front: [].iter(),
+            back: [].iter(),
+            len,
+        }
+    }
+}
+
+impl<'v, T> Iterator for Iter<'v, T> {
+    type Item = &'v T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let elem = loop {
+            if let Some(elem) = self.front.next() {
+                break elem;
+            }
+            match self.leaves.next() {
+                Some(leaf) => self.front = leaf.iter(),
+                None => break self.back.next()?,
+            }
+        };
+        self.len -= 1;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let elem = loop {
+            if let Some(elem) = self.back.next_back() {
+                break elem;
+            }
+            match self.leaves.next_back() {
+                Some(leaf) => self.back = leaf.iter(),
+                None => break self.front.next_back()?,
+            }
+        };
+        self.len -= 1;
+        Some(elem)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
@@ -0,0 +1,7 @@
+pub mod iter;
+mod node;
+#[allow(clippy::module_inception)]
+pub mod persistent_vec;
+
+pub use iter::Iter;
+pub use persistent_vec::PersistentVec;
@@ -0,0 +1,344 @@
+// The tree under a `PersistentVec`: leaves hold up to `WIDTH` elements, branches up to
+// `WIDTH` children. Every operation copies the path it changes and shares the rest.
+//
+// A branch at `shift` has children holding up to `1 << shift` elements each. While all
+// children but the last are full the branch is balanced and finds a child with
+// `index >> shift`, otherwise it keeps cumulative sizes to search instead. Concatenation
+// is what produces the unbalanced ("relaxed") branches.
+
+use std::rc::Rc;
+
+use crate::datastructures::Vector;
+
+pub(crate) const BITS: usize = 5;
+pub(crate) const WIDTH: usize = 1 << BITS;
+/// How many nodes more than the minimum a concatenation may leave behind
+const EXTRA: usize = 2;
+
+pub(crate) enum Node<T> {
+    Leaf(Vector<T>),
+    Branch {
+        children: Vector<Rc<Node<T>>>,
+        /// The cumulative number of elements up to each child, only when unbalanced
+        sizes: Option<Vector<usize>>,
+        len: usize,
+    },
+}
+
+impl<T> Node<T> {
+    pub(crate) fn empty_branch() -> Self {
+        Node::Branch {
+            children: Vector::new(),
+            sizes: None,
+            len: 0,
+        }
+    }
+
+    /// Builds a branch at `shift`, working out whether it needs a size table.
+    pub(crate) fn branch(children: Vector<Rc<Node<T>>>, shift: usize) -> Self {
+        let full = 1 << shift;
+        let balanced = children
+            .iter()
+            .rev()
+            .skip(1)
+            .all(|child| child.len() == full);
+
+        let mut len = 0;
+        let sizes: Vector<usize> = children
+            .iter()
+            .map(|child| {
+                len += child.len();
+                len
+            })
+            .collect();
+
+        Node::Branch {
+            children,
+            sizes: (!balanced).then_some(sizes),
+            len,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Node::Leaf(elems) => elems.len(),
+            Node::Branch { len, .. } => *len,
+        }
+    }
+
+    /// Elements of a leaf or children of a branch.
+    fn slots(&self) -> usize {
+        match self {
+            Node::Leaf(elems) => elems.len(),
+            Node::Branch { children, .. } => children.len(),
+        }
+    }
+
+    pub(crate) fn children(&self) -> &[Rc<Node<T>>] {
+        match self {
+            Node::Leaf(_) => &[],
+            Node::Branch { children, .. } => children,
+        }
+    }
+
+    /// Finds the child of a branch at `shift` holding `index`, and the index within it.
+    fn locate(&self, index: usize, shift: usize) -> (usize, usize) {
+        match self {
+            Node::Leaf(_) => (index, 0),
+            Node::Branch { sizes: None, .. } => (index >> shift, index & ((1 << shift) - 1)),
+            Node::Branch {
+                sizes: Some(sizes), ..
+            } => {
+                // No child holds more than `1 << shift`, so the radix guess is a lower bound
+                let mut slot = index >> shift;
+                while sizes[slot] <= index {
+                    slot += 1;
+                }
+                let before = if slot == 0 { 0 } else { sizes[slot - 1] };
+                (slot, index - before)
+            }
+        }
+    }
+
+    pub(crate) fn get(&self, mut index: usize, mut shift: usize) -> &T {
+        let mut node = self;
+        loop {
+            match node {
+                Node::Leaf(elems) => return &elems[index],
+                Node::Branch { children, .. } => {
+                    let (slot, sub) = node.locate(index, shift);
+                    node = &children[slot];
+                    index = sub;
+                    shift -= BITS;
+                }
+            }
+        }
+    }
+}
+
+impl<T: Clone> Node<T> {
+    /// Returns a copy of the subtree at `shift` with `index` replaced by `elem`.
+    pub(crate) fn set(&self, index: usize, shift: usize, elem: T) -> Self {
+        match self {
+            Node::Leaf(elems) => {
+                let mut elems = elems.clone();
+                elems[index] = elem;
+                Node::Leaf(elems)
+            }
+            Node::Branch {
+                children,
+                sizes,
+                len,
+            } => {
+                let (slot, sub) = self.locate(index, shift);
+                let mut children = children.clone();
+                children[slot] = Rc::new(children[slot].set(sub, shift - BITS, elem));
+                Node::Branch {
+                    children,
+                    sizes: sizes.clone(),
+                    len: *len,
+                }
+            }
+        }
+    }
+
+    /// Appends `leaf` as the last leaf of the branch at `shift`, `None` if it is full.
+    pub(crate) fn push_leaf(&self, shift: usize, leaf: Rc<Node<T>>) -> Option<Self> {
+        let children = self.children();
+        let mut children = if shift == BITS {
+            if children.len() == WIDTH {
+                return None;
+            }
+            let mut children = Vector::from_iter(children.iter().cloned());
+            children.push(leaf);
+            children
+        } else {
+            let pushed = children
+                .last()
+                .and_then(|last| last.push_leaf(shift - BITS, Rc::clone(&leaf)));
+            let mut children = Vector::from_iter(children.iter().cloned());
+            match pushed {
+                Some(last) => *children.last_mut().unwrap() = Rc::new(last),
+                None if children.len() < WIDTH => children.push(Node::path(leaf, shift - BITS)),
+                None => return None,
+            }
+            children
+        };
+        children.shrink_to_fit();
+        Some(Node::branch(children, shift))
+    }
+
+    /// Wraps `node` in single child branches up to `shift`.
+    pub(crate) fn path(node: Rc<Node<T>>, shift: usize) -> Rc<Self> {
+        if shift == 0 {
+            return node;
+        }
+        let mut children = Vector::with_capacity(1);
+        children.push(Node::path(node, shift - BITS));
+        Rc::new(Node::branch(children, shift))
+    }
+
+    /// The first `at` elements of the subtree at `shift`, `0 < at <= len`.
+    pub(crate) fn split_left(self: &Rc<Self>, at: usize, shift: usize) -> Rc<Self> {
+        if at == self.len() {
+            return Rc::clone(self);
+        }
+        match &**self {
+            Node::Leaf(elems) => {
+                Rc::new(Node::Leaf(Vector::from_iter(elems[..at].iter().cloned())))
+            }
+            Node::Branch { children, .. } => {
+                let (slot, sub) = self.locate(at, shift);
+                let mut left = Vector::from_iter(children[..slot].iter().cloned());
+                if sub > 0 {
+                    left.push(children[slot].split_left(sub, shift - BITS));
+                }
+                Rc::new(Node::branch(left, shift))
+            }
+        }
+    }
+
+    /// Everything from element `at` on in the subtree at `shift`, `0 <= at < len`.
+    pub(crate) fn split_right(self: &Rc<Self>, at: usize, shift: usize) -> Rc<Self> {
+        if at == 0 {
+            return Rc::clone(self);
+        }
+        match &**self {
+            Node::Leaf(elems) => {
+                Rc::new(Node::Leaf(Vector::from_iter(elems[at..].iter().cloned())))
+            }
+            Node::Branch { children, .. } => {
+                let (slot, sub) = self.locate(at, shift);
+                let mut right = Vector::with_capacity(children.len() - slot);
+                right.push(children[slot].split_right(sub, shift - BITS));
+                right.extend(children[slot + 1..].iter().cloned());
+                Rc::new(Node::branch(right, shift))
+            }
+        }
+    }
+
+    /// Concatenates two non-empty trees, returns the new root and its shift.
+    pub(crate) fn concat(
+        left: &Rc<Self>,
+        left_shift: usize,
+        right: &Rc<Self>,
+        right_shift: usize,
+    ) -> (Rc<Self>, usize) {
+        let mut roots = concat_sub_trees(left, left_shift, right, right_shift);
+        let shift = left_shift.max(right_shift);
+        if roots.len() == 1 {
+            (roots.pop().unwrap(), shift)
+        } else {
+            (Rc::new(Node::branch(roots, shift + BITS)), shift + BITS)
+        }
+    }
+}
+
+/// Merges the right edge of `left` with the left edge of `right`, returns one or two
+/// nodes at the larger of the shifts.
+fn concat_sub_trees<T: Clone>(
+    left: &Rc<Node<T>>,
+    left_shift: usize,
+    right: &Rc<Node<T>>,
+    right_shift: usize,
+) -> Vector<Rc<Node<T>>> {
+    if left_shift == 0 && right_shift == 0 {
+        return Vector::from_iter([Rc::clone(left), Rc::clone(right)]);
+    }
+
+    let shift = left_shift.max(right_shift);
+    let (left_children, right_children) = (left.children(), right.children());
+    // Only the taller side (or both) descends, the other takes part as it is
+    let (left_rest, left_edge, left_edge_shift) = if left_shift == shift {
+        let (last, rest) = left_children.split_last().unwrap();
+        (rest, last, left_shift - BITS)
+    } else {
+        (&[][..], left, left_shift)
+    };
+    let (right_rest, right_edge, right_edge_shift) = if right_shift == shift {
+        let (first, rest) = right_children.split_first().unwrap();
+        (rest, first, right_shift - BITS)
+    } else {
+        (&[][..], right, right_shift)
+    };
+
+    let middle = concat_sub_trees(left_edge, left_edge_shift, right_edge, right_edge_shift);
+    let mut all = Vector::with_capacity(left_rest.len() + middle.len() + right_rest.len());
+    all.extend(left_rest.iter().cloned());
+    all.extend(middle);
+    all.extend(right_rest.iter().cloned());
+
+    let mut all = rebalance(all, shift - BITS);
+    if all.len() <= WIDTH {
+        Vector::from_iter([Rc::new(Node::branch(all, shift))])
+    } else {
+        let rest = all.split_off(WIDTH);
+        Vector::from_iter([
+            Rc::new(Node::branch(all, shift)),
+            Rc::new(Node::branch(rest, shift)),
+        ])
+    }
+}
+
+/// Redistributes the slots of `nodes` (all at `shift`) so that there are at most `EXTRA`
+/// more nodes than needed, leaving nodes that are nearly full alone.
+fn rebalance<T: Clone>(nodes: Vector<Rc<Node<T>>>, shift: usize) -> Vector<Rc<Node<T>>> {
+    let mut plan: Vector<usize> = nodes.iter().map(|node| node.slots()).collect();
+    let total: usize = plan.iter().sum();
+    let optimal = total.div_ceil(WIDTH);
+
+    let mut i = 0;
+    while plan.len() > optimal + EXTRA {
+        while plan[i] >= WIDTH - EXTRA / 2 {
+            i += 1;
+        }
+        // Spread node `i` over the ones after it, then drop it
+        let mut remaining = plan[i];
+        loop {
+            let size = (remaining + plan[i + 1]).min(WIDTH);
+            remaining = remaining + plan[i + 1] - size;
+            plan[i] = size;
+            i += 1;
+            if remaining == 0 {
+                break;
+            }
+        }
+        plan.remove(i);
+        i -= 1;
+    }
+
+    let mut result = Vector::with_capacity(plan.len());
+    let (mut node, mut offset) = (0, 0);
+    for &size in plan.iter() {
+        if offset == 0 && nodes[node].slots() == size {
+            result.push(Rc::clone(&nodes[node]));
+            node += 1;
+            continue;
+        }
+
+        let mut elems = Vector::new();
+        let mut children = Vector::new();
+        let mut needed = size;
+        while needed > 0 {
+            let take = needed.min(nodes[node].slots() - offset);
+            match &*nodes[node] {
+                Node::Leaf(from) => elems.extend_from_slice(&from[offset..offset + take]),
+                Node::Branch { children: from, .. } => {
+                    children.extend_from_slice(&from[offset..offset + take])
+                }
+            }
+            needed -= take;
+            offset += take;
+            if offset == nodes[node].slots() {
+                node += 1;
+                offset = 0;
+            }
+        }
+        result.push(Rc::new(if shift == 0 {
+            Node::Leaf(elems)
+        } else {
+            Node::branch(children, shift)
+        }));
+    }
+    result
+}
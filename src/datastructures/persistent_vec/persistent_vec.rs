@@ -0,0 +1,421 @@
+use std::{fmt, ops::Index, rc::Rc};
+
+use super::{
+    iter::Iter,
+    node::{Node, BITS, WIDTH},
+};
+use crate::datastructures::Vector;
+
+/// An immutable vector where every update returns a new version, sharing most of its memory
+/// with the old one.
+///
+/// The elements live in a relaxed radix balanced (RRB) tree of `Rc`ed nodes with 32 slots
+/// each, plus a tail buffer for the last few elements. Cloning is O(1), `get`, `set` and
+/// `push_back` are O(log₃₂ n), and `split_at` and `concat` are O(log n) too.
+pub struct PersistentVec<T> {
+    root: Rc<Node<T>>,
+    /// The shift of `root`, its children hold up to `1 << shift` elements each
+    shift: usize,
+    /// The last elements, not in the tree yet
+    tail: Rc<Vector<T>>,
+    len: usize,
+}
+
+impl<T> PersistentVec<T> {
+    pub fn new() -> Self {
+        Self {
+            root: Rc::new(Node::empty_branch()),
+            shift: BITS,
+            tail: Rc::new(Vector::new()),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+
+        let tree_len = self.tree_len();
+        Some(if index >= tree_len {
+            &self.tail[index - tree_len]
+        } else {
+            self.root.get(index, self.shift)
+        })
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.get(self.len.checked_sub(1)?)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(&self.root, &self.tail, self.len)
+    }
+
+    /// Whether both versions share the same memory, which makes them equal.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.root, &other.root) && Rc::ptr_eq(&self.tail, &other.tail)
+    }
+
+    fn tree_len(&self) -> usize {
+        self.len - self.tail.len()
+    }
+
+    /// Drops branches with a single child off the top.
+    fn with_root(mut root: Rc<Node<T>>, mut shift: usize, tail: Rc<Vector<T>>) -> Self {
+        while shift > BITS && root.children().len() == 1 {
+            root = Rc::clone(&root.children()[0]);
+            shift -= BITS;
+        }
+        let len = root.len() + tail.len();
+        Self {
+            root,
+            shift,
+            tail,
+            len,
+        }
+    }
+}
+
+impl<T: Clone> PersistentVec<T> {
+    /// Returns a new version with `elem` appended.
+    pub fn push_back(&self, elem: T) -> Self {
+        if self.tail.len() < WIDTH {
+            let mut tail = Vector::with_capacity(self.tail.len() + 1);
+            tail.extend_from_slice(&self.tail);
+            tail.push(elem);
+            return Self {
+                root: Rc::clone(&self.root),
+                shift: self.shift,
+                tail: Rc::new(tail),
+                len: self.len + 1,
+            };
+        }
+
+        let (root, shift) = self.push_leaf((*self.tail).clone());
+        let mut tail = Vector::with_capacity(WIDTH);
+        tail.push(elem);
+        Self {
+            root,
+            shift,
+            tail: Rc::new(tail),
+            len: self.len + 1,
+        }
+    }
+
+    /// Returns a new version with the element at `index` replaced by `elem`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&self, index: usize, elem: T) -> Self {
+        let len = self.len;
+        assert!(index < len, "index (is {index}) should be < len (is {len})");
+
+        let tree_len = self.tree_len();
+        if index >= tree_len {
+            let mut tail = (*self.tail).clone();
+            tail[index - tree_len] = elem;
+            Self {
+                root: Rc::clone(&self.root),
+                shift: self.shift,
+                tail: Rc::new(tail),
+                len,
+            }
+        } else {
+            Self {
+                root: Rc::new(self.root.set(index, self.shift, elem)),
+                shift: self.shift,
+                tail: Rc::clone(&self.tail),
+                len,
+            }
+        }
+    }
+
+    /// Splits into the first `at` elements and the rest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_at(&self, at: usize) -> (Self, Self) {
+        let len = self.len;
+        assert!(
+            at <= len,
+            "split index (is {at}) should be <= len (is {len})"
+        );
+
+        let tree_len = self.tree_len();
+        if at >= tree_len {
+            let at = at - tree_len;
+            let left = Vector::from_iter(self.tail[..at].iter().cloned());
+            let right = Vector::from_iter(self.tail[at..].iter().cloned());
+            return (
+                Self::with_root(Rc::clone(&self.root), self.shift, Rc::new(left)),
+                Self::with_root(Rc::new(Node::empty_branch()), BITS, Rc::new(right)),
+            );
+        }
+
+        let empty = Rc::new(Vector::new());
+        let left = if at == 0 {
+            Self::new()
+        } else {
+            Self::with_root(self.root.split_left(at, self.shift), self.shift, empty)
+        };
+        let right = Self::with_root(
+            self.root.split_right(at, self.shift),
+            self.shift,
+            Rc::clone(&self.tail),
+        );
+        (left, right)
+    }
+
+    /// Returns a new version with the elements of `other` after those of `self`.
+    pub fn concat(&self, other: &Self) -> Self {
+        if self.is_empty() {
+            return other.clone();
+        }
+        if other.is_empty() {
+            return self.clone();
+        }
+
+        if other.tree_len() == 0 {
+            // Only tails to join, keep the leaves full
+            let mut tail = Vector::with_capacity(self.tail.len() + other.tail.len());
+            tail.extend_from_slice(&self.tail);
+            tail.extend_from_slice(&other.tail);
+            if tail.len() <= WIDTH {
+                return Self::with_root(Rc::clone(&self.root), self.shift, Rc::new(tail));
+            }
+            let rest = tail.split_off(WIDTH);
+            let (root, shift) = self.push_leaf(tail);
+            return Self::with_root(root, shift, Rc::new(rest));
+        }
+
+        let (root, shift) = self.push_leaf((*self.tail).clone());
+        let (root, shift) = Node::concat(&root, shift, &other.root, other.shift);
+        Self::with_root(root, shift, Rc::clone(&other.tail))
+    }
+
+    /// Appends `leaf` to the tree, adding a level if the tree is full.
+    fn push_leaf(&self, leaf: Vector<T>) -> (Rc<Node<T>>, usize) {
+        if leaf.is_empty() {
+            return (Rc::clone(&self.root), self.shift);
+        }
+
+        let leaf = Rc::new(Node::Leaf(leaf));
+        match self.root.push_leaf(self.shift, Rc::clone(&leaf)) {
+            Some(root) => (Rc::new(root), self.shift),
+            None => {
+                let shift = self.shift + BITS;
+                let children =
+                    Vector::from_iter([Rc::clone(&self.root), Node::path(leaf, self.shift)]);
+                (Rc::new(Node::branch(children, shift)), shift)
+            }
+        }
+    }
+}
+
+impl<T> Clone for PersistentVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            root: Rc::clone(&self.root),
+            shift: self.shift,
+            tail: Rc::clone(&self.tail),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Default for PersistentVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialEq> PartialEq for PersistentVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && (self.ptr_eq(other) || self.iter().eq(other.iter()))
+    }
+}
+
+impl<T: Eq> Eq for PersistentVec<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Index<usize> for PersistentVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T: Clone> FromIterator<T> for PersistentVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::new(), |vec, elem| vec.push_back(elem))
+    }
+}
+
+impl<'v, T> IntoIterator for &'v PersistentVec<T> {
+    type Item = &'v T;
+    type IntoIter = Iter<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn push_get_set() {
+        let empty = PersistentVec::new();
+        let one = empty.push_back("A Game of Thrones");
+        let two = one.push_back("A Clash of Kings");
+        let edited = two.set(0, "A Storm of Swords");
+
+        assert!(empty.is_empty());
+        assert_eq!(one.len(), 1);
+        assert_eq!(
+            format!("{two:?}"),
+            r#"["A Game of Thrones", "A Clash of Kings"]"#
+        );
+        assert_eq!(edited[0], "A Storm of Swords");
+        // Older versions are untouched
+        assert_eq!(two[0], "A Game of Thrones");
+        assert_eq!(edited.last(), Some(&"A Clash of Kings"));
+        assert_eq!(edited.get(2), None);
+    }
+
+    #[test]
+    fn deep_tree() {
+        let n = WIDTH * WIDTH * WIDTH + 17;
+        let vec: PersistentVec<usize> = (0..n).collect();
+        assert_eq!(vec.len(), n);
+        assert!(vec.iter().copied().eq(0..n));
+        assert!(vec.iter().rev().copied().eq((0..n).rev()));
+        assert_eq!(vec.iter().len(), n);
+
+        let edited = vec.set(5000, 0).set(n - 1, 0);
+        assert_eq!((edited[5000], edited[n - 1], edited[4999]), (0, 0, 4999));
+        assert_eq!((vec[5000], vec[n - 1]), (5000, n - 1));
+        assert_ne!(edited, vec);
+    }
+
+    #[test]
+    fn shares_structure() {
+        let book = Rc::new("A Feast for Crows");
+        let vec: PersistentVec<_> = (0..100).map(|_| Rc::clone(&book)).collect();
+        assert_eq!(Rc::strong_count(&book), 101);
+
+        // Only the path to the changed leaf is copied
+        let edited = vec.set(3, Rc::new("A Dance with Dragons"));
+        assert_eq!(Rc::strong_count(&book), 101 + WIDTH - 1);
+        let copy = edited.clone();
+        assert!(copy.ptr_eq(&edited));
+
+        drop((vec, edited, copy));
+        assert_eq!(Rc::strong_count(&book), 1);
+    }
+
+    #[test]
+    fn split_and_concat() {
+        let vec: PersistentVec<usize> = (0..2000).collect();
+        for at in [0, 1, 31, 32, 1000, 1024, 1999, 2000] {
+            let (left, right) = vec.split_at(at);
+            assert!(left.iter().copied().eq(0..at));
+            assert!(right.iter().copied().eq(at..2000));
+            assert_eq!(left.concat(&right), vec);
+        }
+
+        let (left, _) = vec.split_at(100);
+        let extended = left.push_back(7).concat(&left);
+        assert_eq!(extended.len(), 201);
+        assert_eq!(extended[100], 7);
+        assert_eq!(extended[200], 99);
+    }
+
+    #[test]
+    #[should_panic(expected = "split index (is 4) should be <= len (is 3)")]
+    fn split_out_of_bounds() {
+        PersistentVec::from_iter([1, 2, 3]).split_at(4);
+    }
+
+    #[test]
+    fn matches_vector() {
+        let mut rng = StdRng::seed_from_u64(46);
+        let mut vec = PersistentVec::new();
+        let mut expected: Vec<u32> = Vec::new();
+        let mut versions: Vec<(PersistentVec<u32>, Vec<u32>)> = Vec::new();
+
+        for _ in 0..2000 {
+            match rng.gen_range(0..10) {
+                0..=3 => {
+                    let elem = rng.gen::<u32>();
+                    vec = vec.push_back(elem);
+                    expected.push(elem);
+                }
+                4 if !expected.is_empty() => {
+                    let i = rng.gen_range(0..expected.len());
+                    let elem = rng.gen::<u32>();
+                    vec = vec.set(i, elem);
+                    expected[i] = elem;
+                }
+                5 | 6 => {
+                    let at = rng.gen_range(0..=expected.len());
+                    let (left, right) = vec.split_at(at);
+                    let (l, r) = expected.split_at(at);
+                    assert!(left.iter().eq(l) && right.iter().eq(r));
+                    vec = if rng.gen() { right.concat(&left) } else { left };
+                    expected = if vec.len() == expected.len() {
+                        [r, l].concat()
+                    } else {
+                        l.to_vec()
+                    };
+                }
+                7 => {
+                    // Concatenating with an older version grows the tree in odd shapes
+                    let (other, other_expected) = versions
+                        .get(rng.gen_range(0..versions.len().max(1)))
+                        .cloned()
+                        .unwrap_or_default();
+                    vec = vec.concat(&other);
+                    expected.extend_from_slice(&other_expected);
+                }
+                _ => versions.push((vec.clone(), expected.clone())),
+            }
+
+            assert_eq!(vec.len(), expected.len());
+            if let Some(i) = (!expected.is_empty()).then(|| rng.gen_range(0..expected.len())) {
+                assert_eq!(vec[i], expected[i]);
+            }
+        }
+
+        assert!(vec.iter().eq(expected.iter()));
+        assert!(vec.iter().rev().eq(expected.iter().rev()));
+        for (version, expected) in versions {
+            assert!(version.iter().eq(expected.iter()));
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::{iter::FusedIterator, str};
+
+use super::node::Node;
+use crate::datastructures::Vector;
+
+/// An iterator over the chunks of a [`Rope`](super::Rope), front to back
+#[derive(Clone)]
+pub struct Chunks<'r> {
+    /// The subtrees still to visit, the next one on top
+    stack: Vector<&'r Node>,
+}
+
+impl<'r> Chunks<'r> {
+    pub(crate) fn new(root: Option<&'r Node>) -> Self {
+        Self {
+            stack: root.into_iter().collect(),
+        }
+    }
+}
+
+impl<'r> Iterator for Chunks<'r> {
+    type Item = &'r str;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.pop()? {
+                Node::Leaf { text, .. } => return Some(text),
+                Node::Branch { left, right, .. } => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+            }
+        }
+    }
+}
+
+impl FusedIterator for Chunks<'_> {}
+
+/// An iterator over the chars of a [`Rope`](super::Rope), front to back
+#[derive(Clone)]
+pub struct Chars<'r> {
+    chunks: Chunks<'r>,
+    chunk: str::Chars<'r>,
+}
+
+impl<'r> Chars<'r> {
+    pub(crate) fn new(chunks: Chunks<'r>) -> Self {
+        Self {
+            chunks,
+            chunk: "".chars(),
+        }
+    }
+}
+
+impl Iterator for Chars<'_> {
+    type Item = char;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(c) = self.chunk.next() {
+                return Some(c);
+            }
+            self.chunk = self.chunks.next()?.chars();
+        }
+    }
+}
+
+impl FusedIterator for Chars<'_> {}
@@ -0,0 +1,7 @@
+pub mod iter;
+mod node;
+#[allow(clippy::module_inception)]
+pub mod rope;
+
+pub use iter::{Chars, Chunks};
+pub use rope::{Rope, MAX_LEAF};
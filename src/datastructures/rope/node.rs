@@ -0,0 +1,386 @@
+// The tree under a `Rope`: an AVL tree with the text in its leaves, in order. Nodes are
+// shared between ropes and never change, every edit builds new paths instead.
+//
+// Everything is built on `join`, which concatenates two balanced trees in time proportional
+// to the difference of their heights. Splitting descends once and joins the pieces it
+// passes on the way back up, which adds up to O(log n) as well.
+
+use std::rc::Rc;
+
+use crate::datastructures::Vector;
+
+pub(crate) type Tree = Option<Rc<Node>>;
+
+/// What a subtree holds, so that searches can skip whole subtrees
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Metrics {
+    pub bytes: usize,
+    pub chars: usize,
+    pub newlines: usize,
+}
+
+impl Metrics {
+    fn of(text: &str) -> Self {
+        Self {
+            bytes: text.len(),
+            chars: text.chars().count(),
+            newlines: text.bytes().filter(|&b| b == b'\n').count(),
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            bytes: self.bytes + other.bytes,
+            chars: self.chars + other.chars,
+            newlines: self.newlines + other.newlines,
+        }
+    }
+}
+
+pub(crate) enum Node {
+    Leaf {
+        text: String,
+        metrics: Metrics,
+    },
+    Branch {
+        left: Rc<Node>,
+        right: Rc<Node>,
+        metrics: Metrics,
+        height: usize,
+    },
+}
+
+impl Node {
+    pub(crate) fn leaf(text: String) -> Rc<Node> {
+        Rc::new(Node::Leaf {
+            metrics: Metrics::of(&text),
+            text,
+        })
+    }
+
+    fn branch(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+        Rc::new(Node::Branch {
+            metrics: left.metrics().add(right.metrics()),
+            height: left.height().max(right.height()) + 1,
+            left,
+            right,
+        })
+    }
+
+    pub(crate) fn metrics(&self) -> Metrics {
+        match self {
+            Node::Leaf { metrics, .. } | Node::Branch { metrics, .. } => *metrics,
+        }
+    }
+
+    pub(crate) fn height(&self) -> usize {
+        match self {
+            Node::Leaf { .. } => 1,
+            Node::Branch { height, .. } => *height,
+        }
+    }
+
+    /// The two children of a branch.
+    fn children(&self) -> (&Rc<Node>, &Rc<Node>) {
+        match self {
+            Node::Branch { left, right, .. } => (left, right),
+            Node::Leaf { .. } => unreachable!("a leaf has no children"),
+        }
+    }
+
+    /// The byte offset of char `chars` in a leaf, or its length.
+    fn leaf_byte(text: &str, chars: usize) -> usize {
+        text.char_indices()
+            .nth(chars)
+            .map_or(text.len(), |(i, _)| i)
+    }
+
+    pub(crate) fn char_to_byte(&self, chars: usize) -> usize {
+        match self {
+            Node::Leaf { text, .. } => Node::leaf_byte(text, chars),
+            Node::Branch { left, right, .. } => {
+                let before = left.metrics();
+                if chars <= before.chars {
+                    left.char_to_byte(chars)
+                } else {
+                    before.bytes + right.char_to_byte(chars - before.chars)
+                }
+            }
+        }
+    }
+
+    pub(crate) fn byte_to_char(&self, bytes: usize) -> usize {
+        match self {
+            Node::Leaf { text, .. } => {
+                assert!(
+                    text.is_char_boundary(bytes),
+                    "byte index {bytes} is not a char boundary"
+                );
+                text[..bytes].chars().count()
+            }
+            Node::Branch { left, right, .. } => {
+                let before = left.metrics();
+                if bytes <= before.bytes {
+                    left.byte_to_char(bytes)
+                } else {
+                    before.chars + right.byte_to_char(bytes - before.bytes)
+                }
+            }
+        }
+    }
+
+    /// The number of newlines in the first `chars` chars.
+    pub(crate) fn newlines_before(&self, chars: usize) -> usize {
+        match self {
+            Node::Leaf { text, .. } => text.chars().take(chars).filter(|&c| c == '\n').count(),
+            Node::Branch { left, right, .. } => {
+                let before = left.metrics();
+                if chars <= before.chars {
+                    left.newlines_before(chars)
+                } else {
+                    before.newlines + right.newlines_before(chars - before.chars)
+                }
+            }
+        }
+    }
+
+    /// The char index just past newline number `n`, counting from one.
+    pub(crate) fn char_after_newline(&self, n: usize) -> usize {
+        match self {
+            Node::Leaf { text, .. } => {
+                let (i, _) = text
+                    .chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == '\n')
+                    .nth(n - 1)
+                    .unwrap();
+                i + 1
+            }
+            Node::Branch { left, right, .. } => {
+                let before = left.metrics();
+                if n <= before.newlines {
+                    left.char_after_newline(n)
+                } else {
+                    before.chars + right.char_after_newline(n - before.newlines)
+                }
+            }
+        }
+    }
+
+    pub(crate) fn char_at(&self, index: usize) -> char {
+        match self {
+            Node::Leaf { text, .. } => text[Node::leaf_byte(text, index)..].chars().next().unwrap(),
+            Node::Branch { left, right, .. } => {
+                let before = left.metrics().chars;
+                if index < before {
+                    left.char_at(index)
+                } else {
+                    right.char_at(index - before)
+                }
+            }
+        }
+    }
+
+    /// Inserts `text` into the leaf at char `index`, `None` if that leaf would outgrow
+    /// `max_leaf` bytes.
+    pub(crate) fn insert_in_leaf(
+        &self,
+        index: usize,
+        insert: &str,
+        max_leaf: usize,
+    ) -> Option<Rc<Node>> {
+        match self {
+            Node::Leaf { text, metrics } => {
+                if metrics.bytes + insert.len() > max_leaf {
+                    return None;
+                }
+                let at = Node::leaf_byte(text, index);
+                let mut new = String::with_capacity(text.len() + insert.len());
+                new.push_str(&text[..at]);
+                new.push_str(insert);
+                new.push_str(&text[at..]);
+                Some(Node::leaf(new))
+            }
+            Node::Branch { left, right, .. } => {
+                let before = left.metrics().chars;
+                Some(if index <= before {
+                    Node::branch(
+                        left.insert_in_leaf(index, insert, max_leaf)?,
+                        Rc::clone(right),
+                    )
+                } else {
+                    Node::branch(
+                        Rc::clone(left),
+                        right.insert_in_leaf(index - before, insert, max_leaf)?,
+                    )
+                })
+            }
+        }
+    }
+}
+
+/// Builds a balanced tree of leaves of at most `max_leaf` bytes.
+pub(crate) fn build(text: &str, max_leaf: usize) -> Tree {
+    let mut leaves = Vector::with_capacity(text.len() / max_leaf + 1);
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(max_leaf);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        leaves.push(Node::leaf(rest[..end].to_owned()));
+        rest = &rest[end..];
+    }
+    build_balanced(&leaves)
+}
+
+/// Halves of equal size give heights at most one apart, so no rebalancing is needed.
+fn build_balanced(leaves: &[Rc<Node>]) -> Tree {
+    match leaves {
+        [] => None,
+        [leaf] => Some(Rc::clone(leaf)),
+        _ => {
+            let (left, right) = leaves.split_at(leaves.len() / 2);
+            Some(Node::branch(
+                build_balanced(left).unwrap(),
+                build_balanced(right).unwrap(),
+            ))
+        }
+    }
+}
+
+/// Concatenates two balanced trees into one.
+fn join(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+    let (left_height, right_height) = (left.height(), right.height());
+    if left_height > right_height + 1 {
+        join_right(&left, right)
+    } else if right_height > left_height + 1 {
+        join_left(left, &right)
+    } else {
+        Node::branch(left, right)
+    }
+}
+
+/// Hangs `right` off the right spine of the taller `left`, rotating on the way back up.
+fn join_right(left: &Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+    let (outer, inner) = left.children();
+    if inner.height() <= right.height() + 1 {
+        let joined = Node::branch(Rc::clone(inner), right);
+        if joined.height() <= outer.height() + 1 {
+            return Node::branch(Rc::clone(outer), joined);
+        }
+        // Double rotation, `inner` is split between both sides
+        let (a, b) = inner.children();
+        let (_, right) = joined.children();
+        return Node::branch(
+            Node::branch(Rc::clone(outer), Rc::clone(a)),
+            Node::branch(Rc::clone(b), Rc::clone(right)),
+        );
+    }
+
+    let joined = join_right(inner, right);
+    if joined.height() <= outer.height() + 1 {
+        Node::branch(Rc::clone(outer), joined)
+    } else {
+        let (a, b) = joined.children();
+        Node::branch(Node::branch(Rc::clone(outer), Rc::clone(a)), Rc::clone(b))
+    }
+}
+
+/// The mirror image of [`join_right`].
+fn join_left(left: Rc<Node>, right: &Rc<Node>) -> Rc<Node> {
+    let (inner, outer) = right.children();
+    if inner.height() <= left.height() + 1 {
+        let joined = Node::branch(left, Rc::clone(inner));
+        if joined.height() <= outer.height() + 1 {
+            return Node::branch(joined, Rc::clone(outer));
+        }
+        let (a, b) = inner.children();
+        let (left, _) = joined.children();
+        return Node::branch(
+            Node::branch(Rc::clone(left), Rc::clone(a)),
+            Node::branch(Rc::clone(b), Rc::clone(outer)),
+        );
+    }
+
+    let joined = join_left(left, inner);
+    if joined.height() <= outer.height() + 1 {
+        Node::branch(joined, Rc::clone(outer))
+    } else {
+        let (a, b) = joined.children();
+        Node::branch(Rc::clone(a), Node::branch(Rc::clone(b), Rc::clone(outer)))
+    }
+}
+
+fn join_trees(left: Tree, right: Tree) -> Tree {
+    match (left, right) {
+        (None, tree) | (tree, None) => tree,
+        (Some(left), Some(right)) => Some(join(left, right)),
+    }
+}
+
+/// Concatenates two trees, merging the leaves where they meet if they fit in `max_leaf` bytes.
+pub(crate) fn concat(left: Tree, right: Tree, max_leaf: usize) -> Tree {
+    let (left, right) = match (left, right) {
+        (None, tree) | (tree, None) => return tree,
+        (Some(left), Some(right)) => (left, right),
+    };
+    if edge_leaf(&left, false).len() + edge_leaf(&right, true).len() > max_leaf {
+        return Some(join(left, right));
+    }
+
+    let (left, last) = split_edge_leaf(&left, false);
+    let (right, first) = split_edge_leaf(&right, true);
+    let merged = Node::leaf(last + &first);
+    join_trees(join_trees(left, Some(merged)), right)
+}
+
+/// The text of the first or last leaf.
+fn edge_leaf(mut node: &Rc<Node>, first: bool) -> &str {
+    loop {
+        match &**node {
+            Node::Leaf { text, .. } => return text,
+            Node::Branch { left, right, .. } => node = if first { left } else { right },
+        }
+    }
+}
+
+/// Takes the first or last leaf out of the tree, returns the rest and its text.
+fn split_edge_leaf(node: &Rc<Node>, first: bool) -> (Tree, String) {
+    match &**node {
+        Node::Leaf { text, .. } => (None, text.clone()),
+        Node::Branch { left, right, .. } if first => {
+            let (rest, text) = split_edge_leaf(left, true);
+            (join_trees(rest, Some(Rc::clone(right))), text)
+        }
+        Node::Branch { left, right, .. } => {
+            let (rest, text) = split_edge_leaf(right, false);
+            (join_trees(Some(Rc::clone(left)), rest), text)
+        }
+    }
+}
+
+/// Splits a tree into the first `index` chars and the rest.
+pub(crate) fn split(node: &Rc<Node>, index: usize) -> (Tree, Tree) {
+    match &**node {
+        Node::Leaf { text, .. } => {
+            let at = Node::leaf_byte(text, index);
+            let part = |text: &str| (!text.is_empty()).then(|| Node::leaf(text.to_owned()));
+            (part(&text[..at]), part(&text[at..]))
+        }
+        Node::Branch { left, right, .. } => {
+            let before = left.metrics().chars;
+            if index == 0 {
+                (None, Some(Rc::clone(node)))
+            } else if index < before {
+                let (a, b) = split(left, index);
+                (a, join_trees(b, Some(Rc::clone(right))))
+            } else if index == before {
+                (Some(Rc::clone(left)), Some(Rc::clone(right)))
+            } else {
+                let (a, b) = split(right, index - before);
+                (join_trees(Some(Rc::clone(left)), a), b)
+            }
+        }
+    }
+}
@@ -0,0 +1,499 @@
+use std::{
+    fmt,
+    ops::{Range, RangeBounds},
+    rc::Rc,
+};
+
+use super::{
+    iter::{Chars, Chunks},
+    node::{self, Tree},
+};
+use crate::datastructures::vector::vector::slice_range;
+
+/// The most bytes a leaf holds.
+pub const MAX_LEAF: usize = 1024;
+
+/// UTF-8 text in a balanced tree of small chunks, for editing large documents.
+///
+/// Positions are char indices. Inserting, removing and slicing take O(log n), as do
+/// conversions between chars, bytes and lines. Lines end after each `'\n'`. The chunks are
+/// shared, so cloning a rope or taking a slice of it copies almost nothing.
+#[derive(Clone)]
+pub struct Rope {
+    root: Tree,
+    /// [`MAX_LEAF`] outside of tests, which use tiny leaves to build real trees
+    max_leaf: usize,
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A rope of `text` whose leaves hold at most `max_leaf` bytes, edits keep that limit.
+    #[cfg(test)]
+    pub(crate) fn with_max_leaf(text: &str, max_leaf: usize) -> Self {
+        Self {
+            root: node::build(text, max_leaf),
+            max_leaf,
+        }
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.metrics().bytes)
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.metrics().chars)
+    }
+
+    /// The number of lines, one more than the number of `'\n'`s.
+    pub fn len_lines(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.metrics().newlines) + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn char(&self, index: usize) -> char {
+        let len = self.len_chars();
+        assert!(
+            index < len,
+            "char index (is {index}) should be < len (is {len})"
+        );
+        self.root.as_ref().unwrap().char_at(index)
+    }
+
+    /// Inserts `text` before the char at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len_chars`.
+    pub fn insert(&mut self, index: usize, text: &str) {
+        self.check_index(index);
+        if text.is_empty() {
+            return;
+        }
+
+        // Small edits fit in the leaf they land in
+        if let Some(root) = self
+            .root
+            .as_ref()
+            .and_then(|r| r.insert_in_leaf(index, text, self.max_leaf))
+        {
+            self.root = Some(root);
+            return;
+        }
+
+        let (left, right) = self.split_root(index);
+        let middle = node::build(text, self.max_leaf);
+        self.root = node::concat(
+            node::concat(left, middle, self.max_leaf),
+            right,
+            self.max_leaf,
+        );
+    }
+
+    pub fn insert_char(&mut self, index: usize, c: char) {
+        self.insert(index, c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Removes the chars in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn remove<R: RangeBounds<usize>>(&mut self, range: R) {
+        let Range { start, end } = slice_range(range, self.len_chars());
+        let (left, rest) = self.split_root(start);
+        let right = rest.and_then(|rest| node::split(&rest, end - start).1);
+        self.root = node::concat(left, right, self.max_leaf);
+    }
+
+    /// A new rope with the chars in `range`, sharing its chunks with this one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Rope {
+        let Range { start, end } = slice_range(range, self.len_chars());
+        let (_, rest) = self.split_root(start);
+        Rope {
+            root: rest.and_then(|rest| node::split(&rest, end - start).0),
+            max_leaf: self.max_leaf,
+        }
+    }
+
+    /// Splits the rope in two, keeping the first `index` chars.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len_chars`.
+    pub fn split_off(&mut self, index: usize) -> Rope {
+        self.check_index(index);
+        let (left, right) = self.split_root(index);
+        self.root = left;
+        Rope {
+            root: right,
+            max_leaf: self.max_leaf,
+        }
+    }
+
+    /// Moves the text of `other` to the end of this rope.
+    pub fn append(&mut self, other: Rope) {
+        self.root = node::concat(self.root.take(), other.root, self.max_leaf);
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `index > len_chars`.
+    pub fn char_to_byte(&self, index: usize) -> usize {
+        self.check_index(index);
+        self.root
+            .as_ref()
+            .map_or(0, |root| root.char_to_byte(index))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `index > len_bytes` or not at a char boundary.
+    pub fn byte_to_char(&self, index: usize) -> usize {
+        let len = self.len_bytes();
+        assert!(
+            index <= len,
+            "byte index (is {index}) should be <= len (is {len})"
+        );
+        self.root
+            .as_ref()
+            .map_or(0, |root| root.byte_to_char(index))
+    }
+
+    /// The line the char at `index` is on.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len_chars`.
+    pub fn char_to_line(&self, index: usize) -> usize {
+        self.check_index(index);
+        self.root
+            .as_ref()
+            .map_or(0, |root| root.newlines_before(index))
+    }
+
+    /// The char index the line starts at, `len_chars` for `len_lines`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line > len_lines`.
+    pub fn line_to_char(&self, line: usize) -> usize {
+        let lines = self.len_lines();
+        assert!(
+            line <= lines,
+            "line index (is {line}) should be <= len_lines (is {lines})"
+        );
+        match &self.root {
+            _ if line == lines => self.len_chars(),
+            Some(root) if line > 0 => root.char_after_newline(line),
+            _ => 0,
+        }
+    }
+
+    /// The text of line `line`, with its `'\n'`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line >= len_lines`.
+    pub fn line(&self, line: usize) -> Rope {
+        let lines = self.len_lines();
+        assert!(
+            line < lines,
+            "line index (is {line}) should be < len_lines (is {lines})"
+        );
+        self.slice(self.line_to_char(line)..self.line_to_char(line + 1))
+    }
+
+    /// Iterates over the text in chunks of at most [`MAX_LEAF`] bytes.
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks::new(self.root.as_deref())
+    }
+
+    pub fn chars(&self) -> Chars<'_> {
+        Chars::new(self.chunks())
+    }
+
+    fn check_index(&self, index: usize) {
+        let len = self.len_chars();
+        assert!(
+            index <= len,
+            "char index (is {index}) should be <= len (is {len})"
+        );
+    }
+
+    fn split_root(&self, index: usize) -> (Tree, Tree) {
+        match &self.root {
+            Some(root) => node::split(root, index),
+            None => (None, None),
+        }
+    }
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Self {
+            root: None,
+            max_leaf: MAX_LEAF,
+        }
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        Self {
+            root: node::build(text, MAX_LEAF),
+            max_leaf: MAX_LEAF,
+        }
+    }
+}
+
+impl From<String> for Rope {
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+impl From<&Rope> for String {
+    fn from(rope: &Rope) -> Self {
+        let mut text = String::with_capacity(rope.len_bytes());
+        rope.chunks().for_each(|chunk| text.push_str(chunk));
+        text
+    }
+}
+
+impl PartialEq for Rope {
+    fn eq(&self, other: &Self) -> bool {
+        let same_root = match (&self.root, &other.root) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        same_root
+            || self.len_bytes() == other.len_bytes()
+                && self
+                    .chunks()
+                    .flat_map(str::bytes)
+                    .eq(other.chunks().flat_map(str::bytes))
+    }
+}
+
+impl Eq for Rope {}
+
+impl PartialEq<str> for Rope {
+    fn eq(&self, other: &str) -> bool {
+        self.len_bytes() == other.len() && self.chunks().flat_map(str::bytes).eq(other.bytes())
+    }
+}
+
+impl PartialEq<&str> for Rope {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl fmt::Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&String::from(self), f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::datastructures::rope::node::Node;
+
+    /// Small enough that a few words already make a tree
+    const LEAF: usize = 16;
+
+    fn rope_of(text: &str) -> Rope {
+        Rope::with_max_leaf(text, LEAF)
+    }
+
+    /// Checks the AVL balance and the leaf sizes, returns the height.
+    fn check_balanced(node: &Node) -> usize {
+        match node {
+            Node::Leaf { text, .. } => {
+                assert!(!text.is_empty() && text.len() <= LEAF);
+                1
+            }
+            Node::Branch {
+                left,
+                right,
+                height,
+                ..
+            } => {
+                let (l, r) = (check_balanced(left), check_balanced(right));
+                assert!(l.abs_diff(r) <= 1, "unbalanced: {l} and {r}");
+                assert_eq!(*height, l.max(r) + 1);
+                *height
+            }
+        }
+    }
+
+    #[test]
+    fn build_and_convert() {
+        let text = "A Game of Thrones\nA Clash of Kings\nA Storm of Swords\n";
+        let rope = rope_of(text);
+        assert!(rope.chunks().count() > 3);
+        check_balanced(rope.root.as_ref().unwrap());
+
+        assert_eq!(rope, text);
+        assert_eq!(rope.to_string(), text);
+        assert_eq!(rope.len_lines(), 4);
+        assert_eq!(rope.line(1), "A Clash of Kings\n");
+        assert_eq!(rope.line(3), "");
+        assert_eq!(rope.line_to_char(2), 35);
+        assert_eq!(rope.line_to_char(4), rope.len_chars());
+        assert_eq!(rope.char_to_line(35), 2);
+        assert_eq!(rope.char_to_line(34), 1);
+        assert_eq!(rope.char(2), 'G');
+    }
+
+    #[test]
+    fn public_ropes_use_max_leaf() {
+        let mut rope = Rope::from("A Dream of Spring ".repeat(100).as_str());
+        assert_eq!(rope.chunks().count(), 2);
+        rope.insert(0, "The Winds of Winter, ");
+        assert!(rope.chunks().all(|chunk| chunk.len() <= MAX_LEAF));
+        assert_eq!(rope.slice(..5).max_leaf, MAX_LEAF);
+    }
+
+    #[test]
+    fn multibyte_chars() {
+        let rope = rope_of("Daenerys Stormborn 🐉🐉🐉 of House Targaryen, Mother of Dragons");
+        check_balanced(rope.root.as_ref().unwrap());
+        assert_eq!(rope.char(19), '🐉');
+        assert_eq!(rope.char_to_byte(20), 23);
+        assert_eq!(rope.byte_to_char(27), 21);
+        assert!(rope.chars().eq(String::from(&rope).chars()));
+        assert_eq!(rope.slice(19..22), "🐉🐉🐉");
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a char boundary")]
+    fn byte_inside_char() {
+        rope_of("🐉").byte_to_char(1);
+    }
+
+    #[test]
+    fn edit() {
+        let mut rope = rope_of("A Feast for Crows");
+        rope.insert(8, "and a Dance ");
+        rope.insert_char(rope.len_chars(), '!');
+        assert_eq!(rope, "A Feast and a Dance for Crows!");
+
+        rope.remove(7..19);
+        assert_eq!(rope, "A Feast for Crows!");
+        let crows = rope.split_off(12);
+        assert_eq!(
+            (rope.to_string(), crows.to_string()),
+            ("A Feast for ".into(), "Crows!".into())
+        );
+
+        rope.append(rope_of("Dragons"));
+        assert_eq!(rope, "A Feast for Dragons");
+        rope.remove(..);
+        assert!(rope.is_empty());
+        assert_eq!(rope.len_lines(), 1);
+    }
+
+    #[test]
+    fn slices_share_chunks() {
+        let rope = rope_of("A Song of Ice and Fire ".repeat(50).as_str());
+        let slice = rope.slice(100..900);
+        check_balanced(slice.root.as_ref().unwrap());
+        assert_eq!(slice.to_string(), rope.to_string()[100..900]);
+        assert_eq!(rope.slice(..), rope);
+        assert!(rope.slice(5..5).is_empty());
+    }
+
+    #[test]
+    fn typing_keeps_leaves_full() {
+        let mut rope = rope_of("");
+        for (i, c) in "The Winds of Winter".repeat(20).chars().enumerate() {
+            rope.insert_char(i, c);
+        }
+        let chunks = rope.chunks().count();
+        assert!(
+            chunks <= rope.len_bytes() / (LEAF / 2) + 1,
+            "{chunks} chunks"
+        );
+    }
+
+    #[test]
+    fn matches_string() {
+        let mut rng = StdRng::seed_from_u64(47);
+        let words = ["Winter", " is ", "coming", "\n", "ドラゴン", "🐺", ""];
+        let mut rope = rope_of("");
+        let mut expected: Vec<char> = Vec::new();
+
+        for _ in 0..3000 {
+            let len = expected.len();
+            match rng.gen_range(0..6) {
+                0..=2 => {
+                    let at = rng.gen_range(0..=len);
+                    let text = words[rng.gen_range(0..words.len())].repeat(rng.gen_range(1..8));
+                    rope.insert(at, &text);
+                    expected.splice(at..at, text.chars());
+                }
+                3 => {
+                    let start = rng.gen_range(0..=len);
+                    let end = rng.gen_range(start..=len.min(start + 40));
+                    rope.remove(start..end);
+                    expected.drain(start..end);
+                }
+                4 => {
+                    let start = rng.gen_range(0..=len);
+                    let end = rng.gen_range(start..=len);
+                    let slice = rope.slice(start..end);
+                    assert!(slice.chars().eq(expected[start..end].iter().copied()));
+                }
+                _ => {
+                    let at = rng.gen_range(0..=len);
+                    let mut right = rope.split_off(at);
+                    right.append(std::mem::take(&mut rope));
+                    rope = right;
+                    expected.rotate_left(at);
+                }
+            }
+
+            if let Some(root) = rope.root.as_ref() {
+                check_balanced(root);
+            }
+            let text: String = expected.iter().collect();
+            assert_eq!(rope, text.as_str());
+            if !expected.is_empty() {
+                let i = rng.gen_range(0..expected.len());
+                assert_eq!(rope.char(i), expected[i]);
+                assert_eq!(rope.char_to_byte(i), text.char_indices().nth(i).unwrap().0);
+                let line = expected[..i].iter().filter(|&&c| c == '\n').count();
+                assert_eq!(rope.char_to_line(i), line);
+                assert!(rope.line_to_char(line) <= i);
+            }
+        }
+
+        let text: String = expected.iter().collect();
+        for (line, expected) in text.split_inclusive('\n').enumerate() {
+            assert_eq!(rope.line(line), expected);
+        }
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    cell::{Cell, OnceCell},
+    fmt,
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops, ptr,
+};
+
+use crate::datastructures::{
+    segments::{self, SEGMENTS},
+    RawVec,
+};
+
+/// A vector that grows by allocating new segments, each twice as large as the last, instead
+/// of reallocating.
+///
+/// Elements never move once pushed, so [`push`](SegmentedVec::push) only needs `&self` and
+/// references to earlier elements stay valid while more are pushed. Indexing is still O(1),
+/// it takes a `log2` to find the segment. The interior mutability makes the vector `!Sync`.
+pub struct SegmentedVec<T> {
+    /// Segment `k` holds `2^k` elements and is allocated when the first of them is pushed
+    segments: [OnceCell<RawVec<T>>; SEGMENTS],
+    len: Cell<usize>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> SegmentedVec<T> {
+    pub const fn new() -> Self {
+        Self {
+            segments: [const { OnceCell::new() }; SEGMENTS],
+            len: Cell::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How many elements fit into the segments allocated so far.
+    pub fn capacity(&self) -> usize {
+        self.segments
+            .iter()
+            .take_while(|segment| segment.get().is_some())
+            .map(|segment| segment.get().unwrap().capacity())
+            .fold(0, usize::saturating_add)
+    }
+
+    /// Appends `elem` and returns a reference to it, which stays valid until the vector is
+    /// mutably borrowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector already holds `usize::MAX` elements.
+    pub fn push(&self, elem: T) -> &T {
+        let len = self.len();
+        assert!(len < usize::MAX, "capacity overflow");
+
+        let (segment, offset) = segments::location(len);
+        let buf = self.segments[segment]
+            .get_or_init(|| RawVec::with_capacity(segments::segment_len(segment)));
+        // Nobody can borrow the slot past the end, and filling it doesn't touch the others
+        let slot = unsafe { buf.ptr().add(offset) };
+        unsafe { slot.write(elem) };
+        self.len.set(len + 1);
+        unsafe { &*slot }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.len().checked_sub(1)?;
+        self.len.set(len);
+        Some(unsafe { self.slot(len).read() })
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        (index < self.len()).then(|| unsafe { &*self.slot(index) })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        (index < self.len()).then(|| unsafe { &mut *self.slot(index) })
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    /// Drops every element past the first `len`, keeping the segments allocated.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len() > len {
+            // Pop the slot before dropping it, so a panic leaves the rest of the tail owned
+            let last = self.len() - 1;
+            self.len.set(last);
+            unsafe { ptr::drop_in_place(self.slot(last)) };
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            vec: self,
+            start: 0,
+            end: self.len(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            start: 0,
+            end: self.len(),
+            vec: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a pointer to element `index`, whose segment must be allocated.
+    unsafe fn slot(&self, index: usize) -> *mut T {
+        let (segment, offset) = segments::location(index);
+        let buf = unsafe { self.segments[segment].get().unwrap_unchecked() };
+        unsafe { buf.ptr().add(offset) }
+    }
+}
+
+impl<T> Drop for SegmentedVec<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for SegmentedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for SegmentedVec<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SegmentedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<SegmentedVec<U>> for SegmentedVec<T> {
+    fn eq(&self, other: &SegmentedVec<U>) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for SegmentedVec<T> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for SegmentedVec<T> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        self == &other[..]
+    }
+}
+
+impl<T: Eq> Eq for SegmentedVec<T> {}
+
+impl<T> ops::Index<usize> for SegmentedVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T> ops::IndexMut<usize> for SegmentedVec<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
+
+impl<T> FromIterator<T> for SegmentedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T> Extend<T> for SegmentedVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|elem| {
+            self.push(elem);
+        });
+    }
+}
+
+/// An iterator over references to the elements of a [`SegmentedVec`]
+pub struct Iter<'v, T> {
+    vec: &'v SegmentedVec<T>,
+    start: usize,
+    end: usize,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self { ..*self }
+    }
+}
+
+impl<'v, T> Iterator for Iter<'v, T> {
+    type Item = &'v T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.start += 1;
+        Some(unsafe { &*self.vec.slot(self.start - 1) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { &*self.vec.slot(self.end) })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over mutable references to the elements of a [`SegmentedVec`]
+pub struct IterMut<'v, T> {
+    /// Only used to find the elements, each of which is handed out once
+    vec: &'v SegmentedVec<T>,
+    start: usize,
+    end: usize,
+    _marker: PhantomData<&'v mut T>,
+}
+
+impl<'v, T> Iterator for IterMut<'v, T> {
+    type Item = &'v mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.start += 1;
+        Some(unsafe { &mut *self.vec.slot(self.start - 1) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { &mut *self.vec.slot(self.end) })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// An owning iterator over the elements of a [`SegmentedVec`]
+pub struct IntoIter<T> {
+    /// Emptied, the remaining elements are tracked here
+    vec: SegmentedVec<T>,
+    start: usize,
+    end: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.start += 1;
+        Some(unsafe { self.vec.slot(self.start - 1).read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { self.vec.slot(self.end).read() })
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+impl<T> IntoIterator for SegmentedVec<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let end = self.len.replace(0);
+        IntoIter {
+            vec: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<'v, T> IntoIterator for &'v SegmentedVec<T> {
+    type Item = &'v T;
+    type IntoIter = Iter<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'v, T> IntoIterator for &'v mut SegmentedVec<T> {
+    type Item = &'v mut T;
+    type IntoIter = IterMut<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn push_keeps_references() {
+        let vec = SegmentedVec::new();
+        let first: &String = vec.push("A Game of Thrones".to_string());
+        let addr = first as *const String;
+
+        // Grows through several segments while `first` is borrowed
+        for n in 0..100 {
+            vec.push(n.to_string());
+        }
+        assert_eq!(first, "A Game of Thrones");
+        assert_eq!(&vec[0] as *const String, addr);
+        assert_eq!(vec.len(), 101);
+        assert_eq!(vec.capacity(), 127);
+        assert_eq!(vec.get(101), None);
+        assert_eq!(vec.last().map(String::as_str), Some("99"));
+    }
+
+    #[test]
+    fn edit() {
+        let mut vec: SegmentedVec<u32> = (0..10).collect();
+        vec[3] = 30;
+        *vec.get_mut(4).unwrap() += 36;
+        assert_eq!(vec.pop(), Some(9));
+        vec.truncate(6);
+        assert_eq!(vec, [0, 1, 2, 30, 40, 5]);
+
+        vec.clear();
+        assert!(vec.is_empty());
+        assert_eq!(vec.pop(), None);
+        assert_eq!(vec.capacity(), 15);
+        assert_eq!(vec.first(), None);
+    }
+
+    #[test]
+    fn iterators() {
+        let mut vec: SegmentedVec<usize> = (0..20).collect();
+        assert!(vec.iter().copied().eq(0..20));
+        assert!(vec.iter().rev().copied().eq((0..20).rev()));
+        assert_eq!(vec.iter().len(), 20);
+
+        vec.iter_mut().for_each(|n| *n *= 2);
+        for n in &mut vec {
+            *n += 1;
+        }
+        assert_eq!((&vec).into_iter().sum::<usize>(), 400);
+
+        let mut iter = vec.clone().into_iter();
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(39));
+        assert_eq!(iter.len(), 18);
+        assert_eq!(
+            format!("{:?}", iter.take(3).collect::<Vec<_>>()),
+            "[3, 5, 7]"
+        );
+    }
+
+    #[test]
+    fn drops_elements() {
+        let book = Rc::new("A Storm of Swords");
+        let vec: SegmentedVec<_> = (0..10).map(|_| Rc::clone(&book)).collect();
+        let mut iter = vec.clone().into_iter();
+        iter.next();
+        assert_eq!(Rc::strong_count(&book), 20);
+
+        drop(iter);
+        drop(vec);
+        assert_eq!(Rc::strong_count(&book), 1);
+    }
+
+    #[test]
+    fn zero_sized() {
+        let vec = SegmentedVec::new();
+        (0..1000).for_each(|_| {
+            vec.push(());
+        });
+        assert_eq!(vec.len(), 1000);
+        assert_eq!(vec.iter().count(), 1000);
+    }
+}
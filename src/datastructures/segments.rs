@@ -0,0 +1,55 @@
+// The layout of vectors that grow by adding segments instead of reallocating: segment `k`
+// holds `2^k` elements, so element `i` lives in segment `floor(log2(i + 1))`.
+
+/// Enough segments to hold `usize::MAX` elements.
+pub(crate) const SEGMENTS: usize = usize::BITS as usize;
+
+/// How many elements segment `segment` holds.
+pub(crate) const fn segment_len(segment: usize) -> usize {
+    1 << segment
+}
+
+/// Returns the segment of element `index` and its offset in there.
+///
+/// `index` must be below `usize::MAX`.
+pub(crate) const fn location(index: usize) -> (usize, usize) {
+    let n = index + 1;
+    let segment = (usize::BITS - 1 - n.leading_zeros()) as usize;
+    (segment, n - segment_len(segment))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn locations() {
+        let expected = [
+            (0, 0),
+            (1, 0),
+            (1, 1),
+            (2, 0),
+            (2, 1),
+            (2, 2),
+            (2, 3),
+            (3, 0),
+        ];
+        for (index, location_) in expected.into_iter().enumerate() {
+            assert_eq!(location(index), location_);
+        }
+        assert_eq!(
+            location(usize::MAX - 1),
+            (SEGMENTS - 1, segment_len(SEGMENTS - 1) - 1)
+        );
+
+        // Every element has its own place
+        let mut next = (0, 0);
+        for index in 0..1000 {
+            assert_eq!(location(index), next);
+            next.1 += 1;
+            if next.1 == segment_len(next.0) {
+                next = (next.0 + 1, 0);
+            }
+        }
+    }
+}
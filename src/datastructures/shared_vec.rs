@@ -0,0 +1,286 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    slice,
+    sync::Arc,
+};
+
+use crate::datastructures::{Allocator, Global, Vector};
+
+/// A [`Vector`] behind an [`Arc`], so clones share one buffer.
+///
+/// Cloning only bumps a reference count. The first write through a clone that still shares
+/// its buffer copies the elements into a buffer of its own, later writes go straight to it.
+pub struct SharedVec<T, A: Allocator = Global> {
+    inner: Arc<Vector<T, A>>,
+}
+
+impl<T> SharedVec<T> {
+    pub fn new() -> Self {
+        Self::from(Vector::new())
+    }
+}
+
+impl<T, A: Allocator> SharedVec<T, A> {
+    pub fn new_in(alloc: A) -> Self {
+        Self::from(Vector::new_in(alloc))
+    }
+
+    /// The shared vector, read only.
+    pub fn as_vector(&self) -> &Vector<T, A> {
+        &self.inner
+    }
+
+    /// Whether `this` and `other` share their buffer.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.inner, &other.inner)
+    }
+
+    /// The number of clones sharing the buffer, this one included.
+    pub fn ref_count(this: &Self) -> usize {
+        Arc::strong_count(&this.inner)
+    }
+
+    /// Whether no other clone shares the buffer, so writing won't copy it.
+    pub fn is_unique(this: &mut Self) -> bool {
+        Arc::get_mut(&mut this.inner).is_some()
+    }
+
+    /// The vector, if no other clone shares it.
+    pub fn get_mut(this: &mut Self) -> Option<&mut Vector<T, A>> {
+        Arc::get_mut(&mut this.inner)
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> SharedVec<T, A> {
+    /// The vector for writing, copied first if other clones share it.
+    pub fn make_mut(this: &mut Self) -> &mut Vector<T, A> {
+        Arc::make_mut(&mut this.inner)
+    }
+
+    /// Takes the vector out, copying it if other clones share it.
+    pub fn into_vector(this: Self) -> Vector<T, A> {
+        Arc::unwrap_or_clone(this.inner)
+    }
+
+    pub fn push(&mut self, elem: T) {
+        Self::make_mut(self).push(elem);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        Self::make_mut(self).pop()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, elem: T) {
+        let len = self.len();
+        assert!(index < len, "index (is {index}) should be < len (is {len})");
+        Self::make_mut(self)[index] = elem;
+    }
+
+    /// Empties the vector, without copying the elements if the buffer is shared.
+    pub fn clear(&mut self) {
+        match Self::get_mut(self) {
+            Some(v) => v.clear(),
+            None => *self = Self::new_in(self.inner.allocator().clone()),
+        }
+    }
+}
+
+impl<T, A: Allocator> From<Vector<T, A>> for SharedVec<T, A> {
+    fn from(v: Vector<T, A>) -> Self {
+        Self { inner: Arc::new(v) }
+    }
+}
+
+impl<T, A: Allocator> Deref for SharedVec<T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T, A: Allocator> Clone for SharedVec<T, A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Default for SharedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for SharedVec<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Clones sharing a buffer are equal without comparing the elements.
+impl<T: PartialEq, A: Allocator> PartialEq for SharedVec<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        Self::ptr_eq(self, other) || self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq, A: Allocator> PartialEq<[T]> for SharedVec<T, A> {
+    fn eq(&self, other: &[T]) -> bool {
+        self[..] == *other
+    }
+}
+
+impl<T: PartialEq, A: Allocator, const N: usize> PartialEq<[T; N]> for SharedVec<T, A> {
+    fn eq(&self, other: &[T; N]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for SharedVec<T, A> {}
+
+/// Hashes exactly like the equivalent slice.
+impl<T: Hash, A: Allocator> Hash for SharedVec<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T> FromIterator<T> for SharedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(Vector::from_iter(iter))
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Extend<T> for SharedVec<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut iter = iter.into_iter().peekable();
+        // Don't copy the buffer just to add nothing to it
+        if iter.peek().is_some() {
+            Self::make_mut(self).extend(iter);
+        }
+    }
+}
+
+impl<'v, T, A: Allocator> IntoIterator for &'v SharedVec<T, A> {
+    type Item = &'v T;
+    type IntoIter = slice::Iter<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use super::*;
+    use crate::datastructures::CountingAllocator;
+
+    fn titles<A: Allocator>(alloc: A) -> Vector<String, A> {
+        let mut v = Vector::new_in(alloc);
+        v.extend(
+            ["Winter Is Coming", "The Kingsroad", "Lord Snow"]
+                .into_iter()
+                .map(String::from),
+        );
+        v
+    }
+
+    #[test]
+    fn clones_share_until_written() {
+        let counter = CountingAllocator::new();
+        let mut original = SharedVec::from(titles(&counter));
+        let allocated = counter.allocations();
+        let live = counter.live_bytes();
+
+        let clones: Vec<_> = (0..10).map(|_| original.clone()).collect();
+        assert_eq!(counter.allocations(), allocated);
+        assert_eq!(counter.live_bytes(), live);
+        assert!(clones.iter().all(|c| SharedVec::ptr_eq(c, &original)));
+        assert_eq!(SharedVec::ref_count(&original), 11);
+        assert_eq!(clones[3][1], "The Kingsroad");
+
+        // Reading and comparing don't copy either
+        assert_eq!(clones[0], original);
+        assert_eq!(clones[0].len(), 3);
+        assert_eq!(counter.allocations(), allocated);
+
+        let mut written = clones[0].clone();
+        written.push("Cripples, Bastards, and Broken Things".into());
+        assert!(!SharedVec::ptr_eq(&written, &original));
+        assert!(counter.allocations() > allocated);
+        assert_eq!(written.len(), 4);
+        assert_eq!(original.len(), 3);
+
+        // The copy is unique now, so writing again doesn't copy it
+        let allocated = counter.allocations();
+        written.set(0, "The Wolf and the Lion".into());
+        written.pop();
+        assert_eq!(counter.allocations(), allocated);
+        assert_eq!(
+            written[..],
+            ["The Wolf and the Lion", "The Kingsroad", "Lord Snow"]
+        );
+
+        drop(clones);
+        assert!(SharedVec::is_unique(&mut original));
+        SharedVec::make_mut(&mut original).truncate(1);
+        assert_eq!(counter.allocations(), allocated);
+        assert_eq!(original, ["Winter Is Coming".to_string()]);
+
+        drop((original, written));
+        assert_eq!(counter.live_bytes(), 0);
+    }
+
+    #[test]
+    fn clear_and_extend_shared() {
+        let counter = CountingAllocator::new();
+        let original = SharedVec::from(titles(&counter));
+        let mut clone = original.clone();
+        let allocated = counter.allocations();
+
+        clone.extend([]);
+        assert!(SharedVec::ptr_eq(&clone, &original));
+        assert_eq!(SharedVec::get_mut(&mut clone), None);
+
+        clone.clear();
+        assert!(clone.is_empty());
+        assert_eq!(original.len(), 3);
+        assert_eq!(counter.allocations(), allocated);
+
+        let mut clone = original.clone();
+        let mut v = SharedVec::into_vector(clone.clone());
+        v.push("A Golden Crown".into());
+        clone.extend(v.iter().skip(3).cloned());
+        assert_eq!(clone.as_vector(), &v);
+        assert_eq!(SharedVec::into_vector(original).len(), 3);
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let v: SharedVec<u64> = (0..1000).collect();
+        thread::scope(|s| {
+            for i in 0..4 {
+                let mut v = v.clone();
+                s.spawn(move || {
+                    assert_eq!(v.iter().sum::<u64>(), 499_500);
+                    v.set(i, 0);
+                    assert_eq!(v.iter().sum::<u64>(), 499_500 - i as u64);
+                });
+            }
+        });
+        assert_eq!(SharedVec::ref_count(&v), 1);
+        assert_eq!(v[..4], [0, 1, 2, 3]);
+    }
+}
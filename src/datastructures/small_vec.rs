@@ -0,0 +1,747 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ops::{Deref, DerefMut, RangeBounds},
+    ptr, slice,
+};
+
+use crate::datastructures::{
+    vector::{
+        len_slice::{LenSlice, RawDrain},
+        raw_vec::handle_reserve,
+        vector::slice_range,
+    },
+    RawVec, TryReserveError, Vector,
+};
+
+/// A fixed size array a [`SmallVec`] can store its elements in.
+///
+/// # Safety
+///
+/// `CAPACITY` must be the number of `Item`s the array holds, laid out like `[Item; CAPACITY]`.
+pub unsafe trait Array {
+    type Item;
+    const CAPACITY: usize;
+}
+
+unsafe impl<T, const N: usize> Array for [T; N] {
+    type Item = T;
+    const CAPACITY: usize = N;
+}
+
+enum Data<A: Array> {
+    Inline(MaybeUninit<A>),
+    Heap(RawVec<A::Item>),
+}
+
+/// A vector that keeps up to `A::CAPACITY` elements inline, e.g. `SmallVec<[u32; 8]>`, and
+/// only allocates once it outgrows them.
+///
+/// Apart from that it behaves like a [`Vector`]. Zero sized types never spill.
+pub struct SmallVec<A: Array> {
+    len: usize,
+    data: Data<A>,
+}
+
+impl<A: Array> SmallVec<A> {
+    const INLINE_CAP: usize = if mem::size_of::<A::Item>() == 0 {
+        usize::MAX
+    } else {
+        A::CAPACITY
+    };
+
+    pub fn new() -> Self {
+        Self {
+            len: 0,
+            data: Data::Inline(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Creates an empty vector with room for at least `capacity` elements, inline if they fit.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut v = Self::new();
+        v.reserve(capacity);
+        v
+    }
+
+    /// How many elements fit before the vector has to allocate.
+    pub fn inline_size(&self) -> usize {
+        Self::INLINE_CAP
+    }
+
+    /// Whether the elements have moved to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Heap(_))
+    }
+
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            Data::Inline(_) => Self::INLINE_CAP,
+            Data::Heap(buf) => buf.capacity(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_ptr(&self) -> *const A::Item {
+        match &self.data {
+            Data::Inline(array) => array.as_ptr().cast(),
+            Data::Heap(buf) => buf.ptr(),
+        }
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut A::Item {
+        match &mut self.data {
+            Data::Inline(array) => array.as_mut_ptr().cast(),
+            Data::Heap(buf) => buf.ptr(),
+        }
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must not exceed the capacity and the first `new_len` elements must be initialized.
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        self.len = new_len;
+    }
+
+    pub fn as_slice(&self) -> &[A::Item] {
+        self
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [A::Item] {
+        self
+    }
+
+    /// Makes room for at least `additional` more elements, moving to the heap if they don't fit
+    /// inline anymore.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows, aborts if the allocation fails.
+    pub fn reserve(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve(additional));
+    }
+
+    /// Like [`reserve`](SmallVec::reserve), but returns an error instead of panicking or
+    /// aborting.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if additional <= self.capacity() - self.len {
+            return Ok(());
+        }
+
+        let len = self.len;
+        match &mut self.data {
+            Data::Heap(buf) => buf.try_reserve(len, additional),
+            Data::Inline(array) => {
+                let required = len
+                    .checked_add(additional)
+                    .ok_or(TryReserveError::CapacityOverflow)?;
+                let mut buf = RawVec::new();
+                buf.try_reserve_exact(0, required.max(A::CAPACITY.saturating_mul(2)))?;
+                unsafe { ptr::copy_nonoverlapping(array.as_ptr().cast(), buf.ptr(), len) };
+                self.data = Data::Heap(buf);
+                Ok(())
+            }
+        }
+    }
+
+    /// Shrinks the capacity as close to the length as possible, moving the elements back
+    /// inline if they fit.
+    pub fn shrink_to_fit(&mut self) {
+        let len = self.len;
+        let Data::Heap(buf) = &mut self.data else {
+            return;
+        };
+
+        if len <= A::CAPACITY {
+            let mut array = MaybeUninit::<A>::uninit();
+            unsafe { ptr::copy_nonoverlapping(buf.ptr(), array.as_mut_ptr().cast(), len) };
+            // Frees the buffer without dropping the moved elements
+            self.data = Data::Inline(array);
+        } else {
+            buf.shrink(len);
+        }
+    }
+
+    pub fn push(&mut self, elem: A::Item) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+        self.raw().push(elem);
+    }
+
+    /// Like [`push`](SmallVec::push), but returns an error if the vector can't grow.
+    /// `elem` is dropped in that case.
+    pub fn try_push(&mut self, elem: A::Item) -> Result<(), TryReserveError> {
+        self.try_reserve(1)?;
+        self.push(elem);
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<A::Item> {
+        self.raw().pop()
+    }
+
+    pub fn peek(&self) -> Option<&A::Item> {
+        self.last()
+    }
+
+    /// Inserts `elem` at `index`, shifting everything after it to the right.
+    pub fn insert(&mut self, index: usize, elem: A::Item) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+        self.raw().insert(index, elem);
+    }
+
+    /// Removes the element at `index`, shifting everything after it to the left.
+    pub fn remove(&mut self, index: usize) -> A::Item {
+        self.raw().remove(index)
+    }
+
+    /// Removes the element at `index` in O(1) by moving the last element into its place.
+    pub fn swap_remove(&mut self, index: usize) -> A::Item {
+        self.raw().swap_remove(index)
+    }
+
+    /// Drops every element past the first `len`, a spilled vector stays on the heap until
+    /// [`shrink_to_fit`](SmallVec::shrink_to_fit).
+    pub fn truncate(&mut self, len: usize) {
+        self.raw().truncate(len);
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&A::Item) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    /// Keeps only the elements `f` returns `true` for, in their original order.
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut A::Item) -> bool,
+    {
+        self.raw().retain_mut(f);
+    }
+
+    /// Removes consecutive elements that map to the same key.
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut A::Item) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Removes consecutive repeated elements.
+    pub fn dedup(&mut self)
+    where
+        A::Item: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    /// Removes consecutive elements for which `same_bucket(elem, previous_kept)` returns `true`.
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut A::Item, &mut A::Item) -> bool,
+    {
+        self.raw().dedup_by(same_bucket);
+    }
+
+    /// Moves every element of `other` to the end of `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        self.reserve(other.len);
+        self.raw().move_from(&mut other.raw(), 0);
+    }
+
+    pub fn extend_from_slice(&mut self, other: &[A::Item])
+    where
+        A::Item: Clone,
+    {
+        self.reserve(other.len());
+        self.raw().extend_from_slice(other);
+    }
+
+    /// Resizes the vector to `new_len`, filling new slots with the results of `f`.
+    pub fn resize_with<F>(&mut self, new_len: usize, f: F)
+    where
+        F: FnMut() -> A::Item,
+    {
+        self.reserve(new_len.saturating_sub(self.len));
+        self.raw().resize_with(new_len, f);
+    }
+
+    /// Resizes the vector to `new_len`, filling new slots with clones of `value`.
+    pub fn resize(&mut self, new_len: usize, value: A::Item)
+    where
+        A::Item: Clone,
+    {
+        self.reserve(new_len.saturating_sub(self.len));
+        self.raw().resize(new_len, value);
+    }
+
+    /// Splits the vector in two at `at`, returning the elements from `at` onwards.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let mut other = Self::with_capacity(self.len.saturating_sub(at));
+        other.raw().move_from(&mut self.raw(), at);
+        other
+    }
+
+    /// Removes `range` from the vector, yielding the removed elements.
+    ///
+    /// Elements that are not consumed are dropped with the iterator. If the iterator is leaked,
+    /// the vector is left truncated at the start of `range`.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        Drain {
+            raw: self.raw().drain(range),
+            marker: PhantomData,
+        }
+    }
+
+    /// Replaces `range` with the items of `replace_with`, returning the removed elements.
+    ///
+    /// The replacement is collected before the vector is touched, so a panicking iterator
+    /// leaves it unchanged.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Self
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = A::Item>,
+    {
+        let range = slice_range(range, self.len);
+        let mut replacement: Self = replace_with.into_iter().collect();
+
+        let mut removed = Self::with_capacity(range.len());
+        self.reserve(replacement.len.saturating_sub(range.len()));
+        self.raw()
+            .splice(range, &mut replacement.raw(), &mut removed.raw());
+
+        removed
+    }
+
+    /// Converts into a [`Vector`], reusing the allocation if the elements have spilled.
+    pub fn into_vector(self) -> Vector<A::Item> {
+        let mut this = mem::ManuallyDrop::new(self);
+        let len = this.len;
+
+        match mem::replace(&mut this.data, Data::Inline(MaybeUninit::uninit())) {
+            Data::Heap(buf) => unsafe { Vector::from_raw_vec(buf, len) },
+            Data::Inline(array) => {
+                let mut v = Vector::with_capacity(len);
+                unsafe {
+                    ptr::copy_nonoverlapping(array.as_ptr().cast(), v.as_mut_ptr(), len);
+                    v.set_len(len);
+                }
+                v
+            }
+        }
+    }
+
+    fn raw(&mut self) -> LenSlice<'_, A::Item> {
+        let (ptr, cap) = (self.as_mut_ptr(), self.capacity());
+        unsafe { LenSlice::new(ptr, cap, &mut self.len) }
+    }
+}
+
+impl<A: Array> Drop for SmallVec<A> {
+    fn drop(&mut self) {
+        // A heap buffer is freed by RawVec
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+impl<A: Array> Deref for SmallVec<A> {
+    type Target = [A::Item];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<A: Array> DerefMut for SmallVec<A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<A: Array> Default for SmallVec<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Array> Clone for SmallVec<A>
+where
+    A::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut v = Self::with_capacity(self.len);
+        v.extend_from_slice(self);
+        v
+    }
+}
+
+impl<A: Array> fmt::Debug for SmallVec<A>
+where
+    A::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<A: Array, B: Array> PartialEq<SmallVec<B>> for SmallVec<A>
+where
+    A::Item: PartialEq<B::Item>,
+{
+    fn eq(&self, other: &SmallVec<B>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<A: Array, U> PartialEq<[U]> for SmallVec<A>
+where
+    A::Item: PartialEq<U>,
+{
+    fn eq(&self, other: &[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<A: Array, U, const N: usize> PartialEq<[U; N]> for SmallVec<A>
+where
+    A::Item: PartialEq<U>,
+{
+    fn eq(&self, other: &[U; N]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<A: Array> Eq for SmallVec<A> where A::Item: Eq {}
+
+/// Hashes exactly like the equivalent slice.
+impl<A: Array> Hash for SmallVec<A>
+where
+    A::Item: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<A: Array> FromIterator<A::Item> for SmallVec<A> {
+    fn from_iter<I: IntoIterator<Item = A::Item>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<A: Array> Extend<A::Item> for SmallVec<A> {
+    fn extend<I: IntoIterator<Item = A::Item>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|elem| self.push(elem));
+    }
+}
+
+/// A draining iterator over a range of a [`SmallVec`], see [`SmallVec::drain`]
+pub struct Drain<'v, A: Array> {
+    raw: RawDrain<'v, A::Item>,
+    marker: PhantomData<&'v mut SmallVec<A>>,
+}
+
+impl<A: Array> Iterator for Drain<'_, A> {
+    type Item = A::Item;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<A: Array> DoubleEndedIterator for Drain<'_, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.raw.next_back()
+    }
+}
+
+impl<A: Array> ExactSizeIterator for Drain<'_, A> {}
+impl<A: Array> FusedIterator for Drain<'_, A> {}
+
+/// An owning iterator over the elements of a [`SmallVec`]
+pub struct IntoIter<A: Array> {
+    /// Emptied, so dropping it only frees a heap buffer
+    vec: SmallVec<A>,
+    start: usize,
+    end: usize,
+}
+
+impl<A: Array> IntoIter<A> {
+    pub fn as_slice(&self) -> &[A::Item] {
+        unsafe { slice::from_raw_parts(self.vec.as_ptr().add(self.start), self.end - self.start) }
+    }
+}
+
+impl<A: Array> Iterator for IntoIter<A> {
+    type Item = A::Item;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        let elem = unsafe { self.vec.as_ptr().add(self.start).read() };
+        self.start += 1;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<A: Array> DoubleEndedIterator for IntoIter<A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { self.vec.as_ptr().add(self.end).read() })
+    }
+}
+
+impl<A: Array> ExactSizeIterator for IntoIter<A> {}
+impl<A: Array> FusedIterator for IntoIter<A> {}
+
+impl<A: Array> Drop for IntoIter<A> {
+    fn drop(&mut self) {
+        let remaining = ptr::slice_from_raw_parts_mut(
+            unsafe { self.vec.as_mut_ptr().add(self.start) },
+            self.end - self.start,
+        );
+        self.start = self.end;
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
+
+impl<A: Array> IntoIterator for SmallVec<A> {
+    type Item = A::Item;
+    type IntoIter = IntoIter<A>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let end = mem::replace(&mut self.len, 0);
+        IntoIter {
+            vec: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<'v, A: Array> IntoIterator for &'v SmallVec<A> {
+    type Item = &'v A::Item;
+    type IntoIter = slice::Iter<'v, A::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'v, A: Array> IntoIterator for &'v mut SmallVec<A> {
+    type Item = &'v mut A::Item;
+    type IntoIter = slice::IterMut<'v, A::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn stays_inline() {
+        let mut v = SmallVec::<[u32; 4]>::new();
+        assert_eq!(v.capacity(), 4);
+
+        (0..4).for_each(|n| v.push(n));
+        assert!(!v.spilled());
+        assert_eq!(v, [0, 1, 2, 3]);
+        assert_eq!(v.pop(), Some(3));
+        assert_eq!(v.peek(), Some(&2));
+    }
+
+    #[test]
+    fn spills_and_comes_back() {
+        let mut v: SmallVec<[String; 2]> = ["A Game of Thrones", "A Clash of Kings"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert!(!v.spilled());
+
+        v.push("A Storm of Swords".to_string());
+        assert!(v.spilled());
+        assert_eq!(v.capacity(), 4);
+        assert_eq!(v[2], "A Storm of Swords");
+
+        v.insert(0, "The Hedge Knight".to_string());
+        v.truncate(2);
+        v.shrink_to_fit();
+        assert!(!v.spilled());
+        assert_eq!(v, ["The Hedge Knight", "A Game of Thrones"]);
+    }
+
+    #[test]
+    fn editing() {
+        let mut v: SmallVec<[i32; 3]> = (0..6).collect();
+        assert_eq!(v.remove(1), 1);
+        assert_eq!(v.swap_remove(0), 0);
+        v.retain(|n| *n % 2 == 1);
+        assert_eq!(v, [5, 3]);
+
+        v.extend_from_slice(&[7, 9]);
+        v.clear();
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn drain_split_and_splice() {
+        let mut v: SmallVec<[u32; 4]> = (0..8).collect();
+        let drained: Vector<_> = v.drain(2..6).collect();
+        assert_eq!(drained, [2, 3, 4, 5]);
+        assert_eq!(v, [0, 1, 6, 7]);
+
+        let mut tail = v.split_off(2);
+        assert!(!tail.spilled());
+        assert_eq!(tail, [6, 7]);
+        tail.append(&mut v);
+        assert!(v.is_empty());
+        assert_eq!(tail, [6, 7, 0, 1]);
+
+        let removed = tail.splice(1..3, [10, 11, 12]);
+        assert_eq!(removed, [7, 0]);
+        assert!(tail.spilled());
+        assert_eq!(tail, [6, 10, 11, 12, 1]);
+    }
+
+    #[test]
+    fn dedup_and_resize() {
+        let mut v: SmallVec<[_; 2]> = [1, 1, 2, 3, 3, 3, 1].into_iter().collect();
+        v.dedup();
+        assert_eq!(v, [1, 2, 3, 1]);
+        v.dedup_by_key(|n| *n / 2);
+        assert_eq!(v, [1, 2, 1]);
+
+        v.resize(5, 9);
+        assert_eq!(v, [1, 2, 1, 9, 9]);
+        v.resize_with(2, || unreachable!());
+        assert_eq!(v, [1, 2]);
+    }
+
+    #[test]
+    fn try_reserve_and_push() {
+        let mut v = SmallVec::<[u64; 2]>::new();
+        assert_eq!(v.try_push(1), Ok(()));
+        assert_eq!(v.try_reserve(1), Ok(()));
+        assert!(!v.spilled());
+
+        assert_eq!(
+            v.try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert!(v.try_reserve(usize::MAX / 2).is_err());
+        assert!(!v.spilled());
+
+        assert_eq!(v.try_reserve(10), Ok(()));
+        assert!(v.spilled());
+        assert!(v.capacity() >= 11);
+        assert_eq!(v, [1]);
+    }
+
+    #[test]
+    fn retain_mut_panicking() {
+        let mut v: SmallVec<[u32; 4]> = (0..8).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            v.retain_mut(|n| {
+                assert!(*n != 5);
+                *n % 2 == 0
+            })
+        }));
+        assert!(result.is_err());
+        // Like `Vector`: what was processed is filtered, the rest kept in order
+        assert_eq!(v, [0, 2, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn drops_elements() {
+        let book = Rc::new("A Feast for Crows");
+        let mut inline = SmallVec::<[_; 4]>::new();
+        let mut spilled = SmallVec::<[_; 2]>::new();
+        (0..3).for_each(|_| inline.push(Rc::clone(&book)));
+        (0..3).for_each(|_| spilled.push(Rc::clone(&book)));
+        assert_eq!(Rc::strong_count(&book), 7);
+
+        drop(inline);
+        let mut iter = spilled.into_iter();
+        iter.next();
+        assert_eq!(Rc::strong_count(&book), 3);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&book), 1);
+    }
+
+    #[test]
+    fn into_iter_and_vector() {
+        let v: SmallVec<[u8; 2]> = (1..=4).collect();
+        let mut iter = v.clone().into_iter();
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.as_slice(), [1, 2, 3]);
+        assert_eq!(iter.sum::<u8>(), 6);
+
+        assert_eq!(v.into_vector(), [1, 2, 3, 4]);
+        let inline: SmallVec<[u8; 8]> = (1..=4).collect();
+        assert_eq!(inline.into_vector(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn zst_never_spills() {
+        let mut v = SmallVec::<[(); 1]>::new();
+        (0..100).for_each(|_| v.push(()));
+        assert!(!v.spilled());
+        assert_eq!(v.len(), 100);
+        assert_eq!(v.into_iter().count(), 100);
+    }
+
+    #[test]
+    fn empty_array() {
+        let mut v = SmallVec::<[u64; 0]>::new();
+        v.push(1);
+        assert!(v.spilled());
+        assert_eq!(format!("{v:?}"), "[1]");
+    }
+}
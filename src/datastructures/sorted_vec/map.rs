@@ -0,0 +1,509 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    iter::FusedIterator,
+    ops::{Bound, Index, RangeBounds},
+    slice,
+};
+
+use crate::{
+    datastructures::{vector, Vector},
+    sorting::quicksort_by,
+};
+
+/// A map that keeps its entries sorted by key in a single [`Vector`].
+///
+/// Lookups are binary searches, inserting and removing shift the entries behind. That makes
+/// it a good fit for small or rarely modified maps, which it stores more compactly than a
+/// [`HashMap`](crate::datastructures::HashMap), and iterates in key order.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SortedVecMap<K, V> {
+    entries: Vector<(K, V)>,
+}
+
+impl<K: Ord, V> SortedVecMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vector::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vector::with_capacity(capacity),
+        }
+    }
+
+    /// Builds a map from unsorted entries in O(n log n), the last value wins for repeated keys.
+    pub fn from_vector(entries: Vector<(K, V)>) -> Self {
+        // Tagging every entry with its position makes the unstable sort deterministic
+        let mut tagged: Vector<(usize, (K, V))> = entries.into_iter().enumerate().collect();
+        quicksort_by(&mut tagged, |(i, (a, _)), (j, (b, _))| {
+            a.cmp(b).then(i.cmp(j))
+        });
+
+        let mut entries: Vector<(K, V)> = Vector::with_capacity(tagged.len());
+        for (_, (key, value)) in tagged {
+            match entries.last_mut() {
+                Some(last) if last.0 == key => *last = (key, value),
+                _ => entries.push((key, value)),
+            }
+        }
+        Self { entries }
+    }
+
+    /// Finds the position of `key`, or where it would have to be inserted.
+    fn search<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entries.binary_search_by(|(k, _)| k.borrow().cmp(key))
+    }
+
+    /// Inserts `value` under `key` and returns the old value, if there was one.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.search(&key) {
+            Ok(i) => Some(std::mem::replace(&mut self.entries[i].1, value)),
+            Err(i) => {
+                self.entries.insert(i, (key, value));
+                None
+            }
+        }
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let i = self.search(key).ok()?;
+        let (k, v) = &self.entries[i];
+        Some((k, v))
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let i = self.search(key).ok()?;
+        Some(&mut self.entries[i].1)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(key).is_ok()
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let i = self.search(key).ok()?;
+        Some(self.entries.remove(i))
+    }
+
+    /// Iterates over the entries whose keys fall into `range`, in key order.
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(key) => self.search(key).unwrap_or_else(|i| i),
+            Bound::Excluded(key) => self.search(key).map_or_else(|i| i, |i| i + 1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(key) => self.search(key).map_or_else(|i| i, |i| i + 1),
+            Bound::Excluded(key) => self.search(key).unwrap_or_else(|i| i),
+            Bound::Unbounded => self.len(),
+        };
+
+        Iter {
+            entries: self.entries[start..end.max(start)].iter(),
+        }
+    }
+
+    /// Keeps only the entries `f` returns `true` for.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.entries.retain_mut(|(k, v)| f(k, v));
+    }
+
+    /// Moves every entry of `other` into `self`, leaving `other` empty. Values of `other` win
+    /// for keys in both maps.
+    pub fn append(&mut self, other: &mut Self) {
+        let mut merged = Vector::with_capacity(self.len() + other.len());
+        let mut a = self.entries.drain(..).peekable();
+        let mut b = other.entries.drain(..).peekable();
+
+        loop {
+            let order = match (a.peek(), b.peek()) {
+                (Some((x, _)), Some((y, _))) => x.cmp(y),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => break,
+            };
+            match order {
+                Ordering::Less => merged.push(a.next().unwrap()),
+                Ordering::Greater => merged.push(b.next().unwrap()),
+                Ordering::Equal => {
+                    a.next();
+                    merged.push(b.next().unwrap());
+                }
+            }
+        }
+
+        drop((a, b));
+        self.entries = merged;
+    }
+}
+
+impl<K, V> SortedVecMap<K, V> {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.entries.first().map(|(k, v)| (k, v))
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.entries.last().map(|(k, v)| (k, v))
+    }
+
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        (!self.is_empty()).then(|| self.entries.remove(0))
+    }
+
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        self.entries.pop()
+    }
+
+    /// Iterates over the entries in key order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            entries: self.entries.iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            entries: self.entries.iter_mut(),
+        }
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator + '_ {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator + '_ {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    pub fn values_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut V> + ExactSizeIterator + '_ {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+
+    /// Returns the entries as a slice sorted by key.
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.entries
+    }
+
+    pub fn into_vector(self) -> Vector<(K, V)> {
+        self.entries
+    }
+}
+
+impl<K: Ord, V> Default for SortedVecMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for SortedVecMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, Q, V> Index<&Q> for SortedVecMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &Self::Output {
+        self.get(key).expect("Key not found")
+    }
+}
+
+impl<K: Ord, V> From<Vector<(K, V)>> for SortedVecMap<K, V> {
+    fn from(entries: Vector<(K, V)>) -> Self {
+        Self::from_vector(entries)
+    }
+}
+
+impl<K: Ord, V, const N: usize> From<[(K, V); N]> for SortedVecMap<K, V> {
+    fn from(entries: [(K, V); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+/// Sorts all entries at once, see [`SortedVecMap::from_vector`].
+impl<K: Ord, V> FromIterator<(K, V)> for SortedVecMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::from_vector(iter.into_iter().collect())
+    }
+}
+
+/// Inserts one entry at a time if there are few of them, otherwise sorts them all at once and
+/// merges them in.
+impl<K: Ord, V> Extend<(K, V)> for SortedVecMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        if iter.size_hint().0 < 8 {
+            iter.for_each(|(k, v)| {
+                self.insert(k, v);
+            });
+        } else {
+            self.append(&mut iter.collect());
+        }
+    }
+}
+
+/// An iterator over the entries of a [`SortedVecMap`], in key order
+#[derive(Clone)]
+pub struct Iter<'m, K, V> {
+    entries: slice::Iter<'m, (K, V)>,
+}
+
+impl<'m, K, V> Iterator for Iter<'m, K, V> {
+    type Item = (&'m K, &'m V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(k, v)| (k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// An iterator over the entries of a [`SortedVecMap`] with mutable values, in key order
+pub struct IterMut<'m, K, V> {
+    entries: slice::IterMut<'m, (K, V)>,
+}
+
+impl<'m, K, V> Iterator for IterMut<'m, K, V> {
+    type Item = (&'m K, &'m mut V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(k, v)| (&*k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back().map(|(k, v)| (&*k, v))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+impl<K, V> IntoIterator for SortedVecMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = vector::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'m, K, V> IntoIterator for &'m SortedVecMap<K, V> {
+    type Item = (&'m K, &'m V);
+    type IntoIter = Iter<'m, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'m, K, V> IntoIterator for &'m mut SortedVecMap<K, V> {
+    type Item = (&'m K, &'m mut V);
+    type IntoIter = IterMut<'m, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::vector;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = SortedVecMap::new();
+        assert_eq!(map.insert("A Storm of Swords", 2000), None);
+        assert_eq!(map.insert("A Game of Thrones", 1996), None);
+        assert_eq!(map.insert("A Clash of Kings", 1998), None);
+        assert_eq!(map.insert("A Clash of Kings", 1999), Some(1998));
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(map["A Clash of Kings"], 1999);
+        assert_eq!(map.get("A Feast for Crows"), None);
+        *map.get_mut("A Clash of Kings").unwrap() = 1998;
+
+        assert!(map.keys().copied().eq([
+            "A Clash of Kings",
+            "A Game of Thrones",
+            "A Storm of Swords"
+        ]));
+        assert_eq!(map.remove("A Game of Thrones"), Some(1996));
+        assert_eq!(map.remove("A Game of Thrones"), None);
+        assert!(!map.contains_key("A Game of Thrones"));
+        assert_eq!(map.first_key_value(), Some((&"A Clash of Kings", &1998)));
+        assert_eq!(map.pop_last(), Some(("A Storm of Swords", 2000)));
+    }
+
+    #[test]
+    fn borrowed_lookups() {
+        let map: SortedVecMap<String, usize> = ["A Feast for Crows", "A Dance with Dragons"]
+            .into_iter()
+            .map(|title| (title.to_string(), title.len()))
+            .collect();
+
+        assert_eq!(map.get("A Feast for Crows"), Some(&17));
+        assert_eq!(
+            map.range::<str, _>((Bound::Included("B"), Bound::Unbounded))
+                .count(),
+            0
+        );
+        assert_eq!(
+            format!("{map:?}"),
+            r#"{"A Dance with Dragons": 20, "A Feast for Crows": 17}"#
+        );
+    }
+
+    #[test]
+    fn bulk_build_last_wins() {
+        let map = SortedVecMap::from(vector![(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd'), (1, 'e')]);
+        assert_eq!(map.as_slice(), [(1, 'e'), (2, 'd'), (3, 'c')]);
+
+        let empty: SortedVecMap<u8, u8> = [].into();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn range() {
+        let map: SortedVecMap<u32, u32> = (0..20).map(|n| (n * 5, n)).collect();
+        let keys = |r: Iter<'_, u32, u32>| r.map(|(k, _)| *k).collect::<Vec<_>>();
+
+        assert_eq!(keys(map.range(10..25)), [10, 15, 20]);
+        assert_eq!(keys(map.range(11..=25)), [15, 20, 25]);
+        assert_eq!(keys(map.range(..7)), [0, 5]);
+        assert_eq!(keys(map.range(93..)), [95]);
+        assert_eq!(
+            keys(map.range((Bound::Excluded(10), Bound::Excluded(20)))),
+            [15]
+        );
+        assert_eq!(keys(map.range(30..30)), []);
+        assert_eq!(map.range(..).len(), 20);
+        assert_eq!(map.range(..).next_back(), Some((&95, &19)));
+    }
+
+    #[test]
+    fn extend_and_retain() {
+        let mut map: SortedVecMap<u32, &str> = [(5, "five"), (1, "one")].into();
+        map.extend([(3, "three")]);
+        map.extend((0..10).map(|n| (n * 2, "even")));
+        assert_eq!(map.len(), 13);
+        assert_eq!(map[&1], "one");
+        assert_eq!(map[&4], "even");
+
+        map.retain(|k, _| k % 3 == 0);
+        assert!(map.keys().copied().eq([0, 3, 6, 12, 18]));
+        for (_, v) in &mut map {
+            *v = "div3";
+        }
+        assert!(map.values().all(|v| *v == "div3"));
+        assert_eq!(map.into_iter().next(), Some((0, "div3")));
+    }
+
+    #[test]
+    fn matches_btree_map() {
+        let mut rng = StdRng::seed_from_u64(44);
+        let mut map = SortedVecMap::new();
+        let mut expected = BTreeMap::new();
+
+        for _ in 0..3000 {
+            let key = rng.gen_range(0..200);
+            if rng.gen_bool(0.6) {
+                assert_eq!(map.insert(key, key * 2), expected.insert(key, key * 2));
+            } else {
+                assert_eq!(map.remove(&key), expected.remove(&key));
+            }
+        }
+        assert!(map.iter().eq(expected.iter()));
+        assert!(map.range(50..150).eq(expected.range(50..150)));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod map;
+pub mod set;
+
+pub use map::SortedVecMap;
+pub use set::SortedVecSet;
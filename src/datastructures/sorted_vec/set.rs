@@ -0,0 +1,378 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    iter::{FusedIterator, Peekable},
+    ops::RangeBounds,
+};
+
+use super::{map, SortedVecMap};
+use crate::datastructures::Vector;
+
+/// A set that keeps its elements sorted in a single [`Vector`], see [`SortedVecMap`].
+///
+/// Set operations walk both sets side by side, so they take linear time.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SortedVecSet<T> {
+    map: SortedVecMap<T, ()>,
+}
+
+impl<T: Ord> SortedVecSet<T> {
+    pub fn new() -> Self {
+        Self {
+            map: SortedVecMap::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: SortedVecMap::with_capacity(capacity),
+        }
+    }
+
+    /// Builds a set from unsorted, possibly repeated elements in O(n log n).
+    pub fn from_vector(elems: Vector<T>) -> Self {
+        Self {
+            map: elems.into_iter().map(|elem| (elem, ())).collect(),
+        }
+    }
+
+    /// Adds `elem`, returns whether it was new.
+    pub fn insert(&mut self, elem: T) -> bool {
+        self.map.insert(elem, ()).is_none()
+    }
+
+    pub fn contains<Q>(&self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(elem)
+    }
+
+    pub fn get<Q>(&self, elem: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.get_key_value(elem).map(|(k, _)| k)
+    }
+
+    /// Removes `elem`, returns whether it was there.
+    pub fn remove<Q>(&mut self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(elem).is_some()
+    }
+
+    pub fn take<Q>(&mut self, elem: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove_entry(elem).map(|(k, _)| k)
+    }
+
+    /// Iterates over the elements in `range`, in order.
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Iter {
+            iter: self.map.range(range),
+        }
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain(|k, _| f(k));
+    }
+
+    /// Moves every element of `other` into `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        self.map.append(&mut other.map);
+    }
+
+    /// The elements in `self` or `other`.
+    pub fn union<'s>(&'s self, other: &'s Self) -> SetOp<'s, T> {
+        SetOp::new(self, other, |_, _| true)
+    }
+
+    /// The elements in both `self` and `other`.
+    pub fn intersection<'s>(&'s self, other: &'s Self) -> SetOp<'s, T> {
+        SetOp::new(self, other, |a, b| a && b)
+    }
+
+    /// The elements in `self` but not in `other`.
+    pub fn difference<'s>(&'s self, other: &'s Self) -> SetOp<'s, T> {
+        SetOp::new(self, other, |a, b| a && !b)
+    }
+
+    /// The elements in exactly one of `self` and `other`.
+    pub fn symmetric_difference<'s>(&'s self, other: &'s Self) -> SetOp<'s, T> {
+        SetOp::new(self, other, |a, b| a != b)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.difference(other).next().is_none()
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+}
+
+impl<T> SortedVecSet<T> {
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|(k, _)| k)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|(k, _)| k)
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.map.pop_first().map(|(k, _)| k)
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.map.pop_last().map(|(k, _)| k)
+    }
+
+    /// Iterates over the elements in order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+}
+
+impl<T: Ord> Default for SortedVecSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SortedVecSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord> From<Vector<T>> for SortedVecSet<T> {
+    fn from(elems: Vector<T>) -> Self {
+        Self::from_vector(elems)
+    }
+}
+
+impl<T: Ord, const N: usize> From<[T; N]> for SortedVecSet<T> {
+    fn from(elems: [T; N]) -> Self {
+        elems.into_iter().collect()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SortedVecSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            map: iter.into_iter().map(|elem| (elem, ())).collect(),
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for SortedVecSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|elem| (elem, ())));
+    }
+}
+
+/// An iterator over the elements of a [`SortedVecSet`], in order
+#[derive(Clone)]
+pub struct Iter<'s, T> {
+    iter: map::Iter<'s, T, ()>,
+}
+
+impl<'s, T> Iterator for Iter<'s, T> {
+    type Item = &'s T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<T> IntoIterator for SortedVecSet<T> {
+    type Item = T;
+    type IntoIter =
+        std::iter::Map<<SortedVecMap<T, ()> as IntoIterator>::IntoIter, fn((T, ())) -> T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let key: fn((T, ())) -> T = |(k, _)| k;
+        self.map.into_iter().map(key)
+    }
+}
+
+impl<'s, T> IntoIterator for &'s SortedVecSet<T> {
+    type Item = &'s T;
+    type IntoIter = Iter<'s, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A set operation on two [`SortedVecSet`]s, yielding the result in order.
+///
+/// Merges both sets in one pass and keeps the elements `keep(in_self, in_other)` accepts.
+#[derive(Clone)]
+pub struct SetOp<'s, T> {
+    a: Peekable<Iter<'s, T>>,
+    b: Peekable<Iter<'s, T>>,
+    keep: fn(bool, bool) -> bool,
+}
+
+impl<'s, T: Ord> SetOp<'s, T> {
+    fn new(a: &'s SortedVecSet<T>, b: &'s SortedVecSet<T>, keep: fn(bool, bool) -> bool) -> Self {
+        Self {
+            a: a.iter().peekable(),
+            b: b.iter().peekable(),
+            keep,
+        }
+    }
+}
+
+impl<'s, T: Ord> Iterator for SetOp<'s, T> {
+    type Item = &'s T;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let order = match (self.a.peek(), self.b.peek()) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => return None,
+            };
+            let (elem, in_a, in_b) = match order {
+                Ordering::Less => (self.a.next(), true, false),
+                Ordering::Greater => (self.b.next(), false, true),
+                Ordering::Equal => {
+                    self.b.next();
+                    (self.a.next(), true, true)
+                }
+            };
+            if (self.keep)(in_a, in_b) {
+                return elem;
+            }
+        }
+    }
+}
+
+impl<T: Ord> FusedIterator for SetOp<'_, T> {}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::vector;
+
+    #[test]
+    fn insert_remove() {
+        let mut set = SortedVecSet::new();
+        assert!(set.insert("A Game of Thrones"));
+        assert!(set.insert("A Clash of Kings"));
+        assert!(!set.insert("A Game of Thrones"));
+
+        assert_eq!(set.len(), 2);
+        assert!(set.contains("A Clash of Kings"));
+        assert_eq!(set.first(), Some(&"A Clash of Kings"));
+        assert!(set.remove("A Clash of Kings"));
+        assert!(!set.remove("A Clash of Kings"));
+        assert_eq!(set.take("A Game of Thrones"), Some("A Game of Thrones"));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn bulk_build() {
+        let set = SortedVecSet::from(vector![5, 3, 5, 1, 3, 9]);
+        assert!(set.iter().copied().eq([1, 3, 5, 9]));
+        assert_eq!(format!("{set:?}"), "{1, 3, 5, 9}");
+        assert!(set.range(2..=5).copied().eq([3, 5]));
+        assert_eq!(set.into_iter().next_back(), Some(9));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = SortedVecSet::from([1, 2, 3, 4, 5]);
+        let b = SortedVecSet::from([4, 5, 6, 7]);
+
+        assert!(a.union(&b).copied().eq(1..=7));
+        assert!(a.intersection(&b).copied().eq([4, 5]));
+        assert!(a.difference(&b).copied().eq([1, 2, 3]));
+        assert!(b.difference(&a).copied().eq([6, 7]));
+        assert!(a.symmetric_difference(&b).copied().eq([1, 2, 3, 6, 7]));
+
+        let c = SortedVecSet::from([2, 3]);
+        assert!(c.is_subset(&a));
+        assert!(a.is_superset(&c));
+        assert!(!a.is_subset(&c));
+        assert!(c.is_disjoint(&b));
+        assert!(!a.is_disjoint(&b));
+    }
+
+    #[test]
+    fn matches_btree_set() {
+        let mut rng = StdRng::seed_from_u64(44);
+        let gen =
+            |rng: &mut StdRng| -> Vec<u32> { (0..300).map(|_| rng.gen_range(0..400)).collect() };
+        let (xs, ys) = (gen(&mut rng), gen(&mut rng));
+
+        let (a, b): (SortedVecSet<u32>, SortedVecSet<u32>) =
+            (xs.iter().copied().collect(), ys.iter().copied().collect());
+        let (ea, eb): (BTreeSet<u32>, BTreeSet<u32>) =
+            (xs.into_iter().collect(), ys.into_iter().collect());
+
+        assert!(a.iter().eq(ea.iter()));
+        assert!(a.union(&b).eq(ea.union(&eb)));
+        assert!(a.intersection(&b).eq(ea.intersection(&eb)));
+        assert!(a.difference(&b).eq(ea.difference(&eb)));
+        assert!(a.symmetric_difference(&b).eq(ea.symmetric_difference(&eb)));
+
+        let mut merged = a.clone();
+        merged.append(&mut b.clone());
+        assert!(merged.iter().eq(ea.union(&eb)));
+    }
+}
@@ -0,0 +1,364 @@
+use std::{
+    iter::FusedIterator,
+    ops::{Range, RangeBounds},
+    ptr, slice,
+};
+
+use super::VecDeque;
+use crate::datastructures::{vector::vector::slice_range, Allocator, Global};
+
+/// An iterator over references to the elements of a [`VecDeque`], front to back
+#[derive(Clone)]
+pub struct Iter<'d, T> {
+    /// The part up to the end of the buffer
+    front: slice::Iter<'d, T>,
+    /// The part that wrapped around to the start of the buffer
+    back: slice::Iter<'d, T>,
+}
+
+impl<'d, T> Iterator for Iter<'d, T> {
+    type Item = &'d T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over mutable references to the elements of a [`VecDeque`], front to back
+pub struct IterMut<'d, T> {
+    front: slice::IterMut<'d, T>,
+    back: slice::IterMut<'d, T>,
+}
+
+impl<'d, T> Iterator for IterMut<'d, T> {
+    type Item = &'d mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// An owning iterator over the elements of a [`VecDeque`], front to back
+pub struct IntoIter<T, A: Allocator = Global> {
+    deque: VecDeque<T, A>,
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.len(), Some(self.deque.len()))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.deque.pop_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> VecDeque<T, A> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+        Iter {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        IterMut {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+
+    /// Removes `range` from the deque, yielding the removed elements.
+    ///
+    /// Elements that are not consumed are dropped with the iterator, then the gap is closed by
+    /// moving whichever side of it is shorter. If the iterator is leaked, the deque is left
+    /// truncated at the start of `range`.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let Range { start, end } = slice_range(range, len);
+
+        self.len = start;
+
+        Drain {
+            deque: self,
+            iter: start..end,
+            drain_start: start,
+            drain_end: end,
+            orig_len: len,
+        }
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for VecDeque<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { deque: self }
+    }
+}
+
+impl<'d, T, A: Allocator> IntoIterator for &'d VecDeque<T, A> {
+    type Item = &'d T;
+    type IntoIter = Iter<'d, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'d, T, A: Allocator> IntoIterator for &'d mut VecDeque<T, A> {
+    type Item = &'d mut T;
+    type IntoIter = IterMut<'d, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A draining iterator over a range of a [`VecDeque`], see [`VecDeque::drain`]
+pub struct Drain<'d, T, A: Allocator = Global> {
+    deque: &'d mut VecDeque<T, A>,
+    /// The drained elements not yielded yet, as logical indices from the unchanged `head`
+    iter: Range<usize>,
+    drain_start: usize,
+    drain_end: usize,
+    orig_len: usize,
+}
+
+impl<T, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|i| unsafe { self.deque.slot(i).read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for Drain<'_, T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next_back()
+            .map(|i| unsafe { self.deque.slot(i).read() })
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {}
+impl<T, A: Allocator> FusedIterator for Drain<'_, T, A> {}
+
+impl<T, A: Allocator> Drop for Drain<'_, T, A> {
+    fn drop(&mut self) {
+        /// Closes the gap, even if dropping the remaining elements panics
+        struct CloseGap<'a, 'd, T, A: Allocator>(&'a mut Drain<'d, T, A>);
+
+        impl<T, A: Allocator> Drop for CloseGap<'_, '_, T, A> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let deque = &mut *drain.deque;
+                let (start, end) = (drain.drain_start, drain.drain_end);
+                let drain_len = end - start;
+                let tail_len = drain.orig_len - end;
+
+                unsafe {
+                    if start < tail_len {
+                        // Move the front elements right, the last one first
+                        for i in (0..start).rev() {
+                            ptr::copy(deque.slot(i), deque.slot(i + drain_len), 1);
+                        }
+                        deque.head = deque.to_physical(drain_len);
+                    } else {
+                        for i in end..drain.orig_len {
+                            ptr::copy(deque.slot(i), deque.slot(i - drain_len), 1);
+                        }
+                    }
+                }
+                deque.len = drain.orig_len - drain_len;
+            }
+        }
+
+        let remaining = self.iter.clone();
+        self.iter = 0..0;
+
+        let guard = CloseGap(self);
+        unsafe { guard.0.deque.drop_range(remaining) };
+    }
+}
+
+impl<T> FromIterator<T> for VecDeque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = VecDeque::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T, A: Allocator> Extend<T> for VecDeque<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|elem| self.push_back(elem));
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator> Extend<&'a T> for VecDeque<T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        cell::Cell,
+        panic::{self, AssertUnwindSafe},
+    };
+
+    use super::*;
+    use crate::datastructures::vec_deque::vec_deque::wrapped;
+
+    #[test]
+    fn iter_wraps() {
+        let mut deque = wrapped(6, 2);
+        assert!(deque.iter().copied().eq(0..6));
+        assert!(deque.iter().rev().copied().eq((0..6).rev()));
+        assert_eq!(deque.iter().len(), 6);
+
+        let mut iter = deque.iter();
+        assert_eq!(iter.next_back(), Some(&5));
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.len(), 4);
+
+        deque.iter_mut().for_each(|n| *n *= 10);
+        for n in &mut deque {
+            *n += 1;
+        }
+        assert_eq!((&deque).into_iter().sum::<usize>(), 156);
+    }
+
+    #[test]
+    fn into_iter() {
+        let deque = wrapped(5, 3);
+        let mut iter = deque.into_iter();
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn drain_matches_vec() {
+        for split in 1..8 {
+            for start in 0..=8 {
+                for end in start..=8 {
+                    let mut deque = wrapped(8, split);
+                    let mut expected: Vec<usize> = (0..8).collect();
+
+                    let drained: Vec<usize> = deque.drain(start..end).collect();
+                    assert!(drained.into_iter().eq(expected.drain(start..end)));
+                    assert_eq!(deque, expected[..]);
+
+                    deque.push_back(100);
+                    deque.push_front(200);
+                    assert_eq!(deque.len(), expected.len() + 2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn drain_unconsumed() {
+        let mut deque = wrapped(10, 4);
+        let mut drain = deque.drain(2..8);
+        assert_eq!(drain.next(), Some(2));
+        assert_eq!(drain.next_back(), Some(7));
+        drop(drain);
+
+        assert_eq!(deque, [0, 1, 8, 9]);
+    }
+
+    #[test]
+    fn drain_leaked() {
+        let mut deque = wrapped(10, 4);
+        std::mem::forget(deque.drain(2..8));
+
+        assert_eq!(deque, [0, 1]);
+    }
+
+    #[test]
+    fn drain_panicking_destructor() {
+        struct PanicOnDrop<'a>(usize, &'a Cell<usize>);
+
+        impl Drop for PanicOnDrop<'_> {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+                assert!(self.0 != 3);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut deque = VecDeque::with_capacity(6);
+        (0..6)
+            .rev()
+            .for_each(|i| deque.push_front(PanicOnDrop(i, &drops)));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| drop(deque.drain(1..5))));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 4);
+        assert_eq!(deque.iter().map(|p| p.0).collect::<Vec<_>>(), [0, 5]);
+
+        drop(deque);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn extend_and_collect() {
+        let mut deque: VecDeque<u32> = (1..3).collect();
+        deque.extend(&[3, 4]);
+        deque.push_front(0);
+        assert_eq!(deque, [0, 1, 2, 3, 4]);
+    }
+}
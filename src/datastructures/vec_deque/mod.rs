@@ -0,0 +1,6 @@
+pub mod iter;
+#[allow(clippy::module_inception)]
+pub mod vec_deque;
+
+pub use iter::*;
+pub use vec_deque::VecDeque;
@@ -0,0 +1,671 @@
+use std::{
+    fmt,
+    mem::ManuallyDrop,
+    ops::{Index, IndexMut, Range},
+    ptr, slice,
+};
+
+use crate::datastructures::{Allocator, Global, RawVec, Vector};
+
+/// A double ended queue in a growable ring buffer.
+///
+/// The elements start at `head` and wrap around the end of the buffer, so both ends can be
+/// pushed and popped in O(1). Like [`Vector`], zero sized types never allocate.
+pub struct VecDeque<T, A: Allocator = Global> {
+    pub(super) buf: RawVec<T, A>,
+    /// The physical index of the front element
+    pub(super) head: usize,
+    pub(super) len: usize,
+}
+
+impl<T> VecDeque<T> {
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Creates an empty deque with room for exactly `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, A: Allocator> VecDeque<T, A> {
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            buf: RawVec::new_in(alloc),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            buf: RawVec::with_capacity_in(capacity, alloc),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.buf.allocator()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Maps the logical index `idx`, counted from `head` and below twice the capacity,
+    /// into the buffer.
+    pub(super) fn to_physical(&self, idx: usize) -> usize {
+        let cap = self.capacity();
+        let idx = if idx >= cap { idx - cap } else { idx };
+        let physical = self.head.wrapping_add(idx);
+        if physical >= cap {
+            physical - cap
+        } else {
+            physical
+        }
+    }
+
+    /// Returns the slot of the element at logical index `idx`.
+    pub(super) fn slot(&self, idx: usize) -> *mut T {
+        unsafe { self.buf.ptr().add(self.to_physical(idx)) }
+    }
+
+    fn is_contiguous(&self) -> bool {
+        // Written to not overflow for the usize::MAX capacity of ZSTs
+        self.head <= self.capacity() - self.len
+    }
+
+    /// Makes room for at least `additional` more elements.
+    pub fn reserve(&mut self, additional: usize) {
+        let old_cap = self.capacity();
+        self.buf.reserve(self.len, additional);
+        if self.capacity() != old_cap {
+            unsafe { self.handle_capacity_increase(old_cap) };
+        }
+    }
+
+    /// Restores the ring after the buffer grew from `old_cap`.
+    ///
+    /// A wrapped deque has its front part at the end of the old buffer, which now sits in the
+    /// middle. Either the back part moves after it, or the front part moves to the new end,
+    /// whichever is shorter.
+    unsafe fn handle_capacity_increase(&mut self, old_cap: usize) {
+        let new_cap = self.capacity();
+        if self.head <= old_cap - self.len {
+            return;
+        }
+
+        let head_len = old_cap - self.head;
+        let tail_len = self.len - head_len;
+        let p = self.buf.ptr();
+
+        if tail_len < head_len && tail_len <= new_cap - old_cap {
+            ptr::copy_nonoverlapping(p, p.add(old_cap), tail_len);
+        } else {
+            let new_head = new_cap - head_len;
+            ptr::copy(p.add(self.head), p.add(new_head), head_len);
+            self.head = new_head;
+        }
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+
+        unsafe { self.slot(self.len).write(elem) };
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+
+        // One step back from the head, wrapping to the end of the buffer
+        self.head = self.to_physical(self.capacity() - 1);
+        unsafe { self.slot(0).write(elem) };
+        self.len += 1;
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.len -= 1;
+        Some(unsafe { self.slot(self.len).read() })
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let elem = unsafe { self.slot(0).read() };
+        self.head = self.to_physical(1);
+        self.len -= 1;
+        Some(elem)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        (index < self.len).then(|| unsafe { &*self.slot(index) })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        (index < self.len).then(|| unsafe { &mut *self.slot(index) })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.len.checked_sub(1).and_then(|i| self.get_mut(i))
+    }
+
+    pub fn swap(&mut self, i: usize, j: usize) {
+        let len = self.len;
+        assert!(
+            i < len && j < len,
+            "swap indices ({i}, {j}) out of bounds for len {len}"
+        );
+        unsafe { ptr::swap(self.slot(i), self.slot(j)) }
+    }
+
+    /// Keeps the first `len` elements and drops the rest, wherever they wrap around to.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let old_len = self.len;
+        // `drop_range` carries on past a panicking destructor, the deque must already end at `len`
+        self.len = len;
+        unsafe { self.drop_range(len..old_len) };
+    }
+
+    /// Drops the elements in the logical `range`, all of them even if a destructor panics.
+    ///
+    /// # Safety
+    ///
+    /// The elements must be initialized and are not used afterwards.
+    pub(super) unsafe fn drop_range(&mut self, range: Range<usize>) {
+        /// Drops the wrapped part even if dropping the first part panics
+        struct DropSlice<T>(*mut [T]);
+
+        impl<T> Drop for DropSlice<T> {
+            fn drop(&mut self) {
+                unsafe { ptr::drop_in_place(self.0) }
+            }
+        }
+
+        let (front, back) = self.physical_ranges(range);
+        let p = self.buf.ptr();
+        let _back = DropSlice(ptr::slice_from_raw_parts_mut(p.add(back.start), back.len()));
+        ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+            p.add(front.start),
+            front.len(),
+        ));
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.head = 0;
+    }
+
+    /// The elements as two slices: the front part up to the end of the buffer, then the part
+    /// that wrapped around to its start.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (front, back) = self.physical_ranges(0..self.len);
+        let p = self.buf.ptr();
+        unsafe {
+            (
+                slice::from_raw_parts(p.add(front.start), front.len()),
+                slice::from_raw_parts(p.add(back.start), back.len()),
+            )
+        }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (front, back) = self.physical_ranges(0..self.len);
+        let p = self.buf.ptr();
+        unsafe {
+            (
+                slice::from_raw_parts_mut(p.add(front.start), front.len()),
+                slice::from_raw_parts_mut(p.add(back.start), back.len()),
+            )
+        }
+    }
+
+    /// Splits the logical `range` into the part up to the end of the buffer and the part that
+    /// wrapped around to its start.
+    pub(super) fn physical_ranges(&self, range: Range<usize>) -> (Range<usize>, Range<usize>) {
+        let start = self.to_physical(range.start);
+        let len = range.len();
+        let until_end = self.capacity() - start;
+
+        if len <= until_end {
+            (start..start + len, 0..0)
+        } else {
+            (start..self.capacity(), 0..len - until_end)
+        }
+    }
+
+    /// Moves the elements so they sit in one slice, and returns it.
+    ///
+    /// Wrapped elements are first moved to close the free gap, so the two parts end up next to
+    /// each other in the wrong order, then swapped into place with an in place rotation.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if !self.is_contiguous() {
+            let head_len = self.capacity() - self.head;
+            let tail_len = self.len - head_len;
+            let free = self.capacity() - self.len;
+
+            unsafe {
+                let p = self.buf.ptr();
+                ptr::copy(p, p.add(free), tail_len);
+                self.head = free;
+                slice::from_raw_parts_mut(p.add(free), self.len).rotate_left(tail_len);
+            }
+        }
+
+        self.as_mut_slices().0
+    }
+
+    /// Rotates the deque `n` places to the left, the element at `n` becomes the front.
+    ///
+    /// Takes O(min(n, len - n)) moves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is larger than the length.
+    pub fn rotate_left(&mut self, n: usize) {
+        let len = self.len;
+        assert!(n <= len, "rotation (is {n}) should be <= len (is {len})");
+
+        if n <= len - n {
+            unsafe { self.rotate_left_inner(n) }
+        } else {
+            unsafe { self.rotate_right_inner(len - n) }
+        }
+    }
+
+    /// Rotates the deque `n` places to the right, the element at `len - n` becomes the front.
+    ///
+    /// Takes O(min(n, len - n)) moves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is larger than the length.
+    pub fn rotate_right(&mut self, n: usize) {
+        let len = self.len;
+        assert!(n <= len, "rotation (is {n}) should be <= len (is {len})");
+
+        if n <= len - n {
+            unsafe { self.rotate_right_inner(n) }
+        } else {
+            unsafe { self.rotate_left_inner(len - n) }
+        }
+    }
+
+    /// Moves the first `n` elements behind the last one.
+    ///
+    /// One at a time, in order: when the free gap is smaller than `n` the destination wraps
+    /// into slots whose elements were already moved.
+    unsafe fn rotate_left_inner(&mut self, n: usize) {
+        for i in 0..n {
+            ptr::copy(self.slot(i), self.slot(self.len + i), 1);
+        }
+        self.head = self.to_physical(n);
+    }
+
+    /// Moves the last `n` elements before the first one, see `rotate_left_inner`.
+    unsafe fn rotate_right_inner(&mut self, n: usize) {
+        let cap = self.capacity();
+        for k in 1..=n {
+            ptr::copy(self.slot(self.len - k), self.slot(cap - k), 1);
+        }
+        self.head = self.to_physical(cap - n);
+    }
+}
+
+impl<T, A: Allocator> Drop for VecDeque<T, A> {
+    fn drop(&mut self) {
+        // The buffer itself is freed by RawVec
+        self.truncate(0);
+    }
+}
+
+impl<T, A: Allocator> Index<usize> for VecDeque<T, A> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T, A: Allocator> IndexMut<usize> for VecDeque<T, A> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
+
+impl<T> Default for VecDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for VecDeque<T, A> {
+    fn clone(&self) -> Self {
+        let mut deque = Self::with_capacity_in(self.len, self.allocator().clone());
+        deque.extend(self.iter().cloned());
+        deque
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for VecDeque<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, U, A1, A2> PartialEq<VecDeque<U, A2>> for VecDeque<T, A1>
+where
+    T: PartialEq<U>,
+    A1: Allocator,
+    A2: Allocator,
+{
+    fn eq(&self, other: &VecDeque<U, A2>) -> bool {
+        self.len == other.len && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator> PartialEq<[U]> for VecDeque<T, A> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.len == other.len() && self.iter().zip(other).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, const N: usize> PartialEq<[U; N]> for VecDeque<T, A> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for VecDeque<T, A> {}
+
+/// Takes over the buffer of the vector without moving any elements.
+impl<T, A: Allocator> From<Vector<T, A>> for VecDeque<T, A> {
+    fn from(v: Vector<T, A>) -> Self {
+        let (buf, len) = v.into_raw_vec();
+        Self { buf, head: 0, len }
+    }
+}
+
+/// Reuses the buffer of the deque after making it contiguous and moving it to the start.
+impl<T, A: Allocator> From<VecDeque<T, A>> for Vector<T, A> {
+    fn from(mut deque: VecDeque<T, A>) -> Self {
+        deque.make_contiguous();
+
+        let deque = ManuallyDrop::new(deque);
+        let buf = unsafe { ptr::read(&deque.buf) };
+        unsafe {
+            ptr::copy(buf.ptr().add(deque.head), buf.ptr(), deque.len);
+            Vector::from_raw_vec(buf, deque.len)
+        }
+    }
+}
+
+/// A deque of `0..cap` with the ring rotated so it wraps after `split` elements.
+#[cfg(test)]
+pub(crate) fn wrapped(cap: usize, split: usize) -> VecDeque<usize> {
+    let mut deque = VecDeque::with_capacity(cap);
+    (0..cap - split).for_each(|_| deque.push_back(usize::MAX));
+    (0..cap - split).for_each(|_| {
+        deque.pop_front();
+    });
+    (0..cap).for_each(|i| deque.push_back(i));
+
+    assert_eq!(deque.capacity(), cap);
+    assert_eq!(deque.as_slices().0.len(), split);
+    deque
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        panic::{self, AssertUnwindSafe},
+        rc::Rc,
+    };
+
+    use super::*;
+    use crate::vector;
+
+    #[test]
+    fn push_and_pop_both_ends() {
+        let mut deque = VecDeque::new();
+        deque.push_back("A Clash of Kings");
+        deque.push_back("A Storm of Swords");
+        deque.push_front("A Game of Thrones");
+
+        assert_eq!(deque.len(), 3);
+        assert_eq!(deque.front(), Some(&"A Game of Thrones"));
+        assert_eq!(deque.back(), Some(&"A Storm of Swords"));
+        assert_eq!(deque[1], "A Clash of Kings");
+
+        assert_eq!(deque.pop_back(), Some("A Storm of Swords"));
+        assert_eq!(deque.pop_front(), Some("A Game of Thrones"));
+        assert_eq!(deque.pop_front(), Some("A Clash of Kings"));
+        assert_eq!(deque.pop_front(), None);
+        assert_eq!(deque.pop_back(), None);
+        assert_eq!(deque.back(), None);
+    }
+
+    #[test]
+    fn work_queue() {
+        let mut deque = VecDeque::new();
+        let mut next = 0;
+
+        for round in 0..100 {
+            (0..3).for_each(|_| deque.push_back(round));
+            (0..2).for_each(|_| {
+                assert!(deque.pop_front().unwrap() <= round);
+                next += 1;
+            });
+        }
+
+        assert_eq!(deque.len(), 100);
+        assert!(deque.capacity() < 256);
+        assert_eq!(deque.iter().copied().max(), Some(99));
+    }
+
+    #[test]
+    fn grows_while_wrapped() {
+        // Front part shorter than the back part, so it moves to the new end
+        let mut deque = wrapped(8, 2);
+        deque.push_back(8);
+        assert!(deque.iter().copied().eq(0..9));
+
+        // Back part shorter, so it moves behind the front part
+        let mut deque = wrapped(8, 6);
+        deque.push_front(usize::MAX);
+        assert_eq!(deque.pop_front(), Some(usize::MAX));
+        assert!(deque.iter().copied().eq(0..8));
+    }
+
+    #[test]
+    fn as_slices_wrap() {
+        let deque = wrapped(8, 3);
+        assert_eq!(deque.as_slices(), (&[0, 1, 2][..], &[3, 4, 5, 6, 7][..]));
+        assert_eq!(deque, [0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn make_contiguous() {
+        for split in 1..8 {
+            let mut deque = wrapped(8, split);
+            assert_eq!(deque.make_contiguous(), [0, 1, 2, 3, 4, 5, 6, 7]);
+            assert_eq!(deque.as_slices().1, []);
+        }
+
+        let mut deque = wrapped(8, 5);
+        deque.truncate(6);
+        deque.pop_front();
+        assert_eq!(deque.make_contiguous(), [1, 2, 3, 4, 5]);
+        deque.push_back(6);
+        assert_eq!(deque, [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn rotate() {
+        let mut deque: VecDeque<_> = (0..10).collect();
+        deque.rotate_left(3);
+        assert_eq!(deque, [3, 4, 5, 6, 7, 8, 9, 0, 1, 2]);
+        deque.rotate_right(3);
+        assert!(deque.iter().copied().eq(0..10));
+
+        deque.rotate_left(8);
+        assert_eq!(deque, [8, 9, 0, 1, 2, 3, 4, 5, 6, 7]);
+        deque.rotate_right(0);
+        deque.rotate_left(10);
+        assert_eq!(deque, [8, 9, 0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn rotate_matches_slice() {
+        for cap in [8, 9] {
+            for split in 1..8 {
+                for n in 0..=8 {
+                    let mut deque = wrapped(8, split);
+                    deque.truncate(cap - 1);
+                    let mut expected: Vec<usize> = deque.iter().copied().collect();
+                    let n = n.min(expected.len());
+
+                    deque.rotate_left(n);
+                    expected.rotate_left(n);
+                    assert_eq!(deque, expected[..]);
+
+                    deque.rotate_right(n);
+                    expected.rotate_right(n);
+                    assert_eq!(deque, expected[..]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "rotation (is 4) should be <= len (is 3)")]
+    fn rotate_too_far() {
+        let mut deque: VecDeque<_> = (0..3).collect();
+        deque.rotate_left(4);
+    }
+
+    #[test]
+    fn truncate_wrapped() {
+        let book = Rc::new("A Feast for Crows");
+        let mut deque = VecDeque::with_capacity(4);
+        (0..3).for_each(|_| deque.push_back(Rc::clone(&book)));
+        deque.pop_front();
+        (0..2).for_each(|_| deque.push_back(Rc::clone(&book)));
+        assert_eq!(Rc::strong_count(&book), 5);
+
+        deque.truncate(1);
+        assert_eq!(Rc::strong_count(&book), 2);
+        drop(deque);
+        assert_eq!(Rc::strong_count(&book), 1);
+    }
+
+    #[test]
+    fn truncate_panicking_destructor() {
+        struct PanicOnDrop<'a>(usize, &'a Cell<usize>);
+
+        impl Drop for PanicOnDrop<'_> {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+                assert!(self.0 != 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut deque = VecDeque::with_capacity(4);
+        (0..4).for_each(|i| deque.push_front(PanicOnDrop(3 - i, &drops)));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| deque.truncate(0)));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 4);
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn index_and_swap() {
+        let mut deque = wrapped(6, 2);
+        deque[0] = 10;
+        deque.swap(1, 4);
+        *deque.back_mut().unwrap() += 10;
+        assert_eq!(deque, [10, 4, 2, 3, 1, 15]);
+        assert_eq!(deque.get(6), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Out of bounds access")]
+    fn index_out_of_bounds() {
+        let deque: VecDeque<u8> = VecDeque::new();
+        let _ = deque[0];
+    }
+
+    #[test]
+    fn zst() {
+        let mut deque = VecDeque::new();
+        assert_eq!(deque.capacity(), usize::MAX);
+
+        (0..10).for_each(|_| deque.push_front(()));
+        (0..10).for_each(|_| deque.push_back(()));
+        deque.rotate_left(3);
+        assert_eq!(deque.len(), 20);
+        assert_eq!(deque.iter().count(), 20);
+        assert_eq!(deque.make_contiguous().len(), 20);
+
+        (0..20).for_each(|_| assert_eq!(deque.pop_front(), Some(())));
+        assert_eq!(deque.pop_back(), None);
+    }
+
+    #[test]
+    fn vector_conversions() {
+        let v = vector![1, 2, 3, 4];
+        let mut deque = VecDeque::from(v);
+        deque.rotate_left(1);
+        deque.push_front(0);
+
+        let v = Vector::from(deque);
+        assert_eq!(v, [0, 2, 3, 4, 1]);
+    }
+
+    #[test]
+    fn clone_and_debug() {
+        let deque = wrapped(4, 1);
+        let clone = deque.clone();
+        assert_eq!(clone, deque);
+        assert_eq!(format!("{clone:?}"), "[0, 1, 2, 3]");
+    }
+}
@@ -0,0 +1,127 @@
+use std::mem;
+
+/// Decides how much a [`Vector`](super::Vector) grows when it runs out of room.
+///
+/// Only amortized growth like `push` or `reserve` asks the policy, `reserve_exact` and
+/// `with_capacity` allocate exactly what they are told to.
+///
+/// Any `Fn(capacity, required) -> usize` is a policy too.
+pub trait GrowthPolicy {
+    /// Returns the capacity to grow to from `capacity` when `required` slots are needed.
+    ///
+    /// A result below `required` is raised to `required`.
+    fn grow<T>(&self, capacity: usize, required: usize) -> usize;
+}
+
+/// Doubles the capacity, but skips the tiny ones: the first allocation holds at least 8
+/// one-byte elements, 4 elements of up to 1 KiB, or a single larger one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DefaultGrowth;
+
+impl DefaultGrowth {
+    pub const fn min_capacity<T>() -> usize {
+        match mem::size_of::<T>() {
+            1 => 8,
+            size if size <= 1024 => 4,
+            _ => 1,
+        }
+    }
+}
+
+impl GrowthPolicy for DefaultGrowth {
+    fn grow<T>(&self, capacity: usize, required: usize) -> usize {
+        Doubling
+            .grow::<T>(capacity, required)
+            .max(Self::min_capacity::<T>())
+    }
+}
+
+/// Doubles the capacity, starting from whatever is required.
+///
+/// Wastes up to half of the memory, in exchange for the fewest reallocations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Doubling;
+
+impl GrowthPolicy for Doubling {
+    fn grow<T>(&self, capacity: usize, required: usize) -> usize {
+        required.max(capacity.saturating_mul(2))
+    }
+}
+
+/// Grows the capacity by half, which wastes at most a third of the memory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OneAndHalf;
+
+impl GrowthPolicy for OneAndHalf {
+    fn grow<T>(&self, capacity: usize, required: usize) -> usize {
+        required.max(capacity.saturating_add(capacity / 2))
+    }
+}
+
+/// Grows the capacity by a fixed number of elements.
+///
+/// Wastes little memory, but pushing `n` elements reallocates O(n) times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedIncrement(pub usize);
+
+impl GrowthPolicy for FixedIncrement {
+    fn grow<T>(&self, capacity: usize, required: usize) -> usize {
+        required.max(capacity.saturating_add(self.0))
+    }
+}
+
+impl<F: Fn(usize, usize) -> usize> GrowthPolicy for F {
+    fn grow<T>(&self, capacity: usize, required: usize) -> usize {
+        self(capacity, required)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The capacities `policy` goes through while pushing one element at a time.
+    fn capacities<T, G: GrowthPolicy>(policy: G, pushes: usize) -> Vec<usize> {
+        let mut capacity = 0;
+        let mut seen = Vec::new();
+        for len in 0..pushes {
+            if len == capacity {
+                capacity = policy.grow::<T>(capacity, len + 1).max(len + 1);
+                seen.push(capacity);
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn default_skips_tiny_capacities() {
+        assert_eq!(capacities::<u8, _>(DefaultGrowth, 40), [8, 16, 32, 64]);
+        assert_eq!(capacities::<u64, _>(DefaultGrowth, 40), [4, 8, 16, 32, 64]);
+        assert_eq!(capacities::<[u8; 1024], _>(DefaultGrowth, 5), [4, 8]);
+        assert_eq!(capacities::<[u8; 1025], _>(DefaultGrowth, 5), [1, 2, 4, 8]);
+        // Zero sized types never grow, but the minimum is still well defined
+        assert_eq!(DefaultGrowth::min_capacity::<()>(), 4);
+    }
+
+    #[test]
+    fn sequences() {
+        assert_eq!(capacities::<u64, _>(Doubling, 20), [1, 2, 4, 8, 16, 32]);
+        assert_eq!(
+            capacities::<u64, _>(OneAndHalf, 20),
+            [1, 2, 3, 4, 6, 9, 13, 19, 28]
+        );
+        assert_eq!(capacities::<u64, _>(FixedIncrement(5), 20), [5, 10, 15, 20]);
+        let quadruple = |capacity: usize, _| capacity * 4 + 3;
+        assert_eq!(capacities::<u64, _>(quadruple, 100), [3, 15, 63, 255]);
+    }
+
+    #[test]
+    fn saturates() {
+        assert_eq!(Doubling.grow::<u8>(usize::MAX / 2 + 1, 10), usize::MAX);
+        assert_eq!(OneAndHalf.grow::<u8>(usize::MAX - 1, 10), usize::MAX);
+        assert_eq!(
+            FixedIncrement(10).grow::<u8>(usize::MAX - 1, 10),
+            usize::MAX
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::{fmt, iter::FusedIterator, marker::PhantomData, ops::RangeBounds, ptr, slice};
+
+use super::{len_slice::RawDrain, DefaultGrowth, GrowthPolicy, RawVec, Vector};
+use crate::datastructures::{Allocator, Global};
+
+/// An owning iterator over the elements of a [`Vector`]
+///
+/// Elements that are never yielded are dropped together with the iterator.
+pub struct IntoIter<T, A: Allocator = Global> {
+    buf: RawVec<T, A>,
+    /// The elements not yielded yet, as indices into `buf`
+    start: usize,
+    end: usize,
+}
+
+impl<T, A: Allocator> IntoIter<T, A> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.ptr().add(self.start), self.end - self.start) }
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        let elem = unsafe { self.buf.ptr().add(self.start).read() };
+        self.start += 1;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { self.buf.ptr().add(self.end).read() })
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        // The buffer is freed by RawVec afterwards, even if dropping the remaining elements panics
+        let remaining = ptr::slice_from_raw_parts_mut(
+            unsafe { self.buf.ptr().add(self.start) },
+            self.end - self.start,
+        );
+        self.start = self.end;
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> IntoIterator for Vector<T, A, G> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let (buf, len) = self.into_raw_vec();
+        IntoIter {
+            buf,
+            start: 0,
+            end: len,
+        }
+    }
+}
+
+impl<'v, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'v Vector<T, A, G> {
+    type Item = &'v T;
+    type IntoIter = slice::Iter<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'v, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'v mut Vector<T, A, G> {
+    type Item = &'v mut T;
+    type IntoIter = slice::IterMut<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A draining iterator over a range of a [`Vector`], see [`Vector::drain`]
+pub struct Drain<'v, T, A: Allocator = Global, G: GrowthPolicy = DefaultGrowth> {
+    raw: RawDrain<'v, T>,
+    marker: PhantomData<&'v mut Vector<T, A, G>>,
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Iterator for Drain<'_, T, A, G> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for Drain<'_, T, A, G> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.raw.next_back()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> ExactSizeIterator for Drain<'_, T, A, G> {}
+impl<T, A: Allocator, G: GrowthPolicy> FusedIterator for Drain<'_, T, A, G> {}
+
+impl<T, A: Allocator, G: GrowthPolicy> Vector<T, A, G> {
+    /// Removes `range` from the vector, yielding the removed elements.
+    ///
+    /// Elements that are not consumed are dropped with the iterator. If the iterator is leaked,
+    /// the vector is left truncated at the start of `range`.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A, G>
+    where
+        R: RangeBounds<usize>,
+    {
+        Drain {
+            raw: self.raw().drain(range),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, G: GrowthPolicy + Default> FromIterator<T> for Vector<T, Global, G> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Vector::with_growth(G::default());
+        v.extend(iter);
+        v
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Extend<T> for Vector<T, A, G> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|elem| self.push(elem));
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator, G: GrowthPolicy> Extend<&'a T> for Vector<T, A, G> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        cell::Cell,
+        panic::{self, AssertUnwindSafe},
+        rc::Rc,
+    };
+
+    use super::*;
+    use crate::vector;
+
+    const BOOKS: &[&str] = &[
+        "A Game of Thrones",
+        "A Clash of Kings",
+        "A Storm of Swords",
+        "A Feast for Crows",
+        "A Dance with Dragons",
+    ];
+
+    fn books() -> Vector<String> {
+        BOOKS.iter().map(|b| b.to_string()).collect()
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut iter = books().into_iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next().as_deref(), Some(BOOKS[0]));
+        assert_eq!(iter.next_back().as_deref(), Some(BOOKS[4]));
+        assert_eq!(iter.as_slice(), &BOOKS[1..4]);
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.collect::<Vector<_>>(), BOOKS[1..4]);
+    }
+
+    #[test]
+    fn into_iter_drops_rest() {
+        let book = Rc::new("A Dance With Dragons");
+        let v: Vector<_> = (0..10).map(|_| Rc::clone(&book)).collect();
+
+        let mut iter = v.into_iter();
+        iter.next();
+        assert_eq!(Rc::strong_count(&book), 10);
+
+        drop(iter);
+        assert_eq!(Rc::strong_count(&book), 1);
+    }
+
+    #[test]
+    fn into_iter_zst() {
+        let v = vector![(); 5];
+        let mut iter = v.into_iter();
+        assert_eq!(iter.next_back(), Some(()));
+        assert_eq!(iter.count(), 4);
+    }
+
+    #[test]
+    fn borrowing_iterators() {
+        let mut v = vector![1, 2, 3];
+        for n in &mut v {
+            *n *= 2;
+        }
+        assert_eq!((&v).into_iter().sum::<i32>(), 12);
+    }
+
+    #[test]
+    fn drain() {
+        let mut v = books();
+        let drained: Vector<String> = v.drain(1..3).collect();
+
+        assert_eq!(drained, BOOKS[1..3]);
+        assert_eq!(v, [BOOKS[0], BOOKS[3], BOOKS[4]]);
+
+        let mut drain = v.drain(..);
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next_back().as_deref(), Some(BOOKS[4]));
+        drop(drain);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn drain_unconsumed() {
+        let mut v: Vector<u32> = (0..10).collect();
+        let mut drain = v.drain(2..8);
+        assert_eq!(drain.next(), Some(2));
+        drop(drain);
+
+        assert_eq!(v, [0, 1, 8, 9]);
+    }
+
+    #[test]
+    fn drain_leaked() {
+        let mut v: Vector<u32> = (0..10).collect();
+        std::mem::forget(v.drain(2..8));
+
+        assert_eq!(v, [0, 1]);
+    }
+
+    #[test]
+    fn drain_panicking_destructor() {
+        struct PanicOnDrop<'a>(u32, &'a Cell<usize>);
+
+        impl Drop for PanicOnDrop<'_> {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+                assert!(self.0 != 3);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut v: Vector<_> = (0..6).map(|i| PanicOnDrop(i, &drops)).collect();
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| drop(v.drain(1..5))));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 4);
+        assert_eq!(v.iter().map(|p| p.0).collect::<Vector<_>>(), [0, 5]);
+
+        drop(v);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn extend() {
+        let mut v = vector![1, 2];
+        v.extend(3..5);
+        v.extend(&[5, 6]);
+        assert_eq!(v, [1, 2, 3, 4, 5, 6]);
+    }
+}
@@ -0,0 +1,389 @@
+use std::{
+    ops::{Range, RangeBounds},
+    ptr,
+};
+
+use super::vector::slice_range;
+
+/// A buffer of `cap` slots whose first `*len` are initialized, borrowed together with the
+/// length of the container that owns it.
+///
+/// [`Vector`](super::Vector), [`SmallVec`](crate::datastructures::SmallVec) and
+/// [`ArrayVec`](crate::datastructures::ArrayVec) only differ in where their elements live and
+/// how they make room for more, the editing itself is done here once. Methods that add elements
+/// expect the caller to have made room first and panic if it didn't.
+pub(crate) struct LenSlice<'a, T> {
+    ptr: *mut T,
+    cap: usize,
+    len: &'a mut usize,
+}
+
+impl<'a, T> LenSlice<'a, T> {
+    /// # Safety
+    ///
+    /// For as long as `'a`, `ptr` must be valid for `cap` slots whose first `*len` are
+    /// initialized and owned by whoever owns `len`.
+    pub(crate) unsafe fn new(ptr: *mut T, cap: usize, len: &'a mut usize) -> Self {
+        Self { ptr, cap, len }
+    }
+
+    fn len(&self) -> usize {
+        *self.len
+    }
+
+    fn assert_room(&self, additional: usize) {
+        assert!(
+            additional <= self.cap - self.len(),
+            "no room for {additional} more elements"
+        );
+    }
+
+    pub(crate) fn push(&mut self, elem: T) {
+        self.assert_room(1);
+        unsafe { self.ptr.add(self.len()).write(elem) };
+        *self.len += 1;
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        if self.len() == 0 {
+            return None;
+        }
+
+        *self.len -= 1;
+        Some(unsafe { self.ptr.add(self.len()).read() })
+    }
+
+    pub(crate) fn insert(&mut self, index: usize, elem: T) {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+        self.assert_room(1);
+
+        unsafe {
+            let p = self.ptr.add(index);
+            ptr::copy(p, p.add(1), len - index);
+            p.write(elem);
+        }
+        *self.len += 1;
+    }
+
+    pub(crate) fn remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(
+            index < len,
+            "removal index (is {index}) should be < len (is {len})"
+        );
+
+        unsafe {
+            let p = self.ptr.add(index);
+            let elem = p.read();
+            ptr::copy(p.add(1), p, len - index - 1);
+            *self.len -= 1;
+            elem
+        }
+    }
+
+    pub(crate) fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(
+            index < len,
+            "swap_remove index (is {index}) should be < len (is {len})"
+        );
+
+        unsafe {
+            let elem = self.ptr.add(index).read();
+            ptr::copy(self.ptr.add(len - 1), self.ptr.add(index), 1);
+            *self.len -= 1;
+            elem
+        }
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        if len >= self.len() {
+            return;
+        }
+
+        let tail = ptr::slice_from_raw_parts_mut(unsafe { self.ptr.add(len) }, self.len() - len);
+        // Shrink first, a panicking destructor must not leave dropped elements in the container
+        *self.len = len;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    pub(crate) fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        /// Closes the gap left by the removed elements, even when `f` or a destructor panics
+        struct Guard<'s, 'a, T> {
+            s: &'s mut LenSlice<'a, T>,
+            processed: usize,
+            deleted: usize,
+            original_len: usize,
+        }
+
+        impl<T> Drop for Guard<'_, '_, T> {
+            fn drop(&mut self) {
+                if self.deleted > 0 {
+                    unsafe {
+                        let p = self.s.ptr;
+                        ptr::copy(
+                            p.add(self.processed),
+                            p.add(self.processed - self.deleted),
+                            self.original_len - self.processed,
+                        );
+                    }
+                }
+                *self.s.len = self.original_len - self.deleted;
+            }
+        }
+
+        let original_len = self.len();
+        // Until the guard is done, the container doesn't own anything
+        *self.len = 0;
+        let p = self.ptr;
+        let mut g = Guard {
+            s: self,
+            processed: 0,
+            deleted: 0,
+            original_len,
+        };
+
+        while g.processed < original_len {
+            let cur = unsafe { p.add(g.processed) };
+
+            if !f(unsafe { &mut *cur }) {
+                g.processed += 1;
+                g.deleted += 1;
+                unsafe { ptr::drop_in_place(cur) };
+            } else {
+                if g.deleted > 0 {
+                    unsafe { ptr::copy_nonoverlapping(cur, p.add(g.processed - g.deleted), 1) };
+                }
+                g.processed += 1;
+            }
+        }
+    }
+
+    pub(crate) fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        /// Moves the unprocessed tail next to the kept elements, even on panic
+        struct FillGap<'s, 'a, T> {
+            s: &'s mut LenSlice<'a, T>,
+            read: usize,
+            write: usize,
+            original_len: usize,
+        }
+
+        impl<T> Drop for FillGap<'_, '_, T> {
+            fn drop(&mut self) {
+                let remaining = self.original_len - self.read;
+                unsafe {
+                    let p = self.s.ptr;
+                    ptr::copy(p.add(self.read), p.add(self.write), remaining);
+                }
+                *self.s.len = self.write + remaining;
+            }
+        }
+
+        let original_len = self.len();
+        if original_len <= 1 {
+            return;
+        }
+
+        *self.len = 0;
+        let p = self.ptr;
+        let mut g = FillGap {
+            s: self,
+            read: 1,
+            write: 1,
+            original_len,
+        };
+
+        while g.read < original_len {
+            unsafe {
+                let read = p.add(g.read);
+                let prev = p.add(g.write - 1);
+
+                if same_bucket(&mut *read, &mut *prev) {
+                    g.read += 1;
+                    ptr::drop_in_place(read);
+                } else {
+                    ptr::copy(read, p.add(g.write), 1);
+                    g.write += 1;
+                    g.read += 1;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        self.assert_room(other.len());
+
+        for elem in other {
+            // One at a time, so a panicking `clone` leaves only initialized elements behind
+            unsafe { self.ptr.add(self.len()).write(elem.clone()) };
+            *self.len += 1;
+        }
+    }
+
+    pub(crate) fn resize_with<F>(&mut self, new_len: usize, mut f: F)
+    where
+        F: FnMut() -> T,
+    {
+        if new_len <= self.len() {
+            self.truncate(new_len);
+            return;
+        }
+
+        self.assert_room(new_len - self.len());
+        while self.len() < new_len {
+            unsafe { self.ptr.add(self.len()).write(f()) };
+            *self.len += 1;
+        }
+    }
+
+    pub(crate) fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        if new_len <= self.len() {
+            self.truncate(new_len);
+            return;
+        }
+
+        self.resize_with(new_len - 1, || value.clone());
+        // The last slot takes `value` itself
+        self.push(value);
+    }
+
+    /// Moves `other[at..]` to the end of `self`, which serves both `append` and `split_off`.
+    pub(crate) fn move_from(&mut self, other: &mut LenSlice<'_, T>, at: usize) {
+        let len = other.len();
+        assert!(
+            at <= len,
+            "`at` split index (is {at}) should be <= len (is {len})"
+        );
+        let count = len - at;
+        self.assert_room(count);
+
+        unsafe { ptr::copy_nonoverlapping(other.ptr.add(at), self.ptr.add(self.len()), count) };
+        *other.len = at;
+        *self.len += count;
+    }
+
+    /// Replaces `range` with all of `replacement`, moving the replaced elements to `removed`.
+    pub(crate) fn splice<R>(
+        &mut self,
+        range: R,
+        replacement: &mut LenSlice<'_, T>,
+        removed: &mut LenSlice<'_, T>,
+    ) where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = slice_range(range, self.len());
+        let (removed_len, added) = (end - start, replacement.len());
+        removed.assert_room(removed_len);
+        self.assert_room(added.saturating_sub(removed_len));
+
+        unsafe {
+            let p = self.ptr;
+            ptr::copy_nonoverlapping(p.add(start), removed.ptr.add(removed.len()), removed_len);
+            ptr::copy(p.add(end), p.add(start + added), self.len() - end);
+            ptr::copy_nonoverlapping(replacement.ptr, p.add(start), added);
+        }
+
+        *self.len = self.len() - removed_len + added;
+        *removed.len += removed_len;
+        *replacement.len = 0;
+    }
+
+    /// Removes `range`, the elements are read out through the returned [`RawDrain`].
+    pub(crate) fn drain<R>(self, range: R) -> RawDrain<'a, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len();
+        let Range { start, end } = slice_range(range, len);
+
+        // A leaked drain leaves the container truncated at `start`
+        *self.len = start;
+
+        RawDrain {
+            ptr: self.ptr,
+            len: self.len,
+            iter: start..end,
+            tail_start: end,
+            tail_len: len - end,
+        }
+    }
+}
+
+/// The iterator behind the `drain` of the contiguous vectors, see [`LenSlice::drain`].
+pub(crate) struct RawDrain<'a, T> {
+    ptr: *mut T,
+    len: &'a mut usize,
+    /// The drained elements not yielded yet
+    iter: Range<usize>,
+    /// The kept elements after the drained range
+    tail_start: usize,
+    tail_len: usize,
+}
+
+// Behaves like the `&mut [T]` it was made from
+unsafe impl<T: Send> Send for RawDrain<'_, T> {}
+unsafe impl<T: Sync> Sync for RawDrain<'_, T> {}
+
+impl<T> Iterator for RawDrain<'_, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|i| unsafe { self.ptr.add(i).read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for RawDrain<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next_back()
+            .map(|i| unsafe { self.ptr.add(i).read() })
+    }
+}
+
+impl<T> Drop for RawDrain<'_, T> {
+    fn drop(&mut self) {
+        /// Moves the tail back in place, even if dropping the remaining elements panics
+        struct MoveTail<'d, 'a, T>(&'d mut RawDrain<'a, T>);
+
+        impl<T> Drop for MoveTail<'_, '_, T> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let start = *drain.len;
+
+                unsafe {
+                    let p = drain.ptr;
+                    ptr::copy(p.add(drain.tail_start), p.add(start), drain.tail_len);
+                }
+                *drain.len = start + drain.tail_len;
+            }
+        }
+
+        let remaining = ptr::slice_from_raw_parts_mut(
+            unsafe { self.ptr.add(self.iter.start) },
+            self.iter.len(),
+        );
+        self.iter = 0..0;
+
+        let _guard = MoveTail(self);
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
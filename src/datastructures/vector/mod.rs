@@ -0,0 +1,29 @@
+pub mod growth;
+pub mod iter;
+pub(crate) mod len_slice;
+pub mod raw_vec;
+#[allow(clippy::module_inception)]
+pub mod vector;
+
+pub use growth::{DefaultGrowth, Doubling, FixedIncrement, GrowthPolicy, OneAndHalf};
+pub use iter::*;
+pub use raw_vec::{RawVec, TryReserveError};
+pub use vector::Vector;
+
+/// Creates a [`Vector`] containing the arguments, like `vec!`.
+///
+/// `vector![elem; n]` clones `elem` to fill `n` slots.
+#[macro_export]
+macro_rules! vector {
+    () => {
+        $crate::datastructures::Vector::new()
+    };
+    ($elem:expr; $n:expr) => {{
+        let mut v = $crate::datastructures::Vector::new();
+        v.resize($n, $elem);
+        v
+    }};
+    ($($x:expr),+ $(,)?) => {
+        <$crate::datastructures::Vector<_> as ::core::iter::FromIterator<_>>::from_iter([$($x),+])
+    };
+}
@@ -0,0 +1,330 @@
+use std::{
+    alloc::{self, Layout},
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    mem,
+    ptr::NonNull,
+};
+
+use crate::datastructures::{Allocator, Global};
+
+fn panic_cap_overflow() -> ! {
+    panic!("Capacity overflow");
+}
+
+/// The error returned by the fallible allocation methods of [`RawVec`] and the containers built on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The capacity would exceed `usize::MAX` or the buffer `isize::MAX` bytes
+    CapacityOverflow,
+    /// The allocator failed to provide memory for `layout`
+    AllocError { layout: Layout },
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CapacityOverflow => write!(f, "capacity overflow"),
+            Self::AllocError { layout } => {
+                write!(f, "memory allocation of {} bytes failed", layout.size())
+            }
+        }
+    }
+}
+
+impl Error for TryReserveError {}
+
+/// Turns a failed infallible reservation into a panic or an abort.
+pub(crate) fn handle_reserve(result: Result<(), TryReserveError>) {
+    match result {
+        Ok(()) => {}
+        Err(TryReserveError::CapacityOverflow) => panic_cap_overflow(),
+        Err(TryReserveError::AllocError { layout }) => alloc::handle_alloc_error(layout),
+    }
+}
+
+/// An owned, possibly uninitialized buffer of `capacity` slots for `T`s.
+///
+/// It only manages the allocation: callers track which slots are initialized and drop those
+/// themselves, dropping a `RawVec` just frees the memory.
+///
+/// Zero sized types never allocate: their buffer is a dangling pointer and the capacity is
+/// `usize::MAX`.
+pub struct RawVec<T, A: Allocator = Global> {
+    // Dangling until the first allocation
+    ptr: NonNull<T>,
+    cap: usize,
+    alloc: A,
+    marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send, A: Allocator + Send> Send for RawVec<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for RawVec<T, A> {}
+
+impl<T> RawVec<T> {
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Allocates room for exactly `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, A: Allocator> RawVec<T, A> {
+    pub const IS_ZST: bool = mem::size_of::<T>() == 0;
+
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            cap: if Self::IS_ZST { usize::MAX } else { 0 },
+            alloc,
+            marker: PhantomData,
+        }
+    }
+
+    /// Allocates room for exactly `capacity` elements from `alloc`.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        let mut buf = Self::new_in(alloc);
+        buf.reserve_exact(0, capacity);
+        buf
+    }
+
+    /// Takes ownership of a buffer.
+    ///
+    /// # Safety
+    ///
+    /// `ptr`, `capacity` and `alloc` must come from a `RawVec<T, A>` that is no longer used.
+    pub unsafe fn from_raw_parts_in(ptr: NonNull<T>, capacity: usize, alloc: A) -> Self {
+        Self {
+            ptr,
+            cap: capacity,
+            alloc,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns a pointer to the first slot, dangling but well aligned if nothing is allocated.
+    pub fn ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Makes room for at least `len + additional` elements, doubling the capacity if that is more.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows, aborts if the allocation fails.
+    pub fn reserve(&mut self, len: usize, additional: usize) {
+        if self.needs_to_grow(len, additional) {
+            handle_reserve(self.grow_amortized(len, additional));
+        }
+    }
+
+    /// Makes room for exactly `len + additional` elements.
+    pub fn reserve_exact(&mut self, len: usize, additional: usize) {
+        if self.needs_to_grow(len, additional) {
+            handle_reserve(self.grow_exact(len, additional));
+        }
+    }
+
+    pub fn try_reserve(&mut self, len: usize, additional: usize) -> Result<(), TryReserveError> {
+        if self.needs_to_grow(len, additional) {
+            self.grow_amortized(len, additional)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn try_reserve_exact(
+        &mut self,
+        len: usize,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        if self.needs_to_grow(len, additional) {
+            self.grow_exact(len, additional)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn needs_to_grow(&self, len: usize, additional: usize) -> bool {
+        additional > self.cap.wrapping_sub(len)
+    }
+
+    /// Grows to at least `len + additional`, doubling the capacity if that is more.
+    pub fn grow_amortized(&mut self, len: usize, additional: usize) -> Result<(), TryReserveError> {
+        let required = Self::required(len, additional)?;
+        let new_cap = required.max(self.cap.saturating_mul(2));
+        self.finish_grow(new_cap)
+    }
+
+    /// Grows to exactly `len + additional`.
+    pub fn grow_exact(&mut self, len: usize, additional: usize) -> Result<(), TryReserveError> {
+        let new_cap = Self::required(len, additional)?;
+        self.finish_grow(new_cap)
+    }
+
+    fn required(len: usize, additional: usize) -> Result<usize, TryReserveError> {
+        // For ZSTs the capacity is already usize::MAX, so any growth is an overflow
+        len.checked_add(additional)
+            .filter(|_| !Self::IS_ZST)
+            .ok_or(TryReserveError::CapacityOverflow)
+    }
+
+    fn finish_grow(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
+        // Fails if the new allocation would exceed isize::MAX bytes
+        let new_layout =
+            Layout::array::<T>(new_cap).map_err(|_| TryReserveError::CapacityOverflow)?;
+
+        let new_ptr = if self.cap == 0 {
+            self.alloc.allocate(new_layout)
+        } else {
+            let old_layout = Layout::array::<T>(self.cap).unwrap();
+            unsafe { self.alloc.grow(self.ptr.cast(), old_layout, new_layout) }
+        };
+
+        // On allocation fail the old buffer is left untouched
+        self.ptr = new_ptr
+            .map_err(|_| TryReserveError::AllocError { layout: new_layout })?
+            .cast();
+        self.cap = new_cap;
+        Ok(())
+    }
+
+    /// Shrinks the buffer to `capacity` slots, anything stored past that is lost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is larger than the current capacity.
+    pub fn shrink(&mut self, capacity: usize) {
+        assert!(capacity <= self.cap, "Tried to shrink to a larger capacity");
+        if Self::IS_ZST || capacity == self.cap {
+            return;
+        }
+
+        let old_layout = Layout::array::<T>(self.cap).unwrap();
+
+        if capacity == 0 {
+            unsafe { self.alloc.deallocate(self.ptr.cast(), old_layout) };
+            self.ptr = NonNull::dangling();
+        } else {
+            // Smaller than the current layout, so it can't overflow
+            let new_layout = Layout::array::<T>(capacity).unwrap();
+            let new_ptr = unsafe { self.alloc.shrink(self.ptr.cast(), old_layout, new_layout) };
+            self.ptr = match new_ptr {
+                Ok(ptr) => ptr.cast(),
+                Err(_) => alloc::handle_alloc_error(new_layout),
+            };
+        }
+        self.cap = capacity;
+    }
+}
+
+impl<T> Default for RawVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, A: Allocator> Drop for RawVec<T, A> {
+    fn drop(&mut self) {
+        if !Self::IS_ZST && self.cap != 0 {
+            let layout = Layout::array::<T>(self.cap).unwrap();
+            unsafe { self.alloc.deallocate(self.ptr.cast(), layout) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::datastructures::CountingAllocator;
+
+    #[test]
+    fn grow_amortized() {
+        let mut buf = RawVec::<u32>::new();
+        assert_eq!(buf.capacity(), 0);
+
+        buf.grow_amortized(0, 1).unwrap();
+        assert_eq!(buf.capacity(), 1);
+        buf.grow_amortized(1, 1).unwrap();
+        assert_eq!(buf.capacity(), 2);
+        buf.grow_amortized(2, 10).unwrap();
+        assert_eq!(buf.capacity(), 12);
+    }
+
+    #[test]
+    fn grow_exact_and_shrink() {
+        let mut buf = RawVec::<String>::with_capacity(4);
+        assert_eq!(buf.capacity(), 4);
+
+        buf.grow_exact(4, 1).unwrap();
+        assert_eq!(buf.capacity(), 5);
+
+        unsafe { buf.ptr().write(String::from("A Feast for Crows")) };
+        buf.shrink(1);
+        assert_eq!(buf.capacity(), 1);
+        assert_eq!(unsafe { buf.ptr().read() }, "A Feast for Crows");
+
+        buf.shrink(0);
+        assert_eq!(buf.capacity(), 0);
+        assert_eq!(buf.ptr(), NonNull::dangling().as_ptr());
+    }
+
+    #[test]
+    fn reserve_keeps_contents() {
+        let mut buf = RawVec::<u64>::new();
+        for i in 0..100 {
+            buf.reserve(i, 1);
+            unsafe { buf.ptr().add(i).write(i as u64) };
+        }
+
+        let contents = unsafe { std::slice::from_raw_parts(buf.ptr(), 100) };
+        assert!(contents.iter().copied().eq(0..100));
+        assert_eq!(buf.capacity(), 128);
+    }
+
+    #[test]
+    fn zst() {
+        let mut buf = RawVec::<()>::new();
+        assert_eq!(buf.capacity(), usize::MAX);
+        assert_eq!(buf.try_reserve(10, 1), Ok(()));
+        assert_eq!(
+            buf.grow_exact(usize::MAX, 1),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        buf.shrink(0);
+        assert_eq!(buf.capacity(), usize::MAX);
+    }
+
+    #[test]
+    fn allocator() {
+        let counter = CountingAllocator::new();
+        {
+            let mut buf = RawVec::<u32, _>::with_capacity_in(4, &counter);
+            assert_eq!(counter.live_bytes(), 16);
+            buf.grow_exact(4, 4).unwrap();
+            assert_eq!(counter.live_bytes(), 32);
+            buf.shrink(2);
+            assert_eq!(counter.live_bytes(), 8);
+        }
+        assert_eq!(counter.live_bytes(), 0);
+        assert_eq!(counter.peak_bytes(), 32);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to shrink to a larger capacity")]
+    fn shrink_larger() {
+        RawVec::<u8>::with_capacity(2).shrink(3);
+    }
+}
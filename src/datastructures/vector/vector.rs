@@ -0,0 +1,1179 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    mem::ManuallyDrop,
+    ops::{Bound, Deref, DerefMut, Index, IndexMut, Range, RangeBounds},
+    ptr,
+    slice::{self, SliceIndex},
+};
+
+use super::{
+    len_slice::LenSlice, raw_vec::handle_reserve, DefaultGrowth, GrowthPolicy, RawVec,
+    TryReserveError,
+};
+use crate::datastructures::{Allocator, Global};
+
+/// Resolves `range` against a slice of length `len`, panicking like slice indexing would.
+pub(crate) fn slice_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start
+            .checked_add(1)
+            .expect("attempted to index slice from after maximum usize"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end
+            .checked_add(1)
+            .expect("attempted to index slice up to maximum usize"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    assert!(
+        start <= end,
+        "slice index starts at {start} but ends at {end}"
+    );
+    assert!(
+        end <= len,
+        "range end index {end} out of range for slice of length {len}"
+    );
+
+    start..end
+}
+
+/// A growable array.
+///
+/// The allocation is managed by a [`RawVec`], the vector only tracks how many of its slots
+/// are initialized. Zero sized types never allocate and have a capacity of `usize::MAX`.
+///
+/// Memory comes from the [`Allocator`] `A`, the global allocator by default. How much the
+/// capacity grows when it runs out is up to the [`GrowthPolicy`] `G`.
+pub struct Vector<T, A: Allocator = Global, G: GrowthPolicy = DefaultGrowth> {
+    buf: RawVec<T, A>,
+    len: usize,
+    growth: G,
+}
+
+impl<T> Vector<T> {
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Creates an empty vector with room for exactly `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, G: GrowthPolicy> Vector<T, Global, G> {
+    /// Creates an empty vector that grows according to `growth`.
+    pub fn with_growth(growth: G) -> Self {
+        Self::with_growth_in(growth, Global)
+    }
+}
+
+impl<T, A: Allocator> Vector<T, A> {
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_growth_in(DefaultGrowth, alloc)
+    }
+
+    /// Creates an empty vector with room for exactly `capacity` elements from `alloc`.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            buf: RawVec::with_capacity_in(capacity, alloc),
+            len: 0,
+            growth: DefaultGrowth,
+        }
+    }
+
+    /// Builds a vector on top of `buf`.
+    ///
+    /// # Safety
+    ///
+    /// The first `len` slots of `buf` must be initialized.
+    pub unsafe fn from_raw_vec(buf: RawVec<T, A>, len: usize) -> Self {
+        Self {
+            buf,
+            len,
+            growth: DefaultGrowth,
+        }
+    }
+}
+
+#[allow(unused)]
+impl<T, A: Allocator, G: GrowthPolicy> Vector<T, A, G> {
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Creates an empty vector that allocates from `alloc` and grows according to `growth`.
+    pub fn with_growth_in(growth: G, alloc: A) -> Self {
+        Self {
+            buf: RawVec::new_in(alloc),
+            len: 0,
+            growth,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.buf.allocator()
+    }
+
+    pub fn growth(&self) -> &G {
+        &self.growth
+    }
+
+    /// Returns a pointer to the buffer, dangling but well aligned if nothing is allocated yet.
+    pub fn as_ptr(&self) -> *const T {
+        self.buf.ptr()
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.buf.ptr()
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must not exceed the capacity and the first `new_len` elements must be initialized.
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        self.len = new_len;
+    }
+
+    /// Takes the vector apart, the first `len` slots of the returned buffer stay initialized.
+    pub fn into_raw_vec(self) -> (RawVec<T, A>, usize) {
+        let v = ManuallyDrop::new(self);
+        drop(unsafe { ptr::read(&v.growth) });
+        (unsafe { ptr::read(&v.buf) }, v.len)
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+
+    pub fn push(&mut self, elem: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+        self.raw().push(elem);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.raw().pop()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            let elem = unsafe { &*self.buf.ptr().add(self.len - 1) };
+            Some(elem)
+        }
+    }
+
+    /// Inserts `elem` at `index`, shifting everything after it to the right.
+    pub fn insert(&mut self, index: usize, elem: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+        self.raw().insert(index, elem);
+    }
+
+    /// Removes the element at `index`, shifting everything after it to the left.
+    pub fn remove(&mut self, index: usize) -> T {
+        self.raw().remove(index)
+    }
+
+    /// Removes the element at `index` in O(1) by moving the last element into its place.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        self.raw().swap_remove(index)
+    }
+
+    /// Drops every element past the first `len`, keeping the capacity.
+    pub fn truncate(&mut self, len: usize) {
+        self.raw().truncate(len);
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    /// Keeps only the elements `f` returns `true` for, in their original order.
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        self.raw().retain_mut(f);
+    }
+
+    /// Removes consecutive elements that map to the same key.
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Removes consecutive repeated elements.
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    /// Removes consecutive elements for which `same_bucket(elem, previous_kept)` returns `true`.
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        self.raw().dedup_by(same_bucket);
+    }
+
+    /// Moves every element of `other` to the end of `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        self.reserve(other.len);
+        self.raw().move_from(&mut other.raw(), 0);
+    }
+
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(other.len());
+        self.raw().extend_from_slice(other);
+    }
+
+    /// Resizes the vector to `new_len`, filling new slots with the results of `f`.
+    pub fn resize_with<F>(&mut self, new_len: usize, f: F)
+    where
+        F: FnMut() -> T,
+    {
+        self.reserve(new_len.saturating_sub(self.len));
+        self.raw().resize_with(new_len, f);
+    }
+
+    /// Resizes the vector to `new_len`, filling new slots with clones of `value`.
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        self.reserve(new_len.saturating_sub(self.len));
+        self.raw().resize(new_len, value);
+    }
+
+    /// Makes room for at least `additional` more elements, as much more as the growth policy
+    /// asks for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows, aborts if the allocation fails.
+    pub fn reserve(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve(additional));
+    }
+
+    /// Makes room for exactly `additional` more elements.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.buf.reserve_exact(self.len, additional);
+    }
+
+    /// Like [`reserve`](Vector::reserve), but returns an error instead of panicking or aborting.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if additional <= self.capacity() - self.len {
+            return Ok(());
+        }
+
+        let required = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        let new_cap = self
+            .growth
+            .grow::<T>(self.capacity(), required)
+            .max(required);
+        self.buf.try_reserve_exact(self.len, new_cap - self.len)
+    }
+
+    /// Like [`reserve_exact`](Vector::reserve_exact), but returns an error instead of panicking
+    /// or aborting.
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.buf.try_reserve_exact(self.len, additional)
+    }
+
+    /// Like [`push`](Vector::push), but returns an error if the vector can't grow.
+    /// `elem` is dropped in that case.
+    pub fn try_push(&mut self, elem: T) -> Result<(), TryReserveError> {
+        self.try_reserve(1)?;
+        self.push(elem);
+        Ok(())
+    }
+
+    /// Shrinks the capacity as close to the length as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(self.len);
+    }
+
+    /// Shrinks the capacity to `max(min_capacity, len)`, if it is currently larger.
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        let new_cap = min_capacity.max(self.len);
+        if new_cap < self.capacity() {
+            self.buf.shrink(new_cap);
+        }
+    }
+
+    /// The buffer and length, for the editing shared with the other contiguous vectors.
+    pub(super) fn raw(&mut self) -> LenSlice<'_, T> {
+        unsafe { LenSlice::new(self.buf.ptr(), self.buf.capacity(), &mut self.len) }
+    }
+}
+
+impl<T, A: Allocator + Clone, G: GrowthPolicy + Clone> Vector<T, A, G> {
+    /// Splits the vector in two at `at`, returning the elements from `at` onwards.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let mut other = self.empty_clone();
+        other.reserve_exact(self.len.saturating_sub(at));
+        other.raw().move_from(&mut self.raw(), at);
+        other
+    }
+
+    /// Replaces `range` with the items of `replace_with`, returning the removed elements.
+    ///
+    /// The replacement is collected before the vector is touched, so a panicking iterator
+    /// leaves it unchanged.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Self
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let range = slice_range(range, self.len);
+
+        let mut replacement = self.empty_clone();
+        replace_with
+            .into_iter()
+            .for_each(|elem| replacement.push(elem));
+
+        let mut removed = self.empty_clone();
+        removed.reserve_exact(range.len());
+        self.reserve(replacement.len.saturating_sub(range.len()));
+        self.raw()
+            .splice(range, &mut replacement.raw(), &mut removed.raw());
+
+        removed
+    }
+
+    /// Creates an empty vector with the same allocator and growth policy.
+    fn empty_clone(&self) -> Self {
+        Self::with_growth_in(self.growth.clone(), self.allocator().clone())
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for Vector<T, A, G> {
+    fn drop(&mut self) {
+        // The buffer itself is freed by RawVec
+        unsafe {
+            ptr::drop_in_place(self.as_mut_slice());
+        }
+    }
+}
+
+/// Slice methods like `iter`, `get`, `sort` or `binary_search` all come through here.
+impl<T, A: Allocator, G: GrowthPolicy> Deref for Vector<T, A, G> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> DerefMut for Vector<T, A, G> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, A: Allocator, G: GrowthPolicy> Index<I> for Vector<T, A, G> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        Index::index(&**self, index)
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, A: Allocator, G: GrowthPolicy> IndexMut<I> for Vector<T, A, G> {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        IndexMut::index_mut(&mut **self, index)
+    }
+}
+
+impl<T, G: GrowthPolicy + Default> Default for Vector<T, Global, G> {
+    fn default() -> Self {
+        Self::with_growth(G::default())
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, G: GrowthPolicy + Clone> Clone for Vector<T, A, G> {
+    fn clone(&self) -> Self {
+        let mut v = self.empty_clone();
+        v.reserve_exact(self.len);
+        v.extend_from_slice(self);
+        v
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for Vector<T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T, U, A1, A2, G1, G2> PartialEq<Vector<U, A2, G2>> for Vector<T, A1, G1>
+where
+    T: PartialEq<U>,
+    A1: Allocator,
+    A2: Allocator,
+    G1: GrowthPolicy,
+    G2: GrowthPolicy,
+{
+    fn eq(&self, other: &Vector<U, A2, G2>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, G: GrowthPolicy> PartialEq<[U]> for Vector<T, A, G> {
+    fn eq(&self, other: &[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, G: GrowthPolicy> PartialEq<&[U]> for Vector<T, A, G> {
+    fn eq(&self, other: &&[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, G: GrowthPolicy, const N: usize> PartialEq<[U; N]>
+    for Vector<T, A, G>
+{
+    fn eq(&self, other: &[U; N]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, G: GrowthPolicy, const N: usize> PartialEq<&[U; N]>
+    for Vector<T, A, G>
+{
+    fn eq(&self, other: &&[U; N]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for Vector<T, A, G> {}
+
+/// Hashes exactly like the equivalent slice.
+impl<T: Hash, A: Allocator, G: GrowthPolicy> Hash for Vector<T, A, G> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T: PartialOrd, A: Allocator, G: GrowthPolicy> PartialOrd for Vector<T, A, G> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> Ord for Vector<T, A, G> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        panic::{self, AssertUnwindSafe},
+        ptr::NonNull,
+        rc::Rc,
+    };
+
+    use super::*;
+    use crate::datastructures::{
+        vector::{Doubling, FixedIncrement, OneAndHalf},
+        CountingAllocator,
+    };
+
+    #[test]
+    fn empty_and_push_1() {
+        let mut v = Vector::new();
+
+        v.push("Something");
+
+        // The default growth policy skips capacities below 4
+        assert_eq!(v.capacity(), 4);
+        assert_eq!(v.len(), 1);
+    }
+
+    #[test]
+    fn push_10() {
+        let mut v = Vector::new();
+        let fav_books = "A Dance With Dragons A Feast For Crows Two Words";
+
+        fav_books.split(" ").for_each(|s| v.push(s));
+
+        assert_eq!(v.len(), 10);
+        assert_eq!(v.capacity(), 16);
+    }
+
+    #[test]
+    fn push_10_pop_11() {
+        let mut v = Vector::new();
+        let fav_books = "A Dance With Dragons A Feast For Crows Two Words";
+
+        fav_books.split(" ").for_each(|s| v.push(s));
+
+        assert_eq!(v.len(), 10);
+        assert_eq!(v.capacity(), 16);
+
+        fav_books
+            .split(" ")
+            .collect::<Vec<&str>>()
+            .iter()
+            .rev()
+            .for_each(|s| assert_eq!(v.pop(), Some(*s)));
+
+        assert_eq!(v.pop(), None);
+    }
+
+    #[test]
+    fn push_10_pop_and_peek_11() {
+        let mut v = Vector::new();
+        let fav_books = "A Dance With Dragons A Feast For Crows Two Words";
+
+        fav_books.split(" ").for_each(|s| v.push(s));
+
+        assert_eq!(v.len(), 10);
+        assert_eq!(v.capacity(), 16);
+
+        fav_books
+            .split(" ")
+            .collect::<Vec<&str>>()
+            .iter()
+            .rev()
+            .for_each(|s| {
+                assert_eq!(v.peek(), Some(s));
+                assert_eq!(v.pop(), Some(*s));
+            });
+
+        assert_eq!(v.peek(), None);
+        assert_eq!(v.pop(), None);
+    }
+
+    #[test]
+    fn drops_elements() {
+        let book = Rc::new("A Dance With Dragons");
+        let mut v = Vector::new();
+        (0..10).for_each(|_| v.push(Rc::clone(&book)));
+
+        assert_eq!(Rc::strong_count(&book), 11);
+        drop(v);
+        assert_eq!(Rc::strong_count(&book), 1);
+    }
+
+    #[test]
+    fn slice_methods() {
+        let mut v = Vector::new();
+        [5, 2, 4, 1, 3].into_iter().for_each(|n| v.push(n));
+
+        assert_eq!(v.as_slice(), [5, 2, 4, 1, 3]);
+        assert_eq!(v.iter().sum::<i32>(), 15);
+        assert_eq!(v.get(4), Some(&3));
+        assert_eq!(v.get(5), None);
+
+        v.sort();
+        assert_eq!(v.as_slice(), [1, 2, 3, 4, 5]);
+        assert_eq!(v.binary_search(&4), Ok(3));
+        assert_eq!(v.binary_search(&6), Err(5));
+
+        *v.get_mut(0).unwrap() = 10;
+        v.iter_mut().for_each(|n| *n *= 2);
+        assert_eq!(v.as_slice(), [20, 4, 6, 8, 10]);
+    }
+
+    #[test]
+    fn index_ranges() {
+        let mut v = Vector::new();
+        (0..10).for_each(|n| v.push(n));
+
+        assert_eq!(v[3], 3);
+        assert_eq!(v[2..5], [2, 3, 4]);
+        assert_eq!(v[..2], [0, 1]);
+        assert_eq!(v[8..], [8, 9]);
+        assert_eq!(v[..], *v.as_slice());
+
+        v[1] = 100;
+        v[5..].fill(0);
+        assert_eq!(v[..], [0, 100, 2, 3, 4, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn empty_slice() {
+        let v = Vector::<u64>::new();
+        assert!(v.as_slice().is_empty());
+        assert_eq!(v.as_ptr(), NonNull::dangling().as_ptr());
+        assert_eq!(v.first(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let mut v = Vector::new();
+        v.push(1);
+        let _ = v[1];
+    }
+
+    thread_local! {
+        static DROPS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// A zero sized type that counts how many times it was dropped on this thread
+    #[derive(Debug, PartialEq)]
+    struct Counted;
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPS.with(|drops| drops.set(drops.get() + 1));
+        }
+    }
+
+    #[test]
+    fn zst_push_pop() {
+        let mut v = Vector::new();
+        assert_eq!(v.capacity(), usize::MAX);
+
+        (0..1000).for_each(|_| v.push(()));
+        assert_eq!(v.len(), 1000);
+        assert_eq!(v.capacity(), usize::MAX);
+        assert_eq!(v.iter().count(), 1000);
+        assert_eq!(v[999], ());
+        assert_eq!(v.peek(), Some(&()));
+
+        (0..1000).for_each(|_| assert_eq!(v.pop(), Some(())));
+        assert_eq!(v.pop(), None);
+        assert_eq!(v.peek(), None);
+    }
+
+    #[test]
+    fn zst_drop_count() {
+        let mut v = Vector::new();
+        (0..10).for_each(|_| v.push(Counted));
+
+        drop(v.pop());
+        assert_eq!(DROPS.get(), 1);
+
+        drop(v);
+        assert_eq!(DROPS.get(), 10);
+    }
+
+    #[test]
+    fn zst_iter_mut() {
+        let mut v = Vector::new();
+        (0..5).for_each(|_| v.push(Counted));
+
+        assert_eq!(v.iter_mut().count(), 5);
+        assert_eq!(v[1..4].len(), 3);
+        assert_eq!(v.as_ptr(), NonNull::dangling().as_ptr());
+    }
+
+    fn vector<T: Clone>(items: &[T]) -> Vector<T> {
+        let mut v = Vector::new();
+        v.extend_from_slice(items);
+        v
+    }
+
+    /// Counts its drops in a shared counter, optionally panicking while being dropped
+    #[derive(Debug)]
+    struct Tracked {
+        value: u32,
+        drops: Rc<Cell<usize>>,
+        panic_on_drop: bool,
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+            if self.panic_on_drop {
+                panic!("Tracked({}) panicked while dropped", self.value);
+            }
+        }
+    }
+
+    fn tracked(values: &[u32], drops: &Rc<Cell<usize>>) -> Vector<Tracked> {
+        let mut v = Vector::new();
+        for &value in values {
+            v.push(Tracked {
+                value,
+                drops: Rc::clone(drops),
+                panic_on_drop: false,
+            });
+        }
+        v
+    }
+
+    fn values(v: &Vector<Tracked>) -> Vec<u32> {
+        v.iter().map(|t| t.value).collect()
+    }
+
+    #[test]
+    fn insert_remove() {
+        let mut v = vector(&["A", "With", "Dragons"]);
+        v.insert(1, "Dance");
+        v.insert(4, "Two");
+        v.insert(0, "Feast");
+
+        assert_eq!(v[..], ["Feast", "A", "Dance", "With", "Dragons", "Two"]);
+        assert_eq!(v.remove(0), "Feast");
+        assert_eq!(v.remove(4), "Two");
+        assert_eq!(v[..], ["A", "Dance", "With", "Dragons"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "insertion index (is 2) should be <= len (is 1)")]
+    fn insert_out_of_bounds() {
+        let mut v = vector(&[1]);
+        v.insert(2, 2);
+    }
+
+    #[test]
+    fn swap_remove() {
+        let mut v = vector(&[0, 1, 2, 3, 4]);
+        assert_eq!(v.swap_remove(1), 1);
+        assert_eq!(v[..], [0, 4, 2, 3]);
+        assert_eq!(v.swap_remove(3), 3);
+        assert_eq!(v[..], [0, 4, 2]);
+    }
+
+    #[test]
+    fn truncate_and_clear() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked(&[0, 1, 2, 3, 4], &drops);
+
+        v.truncate(10);
+        assert_eq!(v.len(), 5);
+        v.truncate(3);
+        assert_eq!(drops.get(), 2);
+        assert_eq!(values(&v), [0, 1, 2]);
+
+        v.clear();
+        assert_eq!(drops.get(), 5);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn truncate_panicking_destructor() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked(&[0, 1, 2, 3, 4], &drops);
+        v[2].panic_on_drop = true;
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| v.truncate(1)));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 4);
+        assert_eq!(values(&v), [0]);
+
+        drop(v);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn retain() {
+        let mut v = vector(&[0, 1, 2, 3, 4, 5, 6]);
+        v.retain(|n| n % 3 != 0);
+        assert_eq!(v[..], [1, 2, 4, 5]);
+
+        v.retain_mut(|n| {
+            *n *= 10;
+            *n > 20
+        });
+        assert_eq!(v[..], [40, 50]);
+    }
+
+    #[test]
+    fn retain_panicking_predicate() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked(&[0, 1, 2, 3, 4, 5], &drops);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            v.retain(|t| {
+                assert!(t.value != 3);
+                t.value % 2 == 0
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 1);
+        assert_eq!(values(&v), [0, 2, 3, 4, 5]);
+
+        drop(v);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn retain_panicking_destructor() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked(&[0, 1, 2, 3, 4, 5], &drops);
+        v[1].panic_on_drop = true;
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| v.retain(|t| t.value % 2 == 0)));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 1);
+        assert_eq!(values(&v), [0, 2, 3, 4, 5]);
+
+        drop(v);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn dedup() {
+        let mut v = vector(&[1, 1, 2, 3, 3, 3, 1, 4, 4]);
+        v.dedup();
+        assert_eq!(v[..], [1, 2, 3, 1, 4]);
+
+        let mut v = vector(&[10, 11, 20, 21, 22, 30, 15]);
+        v.dedup_by_key(|n| *n / 10);
+        assert_eq!(v[..], [10, 20, 30, 15]);
+
+        let mut v = vector(&["a", "A", "b", "B", "B", "c"]);
+        v.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        assert_eq!(v[..], ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn dedup_panicking_key() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked(&[1, 1, 2, 2, 3, 3], &drops);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            v.dedup_by_key(|t| {
+                assert!(t.value != 3);
+                t.value
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+        assert_eq!(values(&v), [1, 2, 3, 3]);
+
+        drop(v);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn split_off_and_append() {
+        let mut v = vector(&[0, 1, 2, 3, 4]);
+        let mut tail = v.split_off(2);
+        assert_eq!(v[..], [0, 1]);
+        assert_eq!(tail[..], [2, 3, 4]);
+
+        tail.append(&mut v);
+        assert_eq!(tail[..], [2, 3, 4, 0, 1]);
+        assert!(v.is_empty());
+
+        assert!(tail.split_off(5).is_empty());
+    }
+
+    #[test]
+    fn resize_with() {
+        let mut v = vector(&[1, 2]);
+        let mut next = 2;
+        v.resize_with(5, || {
+            next += 1;
+            next
+        });
+        assert_eq!(v[..], [1, 2, 3, 4, 5]);
+
+        v.resize_with(1, || unreachable!());
+        assert_eq!(v[..], [1]);
+    }
+
+    #[test]
+    fn splice() {
+        let mut v = vector(&[0, 1, 2, 3, 4]);
+
+        let removed = v.splice(1..3, [10, 20, 30]);
+        assert_eq!(removed[..], [1, 2]);
+        assert_eq!(v[..], [0, 10, 20, 30, 3, 4]);
+
+        let removed = v.splice(..4, [7]);
+        assert_eq!(removed[..], [0, 10, 20, 30]);
+        assert_eq!(v[..], [7, 3, 4]);
+
+        let removed = v.splice(3.., [5, 6]);
+        assert!(removed.is_empty());
+        assert_eq!(v[..], [7, 3, 4, 5, 6]);
+
+        let removed = v.splice(..=1, []);
+        assert_eq!(removed[..], [7, 3]);
+        assert_eq!(v[..], [4, 5, 6]);
+    }
+
+    #[test]
+    fn splice_panicking_iterator() {
+        let drops = Rc::new(Cell::new(0));
+        let mut v = tracked(&[0, 1, 2], &drops);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let replacement = (10..20).map(|value| {
+                assert!(value != 12);
+                Tracked {
+                    value,
+                    drops: Rc::clone(&drops),
+                    panic_on_drop: false,
+                }
+            });
+            v.splice(1..2, replacement);
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+        assert_eq!(values(&v), [0, 1, 2]);
+    }
+
+    #[test]
+    fn resize() {
+        let mut v = vector(&[String::from("A")]);
+        v.resize(3, String::from("Dance"));
+        assert_eq!(v, ["A", "Dance", "Dance"]);
+
+        v.resize(2, String::new());
+        assert_eq!(v, ["A", "Dance"]);
+    }
+
+    #[test]
+    fn clone_and_compare() {
+        let v = vector(&[String::from("A"), String::from("Feast")]);
+        let w = v.clone();
+
+        assert_eq!(v, w);
+        assert_eq!(v, ["A", "Feast"]);
+        assert_eq!(v, &["A", "Feast"]);
+        assert_eq!(v, ["A", "Feast"][..]);
+        assert_eq!(v, &["A", "Feast"][..]);
+        assert_ne!(v, ["A"]);
+        assert_eq!(format!("{v:?}"), r#"["A", "Feast"]"#);
+    }
+
+    #[test]
+    fn ordering() {
+        let a = vector(&[1, 2, 3]);
+        let b = vector(&[1, 3]);
+        let c = vector(&[1, 2]);
+
+        assert!(a < b);
+        assert!(c < a);
+        assert_eq!(a.cmp(&a.clone()), Ordering::Equal);
+        assert_eq!(vector(&[f64::NAN]).partial_cmp(&vector(&[1.0])), None);
+    }
+
+    #[test]
+    fn hash_like_slice() {
+        use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher};
+
+        let state = BuildHasherDefault::<DefaultHasher>::default();
+        let v = vector(&["A", "Dance", "With", "Dragons"]);
+        assert_eq!(state.hash_one(&v), state.hash_one(&v[..]));
+    }
+
+    #[test]
+    fn macro_forms() {
+        let empty: Vector<u8> = crate::vector![];
+        assert!(empty.is_empty());
+
+        let v = crate::vector![1, 2, 3,];
+        assert_eq!(v, [1, 2, 3]);
+
+        let v = crate::vector![String::from("Crows"); 3];
+        assert_eq!(v, ["Crows", "Crows", "Crows"]);
+
+        let v: Vector<String> = crate::vector![String::from("Crows"); 0];
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn with_capacity() {
+        let mut v = Vector::with_capacity(10);
+        assert_eq!(v.capacity(), 10);
+        (0..10).for_each(|n| v.push(n));
+        assert_eq!(v.capacity(), 10);
+
+        v.push(10);
+        assert_eq!(v.capacity(), 20);
+
+        assert_eq!(Vector::<u8>::with_capacity(0).capacity(), 0);
+        assert_eq!(Vector::<()>::with_capacity(10).capacity(), usize::MAX);
+    }
+
+    #[test]
+    fn reserve() {
+        let mut v = vector(&[1, 2, 3]);
+        assert_eq!(v.capacity(), 4);
+
+        // Doubles when that's enough, otherwise takes exactly what's needed
+        v.reserve(1);
+        assert_eq!(v.capacity(), 4);
+        v.reserve(2);
+        assert_eq!(v.capacity(), 8);
+        v.reserve(5);
+        assert_eq!(v.capacity(), 8);
+        v.reserve(20);
+        assert_eq!(v.capacity(), 23);
+
+        v.reserve_exact(21);
+        assert_eq!(v.capacity(), 24);
+        assert_eq!(v, [1, 2, 3]);
+    }
+
+    #[test]
+    fn growth_policies() {
+        /// The capacities `v` goes through while pushing `n` elements.
+        fn capacities<G: GrowthPolicy>(mut v: Vector<u64, Global, G>, n: u64) -> Vec<usize> {
+            let mut seen = Vec::new();
+            for i in 0..n {
+                v.push(i);
+                if seen.last() != Some(&v.capacity()) {
+                    seen.push(v.capacity());
+                }
+            }
+            seen
+        }
+
+        assert_eq!(capacities(Vector::new(), 20), [4, 8, 16, 32]);
+        assert_eq!(
+            capacities(Vector::with_growth(Doubling), 20),
+            [1, 2, 4, 8, 16, 32]
+        );
+        assert_eq!(
+            capacities(Vector::with_growth(OneAndHalf), 20),
+            [1, 2, 3, 4, 6, 9, 13, 19, 28]
+        );
+        assert_eq!(
+            capacities(Vector::with_growth(FixedIncrement(8)), 20),
+            [8, 16, 24]
+        );
+        let custom = Vector::with_growth(|capacity: usize, _| capacity * 3 + 1);
+        assert_eq!(capacities(custom, 20), [1, 4, 13, 40]);
+
+        // Only amortized growth goes through the policy
+        let mut v = Vector::<u8, Global, _>::with_growth(FixedIncrement(100));
+        v.reserve_exact(3);
+        assert_eq!(v.capacity(), 3);
+        v.extend_from_slice(b"A Game of Thrones");
+        assert_eq!(v.capacity(), 103);
+        assert_eq!(v.clone().growth(), &FixedIncrement(100));
+        assert_eq!(v.split_off(10).capacity(), 7);
+    }
+
+    #[test]
+    fn try_reserve_overflow() {
+        let mut v = vector(&[1u32]);
+
+        assert_eq!(
+            v.try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        // Fits in usize, but not in isize::MAX bytes
+        assert_eq!(
+            v.try_reserve_exact(isize::MAX as usize / 4),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(v.try_reserve(10), Ok(()));
+        assert_eq!(v.try_push(2), Ok(()));
+        assert_eq!(v, [1, 2]);
+
+        let mut v = Vector::<()>::new();
+        v.push(());
+        assert_eq!(
+            v.try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn try_reserve_alloc_error() {
+        let mut v = vector(&[1u8]);
+        let result = v.try_reserve_exact(isize::MAX as usize - 4096);
+
+        assert!(matches!(result, Err(TryReserveError::AllocError { .. })));
+        assert_eq!(v, [1]);
+        assert_eq!(v.capacity(), 8);
+    }
+
+    #[test]
+    fn allocator() {
+        let counter = CountingAllocator::new();
+        {
+            let mut v = Vector::new_in(&counter);
+            v.extend((0..100u64).map(|i| i * i));
+            assert_eq!(v.capacity(), 100);
+            assert_eq!(counter.live_bytes(), 800);
+
+            let tail = v.split_off(50);
+            assert_eq!(counter.live_bytes(), 1200);
+
+            v.shrink_to_fit();
+            assert_eq!(v.capacity(), 50);
+            drop(tail);
+            assert_eq!(counter.live_bytes(), 400);
+        }
+
+        assert_eq!(counter.live_bytes(), 0);
+        assert_eq!(counter.peak_bytes(), 1200);
+    }
+
+    #[test]
+    #[should_panic(expected = "Capacity overflow")]
+    fn reserve_overflow_panics() {
+        let mut v = vector(&[1u64]);
+        v.reserve(usize::MAX / 8);
+    }
+
+    #[test]
+    fn shrink() {
+        let mut v = Vector::with_capacity(100);
+        v.extend_from_slice(&[1, 2, 3]);
+
+        v.shrink_to(10);
+        assert_eq!(v.capacity(), 10);
+        v.shrink_to(20);
+        assert_eq!(v.capacity(), 10);
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), 3);
+        assert_eq!(v, [1, 2, 3]);
+
+        v.clear();
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), 0);
+        v.push(4);
+        assert_eq!(v, [4]);
+    }
+}
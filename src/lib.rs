@@ -1,3 +1,3 @@
-mod datastructures;
-mod sorting;
-mod string;
+pub mod datastructures;
+pub mod sorting;
+pub mod string;
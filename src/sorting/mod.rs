@@ -2,4 +2,4 @@ pub mod merge_sort;
 pub mod quicksort;
 
 pub use merge_sort::merge_sort;
-pub use quicksort::quicksort;
+pub use quicksort::{quicksort, quicksort_by};
@@ -1,4 +1,4 @@
-use std::usize;
+use std::{cmp::Ordering, ops::Range};
 
 pub fn quicksort<T: PartialOrd>(slice: &mut [T]) {
     let len = slice.len();
@@ -27,9 +27,94 @@ fn lomuto_partition<T: PartialOrd>(slice: &mut [T]) -> usize {
     i
 }
 
+/// Sorts `slice` with the comparator `compare`, unstably.
+///
+/// The pivot is the median of the first, middle and last element, so already sorted input
+/// stays O(n log n), and elements equal to it are set aside in the same pass, so repeated keys
+/// do too. Recursing only into the smaller part bounds the stack depth to O(log n).
+pub fn quicksort_by<T, F>(slice: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort_by(slice, &mut compare);
+}
+
+fn sort_by<T, F>(mut slice: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    while slice.len() >= 2 {
+        let len = slice.len();
+        let pivot = median_of_three(slice, 0, len / 2, len - 1, compare);
+        slice.swap(pivot, len - 1);
+
+        let equal = three_way_partition_by(slice, compare);
+        let (left, right) = slice.split_at_mut(equal.start);
+        let right = &mut right[equal.len()..];
+        if left.len() < right.len() {
+            sort_by(left, compare);
+            slice = right;
+        } else {
+            sort_by(right, compare);
+            slice = left;
+        }
+    }
+}
+
+fn median_of_three<T, F>(slice: &[T], a: usize, b: usize, c: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let less = |x: usize, y: usize, compare: &mut F| compare(&slice[x], &slice[y]).is_lt();
+    if less(a, b, compare) {
+        if less(b, c, compare) {
+            b
+        } else if less(a, c, compare) {
+            c
+        } else {
+            a
+        }
+    } else if less(a, c, compare) {
+        a
+    } else if less(b, c, compare) {
+        c
+    } else {
+        b
+    }
+}
+
+/// Partitions around the last element into `< pivot | == pivot | > pivot` and returns the
+/// range of the middle part, which is already in place.
+fn three_way_partition_by<T, F>(slice: &mut [T], compare: &mut F) -> Range<usize>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let pivot_idx = slice.len() - 1;
+    // [0, lt) is less, [lt, i) equal and [gt, pivot_idx) greater than the pivot
+    let (mut lt, mut i, mut gt) = (0, 0, pivot_idx);
+
+    while i < gt {
+        match compare(&slice[i], &slice[pivot_idx]) {
+            Ordering::Less => {
+                slice.swap(lt, i);
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Equal => i += 1,
+            Ordering::Greater => {
+                gt -= 1;
+                slice.swap(i, gt);
+            }
+        }
+    }
+
+    slice.swap(gt, pivot_idx);
+    lt..gt + 1
+}
+
 #[cfg(test)]
 mod tests {
-    use super::quicksort as sort;
+    use super::{quicksort as sort, quicksort_by};
 
     #[test]
     fn test_sort_empty() {
@@ -80,4 +165,36 @@ mod tests {
         sort(&mut arr);
         assert_eq!(arr, ["apple", "banana", "blueberry", "cherry", "date"]);
     }
+
+    #[test]
+    fn test_sort_by() {
+        let mut arr = [5, 2, 4, 1, 3];
+        quicksort_by(&mut arr, |a, b| b.cmp(a));
+        assert_eq!(arr, [5, 4, 3, 2, 1]);
+
+        // Sorted and reversed input, which a fixed pivot handles in O(n^2)
+        let mut sorted: Vec<u32> = (0..10_000).collect();
+        quicksort_by(&mut sorted, |a, b| a.cmp(b));
+        assert!(sorted.iter().copied().eq(0..10_000));
+        sorted.reverse();
+        quicksort_by(&mut sorted, |a, b| a.cmp(b));
+        assert!(sorted.iter().copied().eq(0..10_000));
+
+        let mut dups = [3, 1, 3, 3, 2, 1, 3];
+        quicksort_by(&mut dups, |a, b| a.cmp(b));
+        assert_eq!(dups, [1, 1, 2, 3, 3, 3, 3]);
+
+        // Repeated keys, which a two-way partition handles in O(n^2). Counting comparisons
+        // instead of timing keeps the check independent of the machine.
+        for distinct in [1, 3] {
+            let mut keys: Vec<u32> = (0..10_000).map(|i| i * 7 % distinct).collect();
+            let mut comparisons = 0;
+            quicksort_by(&mut keys, |a, b| {
+                comparisons += 1;
+                a.cmp(b)
+            });
+            assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+            assert!(comparisons < 100_000, "{comparisons} comparisons");
+        }
+    }
 }
@@ -1,6 +1,5 @@
 use std::collections::HashMap;
-use std::io::{self, Write};
-use std::iter::repeat;
+use std::iter::repeat_n;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum PangramKind {
@@ -22,8 +21,7 @@ pub fn is_pangram(input_str: &str) -> PangramKind {
     let abc = "abcdefghijklmnopqrstuvwxyz";
     let mut map: HashMap<char, u32> = abc
         .chars()
-        .zip(repeat(0)
-        .take(abc.len()))
+        .zip(repeat_n(0, abc.len()))
         .collect();
 
 
@@ -44,7 +42,7 @@ pub fn is_pangram(input_str: &str) -> PangramKind {
     let mut perfect = true;
 
 
-    for (char, times) in map {
+    for (_, times) in map {
         if times == 0 {
             return PangramKind::Bad;
         }